@@ -0,0 +1,54 @@
+//! Cooperative cancellation and progress reporting for long running solvers.
+
+use std::{sync::{atomic::{AtomicBool, Ordering}, Arc}, thread, time::Duration};
+
+/// A flag shared between a solver and whatever started it, such as a timeout. Solvers check it between units of work
+/// and stop early once it is set. Clones share the same flag.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+	/// Asks every solver holding this token to stop.
+	pub fn cancel(&self) {
+		self.0.store(true, Ordering::Relaxed);
+	}
+
+	/// Whether the solver should stop.
+	pub fn is_cancelled(&self) -> bool {
+		self.0.load(Ordering::Relaxed)
+	}
+
+	/// Cancels the token once the duration has passed, from a background thread.
+	pub fn cancel_after(&self, duration: Duration) {
+		let token = self.clone();
+		thread::spawn(move || {
+			thread::sleep(duration);
+			token.cancel();
+		});
+	}
+}
+
+/// How far through its work a solver is, such as the candidates or equations of a search.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progress<T> {
+	/// The number of items checked so far, including this one.
+	pub completed: usize,
+	pub total: usize,
+	/// The item which was just checked.
+	pub item: T,
+}
+
+impl<T> Progress<T> {
+	/// The fraction of items checked, from 0 to 1.
+	pub fn fraction(&self) -> f64 {
+		if self.total == 0 { 1.0 } else { self.completed as f64 / self.total as f64 }
+	}
+}
+
+/// Draws a progress bar over the current line of stderr, finishing the line once complete.
+pub fn print_progress<T>(progress: Progress<T>) {
+	const WIDTH: usize = 40;
+	let filled = (progress.fraction() * WIDTH as f64) as usize;
+	eprint!("\r[{}{}] {}/{}", "#".repeat(filled), " ".repeat(WIDTH - filled), progress.completed, progress.total);
+	if progress.completed == progress.total { eprintln!(); }
+}
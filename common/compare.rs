@@ -0,0 +1,69 @@
+//! Timing alternate implementations of a solution against each other.
+
+use std::{fmt::Debug, time::{Duration, Instant}};
+
+/// A named way of solving a part, to be compared against the others.
+pub type Implementation<'a, T> = (&'static str, &'a dyn Fn(&str) -> T);
+
+/// The answer an implementation gave, and how long each run of it took.
+pub struct Comparison<T> {
+	pub name: &'static str,
+	pub answer: T,
+	pub samples: Vec<Duration>,
+}
+
+impl<T> Comparison<T> {
+	/// The mean time of a run, in seconds.
+	pub fn mean(&self) -> f64 {
+		self.samples.iter().map(Duration::as_secs_f64).sum::<f64>() / self.samples.len() as f64
+	}
+
+	/// The standard deviation of the run times, in seconds.
+	pub fn stddev(&self) -> f64 {
+		let mean = self.mean();
+		let variance = self.samples.iter().map(|sample| (sample.as_secs_f64() - mean).powi(2)).sum::<f64>();
+		(variance / self.samples.len() as f64).sqrt()
+	}
+}
+
+/// Runs every named implementation on the same input `samples` times (at least once), returning each one's answer from
+/// its last run and how long every run took.
+pub fn compare<T>(input: &str, implementations: &[Implementation<T>], samples: usize) -> Vec<Comparison<T>> {
+	implementations.iter().map(|&(name, implementation)| {
+		let mut times = Vec::new();
+		loop {
+			let start = Instant::now();
+			let answer = implementation(input);
+			times.push(start.elapsed());
+			if times.len() >= samples { return Comparison { name, answer, samples: times } }
+		}
+	}).collect()
+}
+
+/// Whether every implementation gave the same answer.
+pub fn agree<T: PartialEq>(results: &[Comparison<T>]) -> bool {
+	results.windows(2).all(|pair| pair[0].answer == pair[1].answer)
+}
+
+/// Prints a table of the compared implementations' mean times and answers.
+pub fn print_comparison<T: Debug>(results: &[Comparison<T>]) {
+	let width = results.iter().map(|result| result.name.len()).max().unwrap_or(0);
+	for result in results {
+		let time = format!("{:.3?}", Duration::from_secs_f64(result.mean()));
+		let spread = format!("± {:.3?}", Duration::from_secs_f64(result.stddev()));
+		println!("{:<width$}  {time:>12} {spread:<14} {:?}", result.name, result.answer);
+	}
+}
+
+/// Formats the timings as a JSON array for benchmark tracking tools. Each entry has the `name`, `unit`, `value` and
+/// `range` read by github-action-benchmark's custom format, alongside the `mean`, `stddev`, and every sample in seconds.
+pub fn comparison_json<T>(results: &[Comparison<T>]) -> String {
+	let entries = results.iter().map(|result| {
+		let samples = result.samples.iter().map(|sample| sample.as_secs_f64().to_string()).collect::<Vec<_>>().join(",");
+		format!(
+			r#"{{"name":"{}","unit":"s","value":{},"range":"± {}","mean":{},"stddev":{},"samples":[{samples}]}}"#,
+			result.name, result.mean(), result.stddev(), result.mean(), result.stddev(),
+		)
+	}).collect::<Vec<_>>();
+	format!("[{}]", entries.join(","))
+}
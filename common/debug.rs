@@ -0,0 +1,33 @@
+//! Stepping through a simulation interactively on the console.
+
+use std::{cmp, io::{self, Write}};
+
+/// Runs an interactive step debugger over stdin, keeping a snapshot of every state reached so stepping back is free.
+/// `step` advances a state and returns false once no further state can be produced, `view` renders a state.
+pub fn debug<S: Clone>(initial: S, mut step: impl FnMut(&mut S) -> bool, view: impl Fn(&S) -> String) {
+	let mut snapshots = vec![initial];
+	let mut current = 0;
+	let mut finished = false;
+	loop {
+		let end = if finished && current == snapshots.len() - 1 { " (end)" } else { "" };
+		print!("{}\nStep {current}{end} - n: step, b: back, j <step>: jump, q: quit\n> ", view(&snapshots[current]));
+		io::stdout().flush().expect("Failed to flush stdout");
+
+		let mut line = String::new();
+		if io::stdin().read_line(&mut line).unwrap_or(0) == 0 { break }
+		let target = match line.split_whitespace().collect::<Vec<_>>().as_slice() {
+			[] | ["n"] => current + 1,
+			["b"] => current.saturating_sub(1),
+			["j", step] => if let Ok(step) = step.parse() { step } else { println!("Invalid step {step}"); continue },
+			["q"] => break,
+			_ => { println!("Unknown command"); continue },
+		};
+
+		// Simulate any states we haven't reached yet
+		while snapshots.len() <= target && !finished {
+			let mut state = snapshots[snapshots.len() - 1].clone();
+			if step(&mut state) { snapshots.push(state) } else { finished = true }
+		}
+		current = cmp::min(target, snapshots.len() - 1);
+	}
+}
@@ -0,0 +1,9 @@
+//! Code shared between the days' crates, each depends on this crate by path.
+
+pub mod cancel;
+pub mod compare;
+pub mod debug;
+pub mod pathfinding;
+pub mod random;
+pub mod report;
+pub mod validate;
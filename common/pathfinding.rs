@@ -0,0 +1,73 @@
+//! Shortest path searches over graphs given as a neighbor function, so any state (a position, a position and a
+//! facing direction, ...) can be a node, and over grids of open and blocked cells.
+
+use std::{cmp::Reverse, collections::{BinaryHeap, HashMap, VecDeque}, hash::Hash};
+
+/// A single position in a grid, 0,0 is at the top-left
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Position { pub x: usize, pub y: usize }
+
+/// Finds the cheapest cost to reach every node reachable from any of the starting nodes, where `neighbors` gives the
+/// nodes one edge away from a node along with the cost of that edge.
+pub fn dijkstra<N, I>(starts: impl IntoIterator<Item = N>, mut neighbors: impl FnMut(N) -> I) -> HashMap<N, u64>
+where
+	N: Copy + Eq + Hash + Ord,
+	I: IntoIterator<Item = (N, u64)>,
+{
+	let mut costs = HashMap::new();
+	let mut queue = starts.into_iter().map(|node| Reverse((0, node))).collect::<BinaryHeap<_>>();
+	while let Some(Reverse((cost, node))) = queue.pop() {
+		if costs.contains_key(&node) { continue }
+		costs.insert(node, cost);
+		for (next, edge) in neighbors(node) {
+			if !costs.contains_key(&next) { queue.push(Reverse((cost + edge, next))); }
+		}
+	}
+	costs
+}
+
+/// Finds the cheapest cost from any starting node to the first node reached which satisfies `is_goal`, or None if no
+/// such node can be reached.
+pub fn shortest_cost<N, I>(
+	starts: impl IntoIterator<Item = N>,
+	mut neighbors: impl FnMut(N) -> I,
+	is_goal: impl Fn(N) -> bool,
+) -> Option<u64>
+where
+	N: Copy + Eq + Hash + Ord,
+	I: IntoIterator<Item = (N, u64)>,
+{
+	let mut costs = HashMap::new();
+	let mut queue = starts.into_iter().map(|node| Reverse((0, node))).collect::<BinaryHeap<_>>();
+	while let Some(Reverse((cost, node))) = queue.pop() {
+		if costs.contains_key(&node) { continue }
+		if is_goal(node) { return Some(cost) }
+		costs.insert(node, cost);
+		for (next, edge) in neighbors(node) {
+			if !costs.contains_key(&next) { queue.push(Reverse((cost + edge, next))); }
+		}
+	}
+	None
+}
+
+/// The fewest steps from a starting position to every cell of a `width` by `height` grid, moving up, down, left, and
+/// right through cells where `open` is true, indexed by row then column. Unreachable cells are None.
+pub fn distance_field(width: usize, height: usize, start: Position, open: impl Fn(Position) -> bool) -> Vec<Vec<Option<usize>>> {
+	let mut distances = vec![vec![None; width]; height];
+	if start.x >= width || start.y >= height || !open(start) { return distances }
+	distances[start.y][start.x] = Some(0);
+	let mut queue = VecDeque::from([start]);
+	while let Some(pos) = queue.pop_front() {
+		let distance = distances[pos.y][pos.x].expect("Queued cells have a distance");
+		let neighbors = [(-1, 0), (0, -1), (1, 0), (0, 1)].into_iter().filter_map(|(d_x, d_y)| Some(Position {
+			x: pos.x.checked_add_signed(d_x).filter(|&x| x < width)?,
+			y: pos.y.checked_add_signed(d_y).filter(|&y| y < height)?,
+		}));
+		for next in neighbors {
+			if distances[next.y][next.x].is_some() || !open(next) { continue }
+			distances[next.y][next.x] = Some(distance + 1);
+			queue.push_back(next);
+		}
+	}
+	distances
+}
@@ -0,0 +1,25 @@
+//! Seeded pseudo-random numbers for generated inputs, the same seed always gives the same numbers.
+
+/// A small xorshift generator. Its numbers only need to look arbitrary, such as for practice inputs and shuffles.
+#[derive(Debug, Clone)]
+pub struct Xorshift(u64);
+
+impl Xorshift {
+	/// Creates a generator from a seed, any seed (including 0) gives a usable generator.
+	pub fn new(seed: u64) -> Self {
+		Self((seed ^ 0x9E37_79B9_7F4A_7C15).max(1))
+	}
+
+	/// The next number.
+	pub fn next_u64(&mut self) -> u64 {
+		self.0 ^= self.0 << 13;
+		self.0 ^= self.0 >> 7;
+		self.0 ^= self.0 << 17;
+		self.0
+	}
+
+	/// The next number below `bound`.
+	pub fn below(&mut self, bound: u64) -> u64 {
+		self.next_u64() % bound
+	}
+}
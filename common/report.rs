@@ -0,0 +1,24 @@
+//! Readable error reports for the console.
+
+use std::{error::Error, fmt::Debug};
+
+/// Describes a solution for the console, its value when solved, or its error followed by every error which caused it,
+/// e.g. "error while solving day 5 part 1: Line 3: invalid rule '47-53', expected a|b".
+pub fn report<T: Debug, E: Into<Box<dyn Error>>>(result: Result<T, E>, doing: &str) -> String {
+	let error = match result {
+		Ok(value) => return format!("{value:?}"),
+		Err(error) => error.into(),
+	};
+	format!("error while {doing}: {}", error_chain(error.as_ref()))
+}
+
+/// Describes an error followed by every error which caused it, separated by colons.
+pub fn error_chain(error: &dyn Error) -> String {
+	let mut message = error.to_string();
+	let mut source = error.source();
+	while let Some(cause) = source {
+		message += &format!(": {cause}");
+		source = cause.source();
+	}
+	message
+}
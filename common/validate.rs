@@ -0,0 +1,12 @@
+//! Checking input files for problems from the console.
+
+use std::fmt::Display;
+
+/// Checks a file with a day's `validate` function, printing every problem found and exiting with an error code if there
+/// were any.
+pub fn validate_file<P: Display>(path: &str, validate: impl FnOnce(&str) -> Vec<P>) {
+	let problems = validate(&std::fs::read_to_string(path).expect("Failed to read input file"));
+	for problem in &problems { println!("{problem}"); }
+	println!("{} problems found", problems.len());
+	if !problems.is_empty() { std::process::exit(1) }
+}
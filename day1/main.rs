@@ -0,0 +1,101 @@
+use std::{collections::HashMap, error::Error, fmt::{self, Display, Formatter}, num::ParseIntError};
+
+use common::report::report;
+
+/// Possible errors when parsing the two location lists, lines are 1-indexed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ListParseError {
+	/// A line didn't have exactly two location IDs.
+	InvalidPair { line: usize, text: String },
+	/// A location ID wasn't an integer.
+	IntegerParseError { line: usize, text: String, error: ParseIntError },
+}
+
+impl Display for ListParseError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		match self {
+			ListParseError::InvalidPair { line, text } => write!(f, "Line {line}: expected two location IDs, found '{text}'"),
+			ListParseError::IntegerParseError { line, text, error } => {
+				write!(f, "Line {line}: invalid location ID '{text}', {error}")
+			},
+		}
+	}
+}
+
+impl Error for ListParseError {}
+
+/// Parses the left and right lists of location IDs, one pair per line separated by whitespace.
+pub fn parse_lists(input: &str) -> Result<(Vec<u64>, Vec<u64>), ListParseError> {
+	input.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()).map(|(idx, line)| {
+		let parse = |text: &str| text.parse::<u64>()
+			.map_err(|error| ListParseError::IntegerParseError { line: idx + 1, text: text.into(), error });
+		let [left, right] = *line.split_whitespace().collect::<Vec<_>>().as_slice() else {
+			return Err(ListParseError::InvalidPair { line: idx + 1, text: line.into() })
+		};
+		Ok((parse(left)?, parse(right)?))
+	}).collect()
+}
+
+/// Part 1 solution - sum of the distances between the smallest left and right IDs, the second smallest, and so on.
+fn part1_solution(input: &str) -> Result<u64, ListParseError> {
+	let (mut left, mut right) = parse_lists(input)?;
+	left.sort_unstable();
+	right.sort_unstable();
+	Ok(left.iter().zip(&right).map(|(left, right)| left.abs_diff(*right)).sum())
+}
+
+/// Part 2 solution - sum of every left ID multiplied by how many times it appears in the right list.
+fn part2_solution(input: &str) -> Result<u64, ListParseError> {
+	let (left, right) = parse_lists(input)?;
+	let mut occurrences = HashMap::new();
+	for id in right { *occurrences.entry(id).or_insert(0) += 1; }
+	Ok(left.iter().map(|id| id * occurrences.get(id).unwrap_or(&0)).sum())
+}
+
+/// Entry point
+fn main() {
+	let example = "3   4
+4   3
+2   5
+1   3
+3   9
+3   3";
+
+	println!("Part 1 Solution on Example: {}", report(part1_solution(example), "solving day 1 part 1"));
+	println!("Part 2 Solution on Example: {}", report(part2_solution(example), "solving day 1 part 2"));
+
+	// The original input wasn't saved, pass it with `--file <path>`
+	let args = std::env::args().collect::<Vec<_>>();
+	if let Some(path) = args.iter().skip_while(|&arg| arg != "--file").nth(1) {
+		let input = std::fs::read_to_string(path).expect("Failed to read input file");
+		println!("Part 1 Solution on Input: {}", report(part1_solution(&input), "solving day 1 part 1"));
+		println!("Part 2 Solution on Input: {}", report(part2_solution(&input), "solving day 1 part 2"));
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const EXAMPLE: &str = "3   4
+4   3
+2   5
+1   3
+3   9
+3   3";
+
+	/// Tests both parts against the puzzle text
+	#[test]
+	fn test_example() {
+		assert_eq!(part1_solution(EXAMPLE), Ok(11));
+		assert_eq!(part2_solution(EXAMPLE), Ok(31));
+	}
+
+	/// Tests parse errors name the line and offending text
+	#[test]
+	fn test_parse_errors() {
+		assert_eq!(parse_lists("3   4\n4").unwrap_err().to_string(), "Line 2: expected two location IDs, found '4'");
+		assert_eq!(parse_lists("3   x").unwrap_err().to_string(), "Line 1: invalid location ID 'x', invalid digit found in string");
+	}
+
+}
@@ -1,34 +1,180 @@
-use std::{collections::HashMap, fmt::Display};
+use std::{cmp::Reverse, collections::HashMap, error::Error, fmt::{self, Display, Formatter}, ops::RangeInclusive};
 
+use common::{report::report, validate::validate_file};
 use itertools::Itertools;
-use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use rayon::iter::{IntoParallelIterator, IntoParallelRefIterator, ParallelIterator};
 
 /// Represents a position on the map, indexed by `map[x][y]`
 #[derive(Debug, Clone, Copy, Eq, PartialEq, PartialOrd, Ord, Hash)]
-struct Position { x: usize, y: usize }
+pub struct Position { pub x: usize, pub y: usize }
 
 impl Position {
     /// Creates a new position
-    fn new(x: usize, y: usize) -> Self {
+    pub fn new(x: usize, y: usize) -> Self {
         Self { x, y }
     }
 }
 
+/// A path across the map, the positions walked in order. Hiking trails climb from the map's start height up to its end
+/// height, 0 to 9 unless configured otherwise.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Trail {
+    positions: Vec<Position>,
+}
+
+impl Trail {
+    /// The positions of the trail in order, starting at the trailhead.
+    pub fn positions(&self) -> &[Position] {
+        &self.positions
+    }
+
+    /// The trailhead the trail starts from.
+    pub fn start(&self) -> Position {
+        self.positions[0]
+    }
+
+    /// The position the trail ends at.
+    pub fn end(&self) -> Position {
+        self.positions[self.positions.len() - 1]
+    }
+}
+
+/// Which neighbouring positions a trail may continue to.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Connectivity {
+    /// Up, down, left, and right, as in the puzzle.
+    #[default]
+    Four,
+    /// Diagonals as well as up, down, left, and right.
+    Eight,
+}
+
+/// How many trails climb from each position up to a map's end height. Trails from different trailheads share their
+/// sub-paths, so counting once for the whole map means each sub-path is only counted once.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrailCounts {
+    /// The number of trails from each position, indexed by `counts[x][y]`.
+    counts: Vec<Vec<usize>>,
+}
+
+impl TrailCounts {
+    /// The number of trails from a position to the end height, its rating when it's a trailhead.
+    pub fn at(&self, pos: Position) -> usize {
+        self.counts[pos.x][pos.y]
+    }
+}
+
 /// Height map
-struct Map {
+pub struct Map {
     topology: Vec<Vec<u8>>,
+    /// The heights trails start and end at, climbing by 1 each step.
+    heights: RangeInclusive<u8>,
 }
 
 impl Map {
-    /// Gets all trailheads in the map from their origin.
-    fn get_trailheads(&self) -> HashMap<Position, Vec<[Position; 10]>> {
-        let positions = self.topology.iter().enumerate()
+    /// Creates a map from heights indexed by `heights[x][y]`, with trails from 0 to 9.
+    pub fn from_heights(topology: Vec<Vec<u8>>) -> Self {
+        Self { topology, heights: 0..=9 }
+    }
+
+    /// Sets the heights trails start and end at, e.g. `1..=20` for a generated map with deeper elevations.
+    pub fn with_trail_heights(mut self, heights: RangeInclusive<u8>) -> Self {
+        self.heights = heights;
+        self
+    }
+
+    /// Every position on the map, in order.
+    fn positions(&self) -> Vec<Position> {
+        self.topology.iter().enumerate()
             .flat_map(|(x, line)| (0..line.len()).map(move |y| Position::new(x, y)))
-            .collect::<Vec<_>>();
-        positions.into_par_iter().filter(|&item| self.at(item) == 0).filter_map(|origin| {
-            let trails = self.get_trailheads_from_origin(origin);
-            (!trails.is_empty()).then_some((origin, trails))
-        }).collect()
+            .collect()
+    }
+
+    /// Gets every trail in the map, grouped by trailhead in order of position.
+    pub fn all_trails(&self, connectivity: Connectivity) -> Vec<Trail> {
+        self.positions().into_par_iter()
+            .flat_map_iter(|origin| self.trails_from(origin, connectivity))
+            .collect()
+    }
+
+    /// Counts the trails from every position, working down from the end height so each position only adds up the
+    /// counts of the positions a step above it. Every position at a height is counted in parallel, reading only the
+    /// finished counts of the height above.
+    pub fn trail_counts(&self, connectivity: Connectivity) -> TrailCounts {
+        let (start, end) = (*self.heights.start(), *self.heights.end());
+        let mut counts = self.topology.iter().map(|line| vec![0; line.len()]).collect::<Vec<_>>();
+        let by_height = self.positions().into_iter().into_group_map_by(|&pos| self.at(pos));
+        for height in (start..=end).rev() {
+            let Some(positions) = by_height.get(&height) else { continue };
+            let layer = positions.par_iter().map(|&pos| {
+                if height == end { return (pos, 1) }
+                let count = self.neighbors(pos, connectivity).into_iter().flatten()
+                    .filter(|&neighbor| self.at(neighbor) == height + 1)
+                    .map(|neighbor| counts[neighbor.x][neighbor.y])
+                    .sum();
+                (pos, count)
+            }).collect::<Vec<_>>();
+            for (pos, count) in layer { counts[pos.x][pos.y] = count; }
+        }
+        TrailCounts { counts }
+    }
+
+    /// The sum of every trailhead's rating, the number of trails in the map, without walking each trail.
+    pub fn total_rating(&self, connectivity: Connectivity) -> usize {
+        let counts = self.trail_counts(connectivity);
+        self.positions().into_par_iter()
+            .filter(|&pos| self.at(pos) == *self.heights.start())
+            .map(|pos| counts.at(pos))
+            .sum()
+    }
+
+    /// Gets the longest path which climbs at every step, by any amount, from anywhere on the map. Returns None for an
+    /// empty map.
+    pub fn longest_increasing_path(&self, connectivity: Connectivity) -> Option<Trail> {
+        // Visiting the highest positions first means every higher neighbour's longest path is known
+        let positions = self.positions().into_iter().sorted_by_key(|&pos| Reverse(self.at(pos))).collect::<Vec<_>>();
+        let mut longest: HashMap<Position, (usize, Option<Position>)> = HashMap::new();
+        for &pos in &positions {
+            let next = self.neighbors(pos, connectivity).into_iter().flatten()
+                .filter(|&neighbor| self.at(neighbor) > self.at(pos))
+                .max_by_key(|neighbor| longest[neighbor].0);
+            longest.insert(pos, (next.map_or(1, |next| longest[&next].0 + 1), next));
+        }
+
+        let mut pos = positions.into_iter().max_by_key(|pos| longest[pos].0)?;
+        let mut path = vec![pos];
+        while let Some(next) = longest[&pos].1 {
+            path.push(next);
+            pos = next;
+        }
+        Some(Trail { positions: path })
+    }
+
+    /// Gets every maximal descent from an origin, stepping down by 1 each step until there is no lower neighbour.
+    pub fn descents_from(&self, origin: Position, connectivity: Connectivity) -> Vec<Trail> {
+        let mut descents = Vec::new();
+        let mut paths = vec![vec![origin]];
+        while let Some(path) = paths.pop() {
+            let pos = *path.last().unwrap();
+            let lower = self.neighbors(pos, connectivity).into_iter().flatten()
+                .filter(|&neighbor| self.at(neighbor) + 1 == self.at(pos))
+                .collect::<Vec<_>>();
+            if lower.is_empty() { descents.push(Trail { positions: path }); continue }
+            paths.extend(lower.into_iter().map(|neighbor| {
+                let mut path = path.clone();
+                path.push(neighbor);
+                path
+            }));
+        }
+        descents
+    }
+
+    /// Gets every maximal descent from every position at the map's end height, 9 unless configured otherwise.
+    pub fn all_descents(&self, connectivity: Connectivity) -> Vec<Trail> {
+        self.positions().into_par_iter()
+            .filter(|&origin| self.at(origin) == *self.heights.end())
+            .flat_map_iter(|origin| self.descents_from(origin, connectivity))
+            .collect()
     }
 
     /// Height at position
@@ -44,18 +190,21 @@ impl Map {
     }
 
     /// Gets neighboring positions to a position
-    fn neighbors(&self, pos: Position) -> [Option<Position>; 4] {
+    fn neighbors(&self, pos: Position, connectivity: Connectivity) -> Vec<Option<Position>> {
         let (x, y) = (pos.x as i32, pos.y as i32);
-        let neighbors = [(1, 0), (0, 1), (-1, 0), (0, -1)];
-        neighbors.map(|(d_x, d_y)| {
+        let neighbors: &[(i32, i32)] = match connectivity {
+            Connectivity::Four => &[(1, 0), (0, 1), (-1, 0), (0, -1)],
+            Connectivity::Eight => &[(1, 0), (1, 1), (0, 1), (-1, 1), (-1, 0), (-1, -1), (0, -1), (1, -1)],
+        };
+        neighbors.iter().map(|&(d_x, d_y)| {
             self.contains(x + d_x, y + d_y)
                 .then(|| Position::new((x + d_x) as usize, (y + d_y) as usize)) // Use .then to avoid negative unsigned casting
-        })
+        }).collect()
     }
 
     /// Continues an existing trail by adding its valid neighbors
-    fn continue_trail(&self, digit: u8, trail: Vec<Position>) -> Vec<Vec<Position>> {
-        self.neighbors(*trail.last().unwrap()).iter()
+    fn continue_trail(&self, digit: u8, trail: Vec<Position>, connectivity: Connectivity) -> Vec<Vec<Position>> {
+        self.neighbors(*trail.last().unwrap(), connectivity).iter()
             .filter_map(|&pos| (self.at(pos?) == digit + 1).then_some({
                 let mut trail = trail.clone();
                 trail.push(pos?);
@@ -64,24 +213,22 @@ impl Map {
             .collect()
     }
 
-    /// Gets all trails from a single origin
-    fn get_trailheads_from_origin(&self, origin: Position) -> Vec<[Position; 10]> {
-        let trails: Vec<Vec<Position>> = (1..9u8).fold(self.continue_trail(0, vec![origin]), |trails, digit| {
+    /// Gets all trails from a single origin, empty if the origin isn't a trailhead.
+    pub fn trails_from(&self, origin: Position, connectivity: Connectivity) -> Vec<Trail> {
+        let (start, end) = (*self.heights.start(), *self.heights.end());
+        if self.at(origin) != start { return Vec::new() }
+        let trails: Vec<Vec<Position>> = (start..end).fold(vec![vec![origin]], |trails, digit| {
             trails.into_iter()
-                .flat_map(|trail| self.continue_trail(digit, trail))
+                .flat_map(|trail| self.continue_trail(digit, trail, connectivity))
                 .collect()
         });
-        trails.iter().map(|trail| {
-            let [_, t1, t2, t3, t4, t5, t6, t7, t8, t9] = trail.as_slice() else { panic!("Invalid number of trails returned") };
-            [origin, *t1, *t2, *t3, *t4, *t5, *t6, *t7, *t8, *t9]
-        }).collect()
+        trails.into_iter().map(|positions| Trail { positions }).collect()
     }
 
     /// Marks a trail on the map and returns it
-    #[allow(dead_code)]
-    fn mark_trail(&self, trail: &[Position; 10]) -> String {
+    pub fn mark_trail(&self, trail: &Trail) -> String {
         let lines = self.topology.iter().enumerate().map(|(m_x, line)| line.iter().enumerate().map(|(m_y, digit)| {
-            if trail.iter().any(|pos| pos.x == m_x && pos.y == m_y) { String::from("+") }
+            if trail.positions.iter().any(|pos| pos.x == m_x && pos.y == m_y) { String::from("+") }
             else { digit.to_string() }
         }).collect::<String>()).collect::<Vec<_>>();
         lines.join("\n")
@@ -98,7 +245,7 @@ impl Display for Map {
 
 /// Reports the location a map failed to parse.
 #[derive(Debug)]
-struct MapParseError { line: usize, col: usize }
+pub struct MapParseError { line: usize, col: usize }
 
 impl Display for MapParseError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -106,6 +253,8 @@ impl Display for MapParseError {
     }
 }
 
+impl Error for MapParseError {}
+
 /// Parses a height map from a grid string
 impl TryFrom<&str> for Map {
     type Error = MapParseError;
@@ -118,20 +267,61 @@ impl TryFrom<&str> for Map {
                 .collect::<Result<Vec<_>, _>>()
                 .map_err(|col_num| MapParseError { line: line_num, col: col_num })
         }).collect::<Result<Vec<_>, _>>()?;
-        Ok(Map { topology })
+        Ok(Map::from_heights(topology))
+    }
+}
+
+/// A structural problem with a map, found by `validate`. Lines and columns count from 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputProblem {
+    /// The input has no lines.
+    Empty,
+    /// A line isn't as long as the first, so the map isn't rectangular.
+    RaggedLine { line: usize, length: usize, expected: usize },
+    /// A character which isn't a height from 0 to 9.
+    UnknownCharacter { line: usize, column: usize, character: char },
+}
+
+impl Display for InputProblem {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            InputProblem::Empty => write!(f, "The map is empty"),
+            InputProblem::RaggedLine { line, length, expected } => {
+                write!(f, "Line {line} is {length} long, expected {expected} like the first line")
+            },
+            InputProblem::UnknownCharacter { line, column, character } => {
+                write!(f, "Unknown character {character:?} at line {line}, column {column}")
+            },
+        }
     }
 }
 
+/// Checks a map is a rectangular grid of known characters without solving it, reporting every problem rather than
+/// stopping at the first.
+pub fn validate(input: &str) -> Vec<InputProblem> {
+    let lines = input.lines().collect::<Vec<_>>();
+    let Some(first) = lines.first() else { return vec![InputProblem::Empty] };
+    let expected = first.chars().count();
+    let mut problems = Vec::new();
+    for (idx, text) in lines.iter().enumerate() {
+        let length = text.chars().count();
+        if length != expected { problems.push(InputProblem::RaggedLine { line: idx + 1, length, expected }); }
+        for (column, character) in text.chars().enumerate() {
+            if character.is_ascii_digit() { continue }
+            problems.push(InputProblem::UnknownCharacter { line: idx + 1, column: column + 1, character });
+        }
+    }
+    problems
+}
+
 /// The sum of scores of trail ends
-fn part1_solution(input: &str) -> Result<usize, MapParseError> {
-    Ok(Map::try_from(input)?.get_trailheads().values()
-        .map(|trails| trails.iter().unique_by(|trail| trail[9]).count()).sum())
+fn part1_solution(input: &str, connectivity: Connectivity) -> Result<usize, MapParseError> {
+    Ok(Map::try_from(input)?.all_trails(connectivity).iter().unique_by(|trail| (trail.start(), trail.end())).count())
 }
 
 /// The sum of scores of trail heads
-fn part2_solution(input: &str) -> Result<usize, MapParseError> {
-    Ok(Map::try_from(input)?.get_trailheads().values()
-        .map(|trails| trails.len()).sum())
+fn part2_solution(input: &str, connectivity: Connectivity) -> Result<usize, MapParseError> {
+    Ok(Map::try_from(input)?.total_rating(connectivity))
 }
 
 /// Entry point
@@ -144,11 +334,134 @@ pub fn main() {
 32019012
 01329801
 10456732";
-    let input = include_str!("day10.txt");
+    // Solve another input instead of the bundled one with `--file <path>`
+    let args = std::env::args().collect::<Vec<_>>();
+    let file = args.iter().skip_while(|&arg| arg != "--file").nth(1)
+        .map(|path| std::fs::read_to_string(path).expect("Failed to read input file"));
+    let input = file.as_deref().unwrap_or(include_str!("day10.txt"));
+
+    // Check a file for problems without solving it with `--validate <path>`
+    if let Some(path) = args.iter().skip_while(|&arg| arg != "--validate").nth(1) {
+        validate_file(path, validate);
+        return;
+    }
+
+    // Trails may also move diagonally with `--diagonal`
+    let connectivity = if args.iter().any(|arg| arg == "--diagonal") { Connectivity::Eight } else { Connectivity::Four };
+
+    // Print the longest climbing path and how many ways there are down from the peaks
+    if args.iter().any(|arg| arg == "--gradients") {
+        let map = Map::try_from(if args.iter().any(|arg| arg == "--example") { example } else { input }).expect("Failed to parse map");
+        if let Some(path) = map.longest_increasing_path(connectivity) {
+            println!("Longest increasing path: {} positions\n{}", path.positions().len(), map.mark_trail(&path));
+        }
+        let descents = map.all_descents(connectivity);
+        println!("Maximal descents from peaks: {}", descents.len());
+        println!("Descents reaching the ground: {}", descents.iter().filter(|descent| map.at(descent.end()) == 0).count());
+        return;
+    }
+
+    println!("Part 1 Solution on Example: {}", report(part1_solution(example, connectivity), "solving day 10 part 1"));
+	println!("Part 1 Solution on Input: {}", report(part1_solution(input, connectivity), "solving day 10 part 1"));
+
+    println!("Part 2 Solution on Example: {}", report(part2_solution(example, connectivity), "solving day 10 part 2"));
+	println!("Part 2 Solution on Input: {}", report(part2_solution(input, connectivity), "solving day 10 part 2"));
+}
 
-    println!("Part 1 Solution on Example: {:#?}", part1_solution(example));
-	println!("Part 1 Solution on Input: {:#?}", part1_solution(input));
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    /// Tests trails are walked from their trailhead up to a height of 9
+    #[test]
+    fn test_trails_from() {
+        let map = Map::try_from("0123\n1234\n8765\n9876").unwrap();
+        let trails = map.trails_from(Position::new(0, 0), Connectivity::Four);
+        assert_eq!(trails.len(), 16);
+        assert!(trails.iter().all(|trail| trail.start() == Position::new(0, 0) && trail.end() == Position::new(3, 0)));
+        assert!(trails.iter().all(|trail| trail.positions().iter().enumerate().all(|(height, &pos)| map.at(pos) as usize == height)));
+        assert!(map.trails_from(Position::new(0, 1), Connectivity::Four).is_empty());
+        assert_eq!(map.all_trails(Connectivity::Four), trails);
+    }
+
+    /// Tests trails with heights beyond a single digit
+    #[test]
+    fn test_trail_heights() {
+        let map = Map::from_heights(vec![vec![10, 11, 12], vec![13, 12, 13], vec![14, 15, 14]]).with_trail_heights(10..=15);
+        let trails = map.all_trails(Connectivity::Four);
+        assert_eq!(trails.len(), 3);
+        assert!(trails.iter().all(|trail| trail.positions().len() == 6 && trail.end() == Position::new(2, 1)));
+
+        let map = Map::try_from("0123").unwrap().with_trail_heights(1..=1);
+        assert_eq!(map.all_trails(Connectivity::Four).iter().map(Trail::positions).collect::<Vec<_>>(), [[Position::new(0, 1)]]);
+    }
+
+    /// Tests diagonal steps are only taken with eight way connectivity
+    #[test]
+    fn test_connectivity() {
+        let map = Map::try_from("05\n51").unwrap().with_trail_heights(0..=1);
+        assert!(map.all_trails(Connectivity::Four).is_empty());
+        let trails = map.all_trails(Connectivity::Eight);
+        assert_eq!(trails.iter().map(Trail::positions).collect::<Vec<_>>(), [[Position::new(0, 0), Position::new(1, 1)]]);
+    }
+
+    /// Tests the longest climbing path and descents on a small map
+    #[test]
+    fn test_gradients() {
+        let map = Map::try_from("0123\n7654\n8991").unwrap();
+        let longest = map.longest_increasing_path(Connectivity::Four).unwrap();
+        let heights = longest.positions().iter().map(|&pos| map.at(pos)).collect::<Vec<_>>();
+        assert_eq!(heights, [0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+
+        // Every descent from a 9 ends where there is no lower neighbour, only one reaches the ground
+        let descents = map.all_descents(Connectivity::Four);
+        assert_eq!(descents.len(), 2);
+        let ends = descents.iter().map(|descent| map.at(descent.end())).sorted().collect::<Vec<_>>();
+        assert_eq!(ends, [0, 9]);
+    }
+
+    /// Tests trails and descents come back grouped by position in order however the positions are split between threads
+    #[test]
+    fn test_parallel_order() {
+        let map = Map::try_from("89010123\n78121874\n87430965\n96549874\n45678903\n32019012\n01329801\n10456732").unwrap();
+        let trails = map.all_trails(Connectivity::Four);
+        let descents = map.all_descents(Connectivity::Four);
+        assert!(trails.is_sorted_by_key(|trail| trail.start()));
+        assert!(descents.is_sorted_by_key(|trail| trail.start()));
+        for _ in 0..10 {
+            assert_eq!(map.all_trails(Connectivity::Four), trails);
+            assert_eq!(map.all_descents(Connectivity::Four), descents);
+        }
+    }
+
+    /// Tests validation reports every problem with a map at once
+    #[test]
+    fn test_validate() {
+        assert!(validate("0123\n1234").is_empty());
+        assert_eq!(validate(""), [InputProblem::Empty]);
+        assert_eq!(validate("0123\n1.3\n12345"), [
+            InputProblem::RaggedLine { line: 2, length: 3, expected: 4 },
+            InputProblem::UnknownCharacter { line: 2, column: 2, character: '.' },
+            InputProblem::RaggedLine { line: 3, length: 5, expected: 4 },
+        ]);
+    }
+
+    /// Tests the shared trail counts match walking every trail from each trailhead
+    #[test]
+    fn test_trail_counts() {
+        let map = Map::try_from("89010123\n78121874\n87430965\n96549874\n45678903\n32019012\n01329801\n10456732").unwrap();
+        for connectivity in [Connectivity::Four, Connectivity::Eight] {
+            let counts = map.trail_counts(connectivity);
+            for pos in map.positions().into_iter().filter(|&pos| map.at(pos) == 0) {
+                assert_eq!(counts.at(pos), map.trails_from(pos, connectivity).len());
+            }
+            assert_eq!(map.total_rating(connectivity), map.all_trails(connectivity).len());
+        }
+        assert_eq!(map.total_rating(Connectivity::Four), 81);
+
+        let map = Map::from_heights(vec![vec![10, 11, 12], vec![13, 12, 13], vec![14, 15, 14]]).with_trail_heights(10..=15);
+        assert_eq!(map.total_rating(Connectivity::Four), 3);
+    }
 
-    println!("Part 2 Solution on Example: {:#?}", part2_solution(example));
-	println!("Part 2 Solution on Input: {:#?}", part2_solution(input));
 }
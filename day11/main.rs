@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, fmt::{self, Display, Formatter}};
 
 /// Describes a single stone
 struct Stone {
@@ -13,13 +13,91 @@ impl Stone {
 	}
 }
 
+/// The rules a stone changes by each blink.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlinkRules {
+	/// What a stone engraved with 0 becomes.
+	pub zero_becomes: u64,
+	/// The base digits are counted in, at least 2. A stone with an even number of digits is split into its halves.
+	pub base: u64,
+	/// What any other stone's engraving is multiplied by.
+	pub multiplier: u64,
+}
+
+impl Default for BlinkRules {
+	/// The rules from the puzzle.
+	fn default() -> Self {
+		Self { zero_becomes: 1, base: 10, multiplier: 2024 }
+	}
+}
+
+impl BlinkRules {
+	/// Gets the stone, or the two stones, an engraving becomes after a blink. Digits can't be counted in a base below 2,
+	/// so stones are never split, and engravings too large for a `u64` saturate at `u64::MAX`.
+	pub fn blink(&self, engraving: u64) -> (u64, Option<u64>) {
+		if engraving == 0 { return (self.zero_becomes, None) }
+		match engraving.checked_ilog(self.base).map(|log| log + 1) {
+			Some(digits) if digits.is_multiple_of(2) => {
+				let half = self.base.pow(digits / 2);
+				(engraving / half, Some(engraving % half))
+			},
+			_ => (engraving.saturating_mul(self.multiplier), None),
+		}
+	}
+
+	/// Gets how many stones have each engraving after a number of blinks, starting from the given stones.
+	pub fn distribution_after_blinks(&self, input: &[usize], blinks: usize) -> HashMap<u64, u64> {
+		(0..blinks).fold(distribution(input), |stones, _| self.blink_all(&stones))
+	}
+
+	/// Iterates over the total number of stones after each successive blink.
+	pub fn counts(&self, input: &[usize]) -> BlinkCounts {
+		BlinkCounts { rules: *self, stones: distribution(input) }
+	}
+
+	/// Gets the number of blinks after which there are more than `threshold` stones. Returns None if that never happens,
+	/// either because the threshold is `u64::MAX` or the stones repeat without growing.
+	pub fn blinks_until(&self, input: &[usize], threshold: u64) -> Option<usize> {
+		let mut stones = distribution(input);
+		// The total never shrinks, so while it stays the same a repeated distribution means it never grows again
+		let mut seen = Vec::new();
+		for blinks in 0.. {
+			if total(&stones) > threshold { return Some(blinks) }
+			let next = self.blink_all(&stones);
+			if threshold == u64::MAX { return None }
+			if total(&next) > total(&stones) { seen.clear() } else { seen.push(stones) }
+			if seen.contains(&next) { return None }
+			stones = next;
+		}
+		unreachable!()
+	}
+
+	/// Blinks once at a distribution of stones, mapping each engraving to how many stones have it.
+	fn blink_all(&self, stones: &HashMap<u64, u64>) -> HashMap<u64, u64> {
+		let mut next = HashMap::with_capacity(stones.len());
+		for (&engraving, &count) in stones {
+			let (first, second) = self.blink(engraving);
+			// Counts saturate rather than overflow, totals past u64::MAX only matter as being huge
+			let entry = next.entry(first).or_default();
+			*entry = count.saturating_add(*entry);
+			if let Some(second) = second {
+				let entry = next.entry(second).or_default();
+				*entry = count.saturating_add(*entry);
+			}
+		}
+		next
+	}
+}
+
 /// Solver for day 11
 #[derive(Default)]
 struct Day11 {
 	/// Static digit map used for quick cached access, contains subdivision modification of all single-digits
 	digit_map: HashMap<usize, Stone>,
 	/// Cached map of count_after_blinks results.
-	cached_counts: HashMap<(usize, usize), usize>
+	cached_counts: HashMap<(usize, usize), usize>,
+	/// The rules stones change by.
+	rules: BlinkRules,
 }
 
 impl Day11 {
@@ -46,6 +124,12 @@ impl Day11 {
 		}
 	}
 
+	/// Creates a solver for stones changing by different rules. The single digit lookups only apply to the puzzle's rules,
+	/// so every stone is split normally.
+	fn with_rules(rules: BlinkRules) -> Self {
+		Self { rules, ..Default::default() }
+	}
+
 	/// Counts the number of stones this stone would subdivide into after a certain amount of blinks.
 	/// For each blink:
 	/// - If Engraving 0 -> 1
@@ -73,15 +157,11 @@ impl Day11 {
 				}
 			} else {
 				// It is not a single digit, we need to split it normally and recurse until it becomes a single digit.
-				let mut engraving_str = engraving.to_string();
-				if engraving_str.len() % 2 == 0 {
-					// Split off returns the second half, and mutates the string to be the first half
-					let second = engraving_str.split_off(engraving_str.len() / 2).parse().unwrap();
-					let first = engraving_str.parse().unwrap();
-					self.count_after_blinks(first, blinks - 1) + self.count_after_blinks(second, blinks - 1)
-				} else {
-					// Multiply by 2024
-					self.count_after_blinks(engraving * 2024, blinks - 1)
+				match self.rules.blink(engraving as u64) {
+					(first, Some(second)) => {
+						self.count_after_blinks(first as usize, blinks - 1) + self.count_after_blinks(second as usize, blinks - 1)
+					},
+					(engraving, None) => self.count_after_blinks(engraving as usize, blinks - 1),
 				}
 			}
 		};
@@ -97,12 +177,131 @@ impl Day11 {
 	}
 }
 
+/// Maps each engraving of the given stones to how many stones have it.
+fn distribution(input: &[usize]) -> HashMap<u64, u64> {
+	let mut stones = HashMap::new();
+	for &engraving in input { *stones.entry(engraving as u64).or_default() += 1; }
+	stones
+}
+
+/// The total number of stones in a distribution, saturating at u64::MAX.
+fn total(stones: &HashMap<u64, u64>) -> u64 {
+	stones.values().fold(0, |total: u64, &count| total.saturating_add(count))
+}
+
+/// Gets the number of blinks after which there are more than `threshold` stones, None if that never happens.
+pub fn blinks_until(input: &[usize], threshold: u64) -> Option<usize> {
+	BlinkRules::default().blinks_until(input, threshold)
+}
+
+/// Gets how many stones have each engraving after a number of blinks, starting from the given stones.
+pub fn distribution_after_blinks(input: &[usize], blinks: usize) -> HashMap<u64, u64> {
+	BlinkRules::default().distribution_after_blinks(input, blinks)
+}
+
+/// Iterator over the total number of stones after each successive blink, created by `counts`.
+pub struct BlinkCounts {
+	/// The rules stones change by.
+	rules: BlinkRules,
+	/// The distribution of stones after the blinks so far.
+	stones: HashMap<u64, u64>,
+}
+
+impl Iterator for BlinkCounts {
+	type Item = u64;
+
+	fn next(&mut self) -> Option<u64> {
+		self.stones = self.rules.blink_all(&self.stones);
+		Some(total(&self.stones))
+	}
+}
+
+/// Iterates over the total number of stones after the first blink, the second blink, and so on, e.g.
+/// `counts(input).take(75)` for the totals of every blink up to part 2.
+pub fn counts(input: &[usize]) -> BlinkCounts {
+	BlinkRules::default().counts(input)
+}
+
+/// Possible errors when parsing the initial stones.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StoneParseError {
+	/// The stone at the given index, counted from 0, isn't a whole number.
+	InvalidEngraving { index: usize, engraving: String },
+}
+
+impl Display for StoneParseError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		match self {
+			StoneParseError::InvalidEngraving { index, engraving } => write!(f, "Invalid engraving '{engraving}' on stone {index}"),
+		}
+	}
+}
+
+/// Parses the engravings of the initial stones, separated by whitespace.
+pub fn parse_stones(input: &str) -> Result<Vec<usize>, StoneParseError> {
+	input.split_whitespace()
+		.enumerate()
+		.map(|(index, engraving)| {
+			engraving.parse().map_err(|_| StoneParseError::InvalidEngraving { index, engraving: engraving.to_string() })
+		})
+		.collect()
+}
 
 /// Entry point
 pub fn main() {
 	let mut solver = Day11::new();
 	let example = vec![125, 17];
-	let input = vec![872027, 227, 18, 9760, 0, 4, 67716, 9245696];
+
+	// Read the input stones from another file with `--file <path>`, every other flag uses them too
+	let args = std::env::args().collect::<Vec<_>>();
+	let input = match args.iter().skip_while(|&arg| arg != "--file").nth(1) {
+		Some(path) => std::fs::read_to_string(path).expect("Failed to read input file"),
+		None => include_str!("day11.txt").to_string(),
+	};
+	let input = parse_stones(&input).expect("Failed to parse input stones");
+
+	// Solve both parts with different rules, e.g. `--multiplier 2025 --base 2`
+	let rule_arg = |flag: &str| args.iter().skip_while(|&arg| arg != flag).nth(1)
+		.map(|value| value.parse::<u64>().unwrap_or_else(|_| panic!("Invalid {flag}, expected a number")));
+	let rules = BlinkRules {
+		zero_becomes: rule_arg("--zero").unwrap_or(1),
+		base: rule_arg("--base").inspect(|&base| if base < 2 { panic!("Invalid --base, expected at least 2") }).unwrap_or(10),
+		multiplier: rule_arg("--multiplier").unwrap_or(2024),
+	};
+	if rules != BlinkRules::default() {
+		let mut solver = Day11::with_rules(rules);
+		println!("Part 1 Solution on Input with {rules:?}: {:#?}", solver.count_arrangement_after_blinks(&input, 25));
+		println!("Part 2 Solution on Input with {rules:?}: {:#?}", solver.count_arrangement_after_blinks(&input, 75));
+		return;
+	}
+
+	// Print the most common engravings after a number of blinks, e.g. `--distribution 75`
+	if let Some(blinks) = args.iter().skip_while(|&arg| arg != "--distribution").nth(1) {
+		let blinks = blinks.parse().expect("Invalid --distribution, expected a number of blinks");
+		let distribution = distribution_after_blinks(&input, blinks);
+		let mut common = distribution.iter().collect::<Vec<_>>();
+		common.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+		println!("{} distinct engravings after {blinks} blinks", distribution.len());
+		for (engraving, count) in common.iter().take(20) { println!("{engraving}: {count}"); }
+		return;
+	}
+
+	// Print how many blinks it takes to have more than a number of stones, e.g. `--until 1000000000000`
+	if let Some(threshold) = args.iter().skip_while(|&arg| arg != "--until").nth(1) {
+		let threshold = threshold.parse().expect("Invalid --until, expected a number of stones");
+		match blinks_until(&input, threshold) {
+			Some(blinks) => println!("More than {threshold} stones after {blinks} blinks"),
+			None => println!("Never more than {threshold} stones"),
+		}
+		return;
+	}
+
+	// Print the total number of stones after every blink up to a number of blinks, e.g. `--growth 75`
+	if let Some(blinks) = args.iter().skip_while(|&arg| arg != "--growth").nth(1) {
+		let blinks = blinks.parse().expect("Invalid --growth, expected a number of blinks");
+		for (blink, count) in counts(&input).take(blinks).enumerate() { println!("{}: {count}", blink + 1); }
+		return;
+	}
 
 	println!("Part 1 Solution on Example: {:#?}", solver.count_arrangement_after_blinks(&example, 25));
 	println!("Part 1 Solution on Input: {:#?}", solver.count_arrangement_after_blinks(&input, 25));
@@ -110,3 +309,71 @@ pub fn main() {
 	println!("Part 2 Solution on Example: {:#?}", solver.count_arrangement_after_blinks(&example, 75));
 	println!("Part 2 Solution on Input: {:#?}", solver.count_arrangement_after_blinks(&input, 75));
 }
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+
+	/// Tests the distribution matches the example and the recursive counter
+	#[test]
+	fn test_distribution() {
+		let distribution = distribution_after_blinks(&[125, 17], 6);
+		assert_eq!(distribution.values().sum::<u64>(), 22);
+		assert_eq!(distribution[&2], 4);
+		assert_eq!(distribution[&4048], 1);
+
+		let stones = [872027, 227, 18, 9760, 0, 4, 67716, 9245696];
+		let total = distribution_after_blinks(&stones, 40).values().sum::<u64>();
+		assert_eq!(total as usize, Day11::new().count_arrangement_after_blinks(&stones, 40));
+	}
+
+	/// Tests the growth iterator yields the example totals after each blink
+	#[test]
+	fn test_counts() {
+		assert_eq!(counts(&[125, 17]).take(6).collect::<Vec<_>>(), [3, 4, 5, 9, 13, 22]);
+		assert_eq!(counts(&[125, 17]).nth(24), Some(55312));
+	}
+
+	/// Tests custom rules against the lookup table and a base 2 variant
+	#[test]
+	fn test_blink_rules() {
+		let stones = [872027, 227, 18, 9760, 0, 4, 67716, 9245696];
+		let expected = Day11::new().count_arrangement_after_blinks(&stones, 30);
+		assert_eq!(Day11::with_rules(BlinkRules::default()).count_arrangement_after_blinks(&stones, 30), expected);
+
+		// 0b101 has 3 digits so is multiplied, 0b1111 is split into 0b11 and 0b11
+		let rules = BlinkRules { zero_becomes: 2, base: 2, multiplier: 3 };
+		assert_eq!(rules.blink(0b101), (15, None));
+		assert_eq!(rules.blink(0b1111), (0b11, Some(0b11)));
+		assert_eq!(rules.blink(0), (2, None));
+
+		// Bases below 2 never split, and engravings saturate rather than overflowing
+		assert_eq!(BlinkRules { base: 1, ..Default::default() }.blink(1000), (2024000, None));
+		assert_eq!(BlinkRules { base: 0, ..Default::default() }.blink(10), (20240, None));
+		assert_eq!(BlinkRules { multiplier: u64::MAX / 2, ..Default::default() }.blink(5), (u64::MAX, None));
+		let total = rules.counts(&[5, 0]).nth(9).unwrap();
+		assert_eq!(total as usize, Day11::with_rules(rules).count_arrangement_after_blinks(&[5, 0], 10));
+	}
+
+	/// Tests the number of blinks to cross a threshold, and thresholds which are never crossed
+	#[test]
+	fn test_blinks_until() {
+		assert_eq!(blinks_until(&[125, 17], 1), Some(0));
+		assert_eq!(blinks_until(&[125, 17], 2), Some(1));
+		assert_eq!(blinks_until(&[125, 17], 55311), Some(25));
+		assert_eq!(blinks_until(&[125, 17], 55312), Some(26));
+		assert_eq!(blinks_until(&[125, 17], u64::MAX), None);
+		assert_eq!(blinks_until(&[], 0), None);
+
+		// Counts saturate rather than overflowing
+		assert!(blinks_until(&[125, 17], u64::MAX - 1).is_some());
+		let rules = BlinkRules { multiplier: 1, ..Default::default() };
+		assert_eq!(rules.blinks_until(&[5], 1), None);
+		// The stones alternate between 5 and 0 forever
+		let rules = BlinkRules { zero_becomes: 5, multiplier: 0, ..Default::default() };
+		assert_eq!(rules.blinks_until(&[5], 1), None);
+		assert_eq!(rules.blinks_until(&[5, 10], 2), Some(1));
+	}
+
+}
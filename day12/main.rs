@@ -1,19 +1,47 @@
-use std::collections::{HashMap, HashSet, VecDeque};
+mod stream;
+
+use std::{collections::{BTreeMap, HashMap, HashSet, VecDeque}, fmt::{self, Display, Formatter}, io::BufReader};
+
+use common::validate::validate_file;
+use stream::stream_totals;
 
 /// A single position on the garden
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
-struct Position { x: usize, y: usize }
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Position { pub x: usize, pub y: usize }
 
 impl Position {
+	/// Offsets this position, wrapping around the edges of a `(width, height)` garden if given. Returns None if the
+	/// position would've had a negative.
+	fn offset(&self, d_x: isize, d_y: isize, wrap: Option<(usize, usize)>) -> Option<Position> {
+		match wrap {
+			Some((width, height)) => Some(Self {
+				x: (self.x as isize + d_x).rem_euclid(width as isize) as usize,
+				y: (self.y as isize + d_y).rem_euclid(height as isize) as usize,
+			}),
+			None => Some(Self { x: self.x.checked_add_signed(d_x)?, y: self.y.checked_add_signed(d_y)? }),
+		}
+	}
+
+	/// Gets the positions joined to this one with the given connectivity, skipping any which would've had a negative.
+	fn get_connected(&self, connectivity: Connectivity, wrap: Option<(usize, usize)>) -> Vec<Position> {
+		let mut connected = self.get_neighbors_in(wrap).into_iter().flatten().collect::<Vec<_>>();
+		if connectivity == Connectivity::Eight {
+			connected.extend([(-1, -1), (1, -1), (1, 1), (-1, 1)].into_iter().filter_map(|(d_x, d_y)| self.offset(d_x, d_y, wrap)));
+		}
+		connected
+	}
+
 	/// Gets neighboring positions to this one in clockwise order. Returns None if the position would've had a negative.
 	fn get_neighbors(&self) -> [Option<Position>; 4] {
-		[
-			// 0,0 is at top-left, x increases right, y increases down
-			self.x.checked_sub(1).map(|x| Self { x, y: self.y }),
-			self.y.checked_sub(1).map(|y| Self { x: self.x, y }),
-			Some(Self { x: self.x + 1, y: self.y }),
-			Some(Self { x: self.x, y: self.y + 1 }),
-		]
+		self.get_neighbors_in(None)
+	}
+
+	/// Gets neighboring positions to this one in clockwise order, wrapping around the edges of a `(width, height)`
+	/// garden if given. Returns None if the position would've had a negative.
+	fn get_neighbors_in(&self, wrap: Option<(usize, usize)>) -> [Option<Position>; 4] {
+		// 0,0 is at top-left, x increases right, y increases down
+		[(-1, 0), (0, -1), (1, 0), (0, 1)].map(|(d_x, d_y)| self.offset(d_x, d_y, wrap))
 	}
 }
 
@@ -23,16 +51,110 @@ struct Plant { variant: char }
 
 /// A single region, a group of multiple plots growing the same plant with adjacent borders.
 #[derive(Debug, Clone)]
-struct Region { plots: HashSet<Position> }
+struct Region {
+	plant: Plant,
+	plots: HashSet<Position>,
+	/// The `(width, height)` of the garden when its edges wrap around, so plots on opposite edges are neighbors.
+	wrap: Option<(usize, usize)>,
+}
 
 impl Region {
 	/// Gets the neighboring plots to a plot which are present in this region.
 	fn get_neighbors(&self, plot: Position) -> [Option<Position>; 4] {
-		plot.get_neighbors().map(|neighbor| {
+		plot.get_neighbors_in(self.wrap).map(|neighbor| {
 			self.plots.contains(&neighbor?).then_some(neighbor?)
 		})
 	}
 
+	/// The first plot of the region in reading order, top to bottom then left to right.
+	fn representative(&self) -> Position {
+		*self.plots.iter().min_by_key(|pos| (pos.y, pos.x)).expect("Regions always have a plot")
+	}
+
+	/// Gets the smallest box containing every plot of the region.
+	fn bounding_box(&self) -> BoundingBox {
+		let (xs, ys) = (self.plots.iter().map(|pos| pos.x), self.plots.iter().map(|pos| pos.y));
+		BoundingBox {
+			top_left: Position { x: xs.clone().min().unwrap_or(0), y: ys.clone().min().unwrap_or(0) },
+			bottom_right: Position { x: xs.max().unwrap_or(0), y: ys.max().unwrap_or(0) },
+		}
+	}
+
+	/// Gets the holes inside this region, each a connected group of plots outside the region which it completely
+	/// surrounds.
+	fn holes(&self) -> Vec<HashSet<Position>> {
+		// Work within the bounding box with a ring of space around it, so the region never touches the edge
+		let BoundingBox { top_left, bottom_right } = self.bounding_box();
+		let (width, height) = (bottom_right.x - top_left.x + 3, bottom_right.y - top_left.y + 3);
+		let to_global = |pos: Position| Position { x: pos.x + top_left.x - 1, y: pos.y + top_left.y - 1 };
+		let on_ring = |pos: Position| pos.x == 0 || pos.y == 0 || pos.x == width - 1 || pos.y == height - 1;
+		let mut open: HashSet<Position> = (0..width).flat_map(|x| (0..height).map(move |y| Position { x, y }))
+			.filter(|&pos| on_ring(pos) || !self.plots.contains(&to_global(pos)))
+			.collect();
+
+		// Anything reachable from the ring is outside, every other group of open plots is a hole
+		let flood = |start: Position, open: &mut HashSet<Position>| {
+			let mut group = HashSet::new();
+			let mut exploring = vec![start];
+			while let Some(pos) = exploring.pop() {
+				if !open.remove(&pos) { continue }
+				exploring.extend(pos.get_neighbors().into_iter().flatten());
+				group.insert(pos);
+			}
+			group
+		};
+		flood(Position { x: 0, y: 0 }, &mut open);
+		let mut holes = Vec::new();
+		while let Some(&start) = open.iter().next() {
+			holes.push(flood(start, &mut open).into_iter().map(to_global).collect::<HashSet<_>>());
+		}
+		holes.sort_by_cached_key(|hole| hole.iter().map(|pos| (pos.y, pos.x)).min());
+		holes
+	}
+
+	/// Traces the fences around this region into closed rings of corner points, ignoring any wrapping. Points are `(x, y)`
+	/// with y increasing upward, so the garden's top-left corner is `(0, 0)` and row `y` spans `-y - 1` to `-y`. Each
+	/// ring keeps the region on its left, making the outer boundaries counter-clockwise and holes clockwise, and only
+	/// has a point where the fence turns, so the rings have as many points as the region has sides.
+	fn boundary_rings(&self) -> Vec<Vec<(i64, i64)>> {
+		// Every side of a plot without a neighbor in the region is a length of fence, walked counter-clockwise around the plot
+		let mut fences: HashMap<(i64, i64), Vec<(i64, i64)>> = HashMap::new();
+		for plot in &self.plots {
+			let (x, y) = (plot.x as i64, -(plot.y as i64));
+			let neighbors = plot.get_neighbors().map(|neighbor| neighbor.is_some_and(|pos| self.plots.contains(&pos)));
+			// Neighbors are left, up, right, down, and each fence runs from its first corner to the next
+			let corners = [(x, y), (x, y - 1), (x + 1, y - 1), (x + 1, y)];
+			for (side, start, end) in [(0, 0, 1), (3, 1, 2), (2, 2, 3), (1, 3, 0)] {
+				if !neighbors[side] { fences.entry(corners[start]).or_default().push(corners[end]); }
+			}
+		}
+
+		// Starting each ring at the top-left-most remaining corner always starts it on a corner, and in a consistent place
+		let mut rings = Vec::new();
+		while let Some(start) = fences.keys().copied().max_by_key(|&(x, y)| (y, -x)) {
+			let mut points = vec![start];
+			let mut direction = (0, 0);
+			let mut current = start;
+			loop {
+				// Where two plots of the region only touch at a corner, turn left to stay with the same plot
+				let ends = fences.get_mut(&current).expect("Every fence continues from where it ends");
+				let turns_left = |end: &(i64, i64)| direction.0 * (end.1 - current.1) - direction.1 * (end.0 - current.0) > 0;
+				let index = ends.iter().position(turns_left).unwrap_or(0);
+				let next = ends.swap_remove(index);
+				let next_direction = (next.0 - current.0, next.1 - current.1);
+				if next_direction == direction { points.pop(); }
+				points.push(next);
+				(current, direction) = (next, next_direction);
+				if current == start { break }
+			}
+
+			points.pop();
+			rings.push(points);
+			fences.retain(|_, ends| !ends.is_empty());
+		}
+		rings
+	}
+
 	/// Calculates the perimeter of this region
 	fn calculate_perimeter(&self) -> usize {
 		self.plots.iter().map(|&pos| {
@@ -55,8 +177,11 @@ impl Region {
 
 			// It is an edge, first we need to go backward to the "left" corner, then loop forward to the "right" corner, and mark
 			// everything in between as visited.
+			// When the garden wraps an edge can loop all the way around, so stop on returning to where we started
 			for turn in [1, 3] {
+				let origin = pos;
 				while let Some(next) = neighbors[(facing + turn) % 4] {
+					if next == origin { break }
 					pos = next;
 					neighbors = self.get_neighbors(pos);
 					unvisited.remove(&(pos, facing));
@@ -71,8 +196,130 @@ impl Region {
 	}
 }
 
+/// Describes the smallest box containing a region, both corners are inclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BoundingBox {
+	pub top_left: Position,
+	pub bottom_right: Position,
+}
+
+/// Measurements of a single region of the garden.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RegionReport {
+	/// The plant grown in the region.
+	pub plant: char,
+	pub area: usize,
+	pub perimeter: usize,
+	/// The number of straight sides of the region's perimeter.
+	pub sides: usize,
+	pub bounding_box: BoundingBox,
+	/// The first plot of the region in reading order, which identifies the region.
+	pub representative: Position,
+}
+
+impl From<&Region> for RegionReport {
+	fn from(region: &Region) -> Self {
+		Self {
+			plant: region.plant.variant,
+			area: region.plots.len(),
+			perimeter: region.calculate_perimeter(),
+			sides: region.calculate_sides(),
+			bounding_box: region.bounding_box(),
+			representative: region.representative(),
+		}
+	}
+}
+
+/// A region of the garden, the holes inside it, and where it sits in the tree of regions enclosing each other.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RegionNode {
+	pub report: RegionReport,
+	/// The plots of each hole inside the region, in reading order.
+	pub holes: Vec<Vec<Position>>,
+	/// The index of the innermost region completely surrounding this one.
+	pub parent: Option<usize>,
+	/// The indices of the regions this region is the innermost surrounding region of.
+	pub children: Vec<usize>,
+}
+
+/// A region bordering another, and how many plot sides of fence the two share.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RegionBorder {
+	/// The index of the neighboring region.
+	pub region: usize,
+	pub length: usize,
+}
+
+/// The outline of a single region, as polygons of corner points (see `Region::boundary_rings` for the coordinates).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegionPolygon {
+	pub report: RegionReport,
+	/// Each polygon is its outer boundary followed by any holes. There is more than one polygon when plots are joined
+	/// only by touching corners.
+	pub polygons: Vec<Vec<Vec<(i64, i64)>>>,
+}
+
+impl From<&Region> for RegionPolygon {
+	fn from(region: &Region) -> Self {
+		let (mut outer, mut holes): (Vec<_>, Vec<_>) = region.boundary_rings().into_iter().partition(|ring| signed_area(ring) > 0);
+		outer.sort_by_key(|ring| std::cmp::Reverse(ring.iter().map(|&(x, y)| (y, -x)).max()));
+		let mut polygons = outer.into_iter().map(|ring| vec![ring]).collect::<Vec<_>>();
+
+		// Every hole goes in the smallest outer boundary containing the middle of the plot just inside its first fence
+		holes.sort_by_key(|ring| std::cmp::Reverse(ring.iter().map(|&(x, y)| (y, -x)).max()));
+		for hole in holes {
+			let ((x1, y1), (x2, y2)) = (hole[0], hole[1]);
+			let (d_x, d_y) = ((x2 - x1).signum(), (y2 - y1).signum());
+			// The region is on the left of the fence, so the hole is on the right
+			let inside = (2 * x1 + d_x + d_y, 2 * y1 + d_y - d_x);
+			let polygon = polygons.iter_mut()
+				.filter(|polygon| contains_doubled(&polygon[0], inside))
+				.min_by_key(|polygon| signed_area(&polygon[0]))
+				.expect("Holes are always inside an outer boundary");
+			polygon.push(hole);
+		}
+		Self { report: RegionReport::from(region), polygons }
+	}
+}
+
+/// The area inside a ring, positive when counter-clockwise and negative when clockwise.
+fn signed_area(ring: &[(i64, i64)]) -> i64 {
+	let doubled: i64 = ring.iter().zip(ring.iter().cycle().skip(1)).map(|(a, b)| a.0 * b.1 - b.0 * a.1).sum();
+	doubled / 2
+}
+
+/// Whether a point, given at double scale so it can sit halfway between corners, is inside a ring. The point must not
+/// be on the ring.
+fn contains_doubled(ring: &[(i64, i64)], (x, y): (i64, i64)) -> bool {
+	ring.iter().zip(ring.iter().cycle().skip(1))
+		.filter(|&(&(x1, y1), &(x2, y2))| x1 == x2 && 2 * x1 > x && (2 * y1 > y) != (2 * y2 > y))
+		.count() % 2 == 1
+}
+
+/// Which plots of the same plant are joined into one region.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Connectivity {
+	/// Plots sharing a side, as in the puzzle.
+	#[default]
+	Four,
+	/// Plots sharing a side or only touching diagonally.
+	Eight,
+}
+
 /// A map from plot positions to their plant type for all plots in the garden.
-struct Garden { plots: HashMap<Position, Plant> }
+pub struct Garden {
+	plots: HashMap<Position, Plant>,
+	/// Which plots are joined into regions, fences are always along the sides of plots either way.
+	connectivity: Connectivity,
+	/// The `(width, height)` of the garden.
+	size: (usize, usize),
+	/// Whether the edges of the garden wrap around to the opposite edge.
+	wrap: bool,
+}
 
 impl From<&str> for Garden {
     fn from(value: &str) -> Self {
@@ -82,29 +329,46 @@ impl From<&str> for Garden {
 					line.chars().enumerate()
 						.map(move |(x, variant)| (Position { x, y }, Plant { variant }))
 				})
-				.collect()
+				.collect(),
+			connectivity: Connectivity::Four,
+			size: (value.lines().next().map_or(0, |line| line.chars().count()), value.lines().count()),
+			wrap: false,
 		}
     }
 }
 
 impl Garden {
-	/// Calculates all regions of gardens growing the same crops and returns them.
+	/// Sets which plots of the same plant are joined into one region.
+	pub fn with_connectivity(mut self, connectivity: Connectivity) -> Self {
+		self.connectivity = connectivity;
+		self
+	}
+
+	/// Sets whether the edges of the garden wrap around, so plots on opposite edges are neighbors for both regions and
+	/// fences. Holes are still only found within a region's bounding box.
+	pub fn with_wrapping(mut self, wrap: bool) -> Self {
+		self.wrap = wrap;
+		self
+	}
+
+	/// Calculates all regions of gardens growing the same crops and returns them, in reading order of their first plot.
 	fn calculate_regions(&self) -> Vec<Region> {
 		// Clone plots to drain into regions
 		let mut plots = self.plots.clone();
 		let mut regions = Vec::new();
+		let wrap = self.wrap.then_some(self.size);
 
 		// Loop while plots is not empty
 		while let Some((start_pos, region_plant)) = plots.iter().next().map(|(&p, &r)| (p, r)) {
 			// Each region will have an exploring list, which will be continually updated with neighbors
 			let mut exploring_list = VecDeque::from([start_pos]);
-			let mut region = Region { plots: HashSet::new() };
+			let mut region = Region { plant: region_plant, plots: HashSet::new(), wrap };
 
 			// Add exploring into region if it is the same plant, and expand the exploring list with the neighbors
 			while let Some(exploring) = exploring_list.pop_back() {
 				let Some(plant) = plots.get(&exploring) else { continue };
 				if *plant != region_plant { continue; }
-				exploring_list.extend(exploring.get_neighbors().into_iter().flatten());
+				exploring_list.extend(exploring.get_connected(self.connectivity, wrap));
 				plots.remove(&exploring);
 				region.plots.insert(exploring);
 			}
@@ -112,24 +376,181 @@ impl Garden {
 			regions.push(region);
 		}
 
+		regions.sort_by_cached_key(|region| { let pos = region.representative(); (pos.y, pos.x) });
 		regions
 	}
+
+	/// Measures every region of the garden, in reading order of their first plot.
+	pub fn analyze(&self) -> Vec<RegionReport> {
+		self.calculate_regions().iter().map(RegionReport::from).collect()
+	}
+
+	/// Calculates the sum of the prices of every region in the garden, priced by `pricer`.
+	pub fn total_price(&self, pricer: impl Fn(&RegionReport) -> u64) -> u64 {
+		self.analyze().iter().map(pricer).sum()
+	}
+
+	/// Finds the holes in every region and which regions enclose which. Regions are in the same order as `analyze`,
+	/// which the parent and child indices refer to.
+	pub fn containment_tree(&self) -> Vec<RegionNode> {
+		let regions = self.calculate_regions();
+		let holes = regions.iter().map(Region::holes).collect::<Vec<_>>();
+		let representatives: HashMap<Position, usize> = regions.iter().enumerate()
+			.map(|(idx, region)| (region.representative(), idx))
+			.collect();
+
+		// A region is inside another if its first plot is in one of the other's holes, the smallest such hole is innermost
+		let mut parents: Vec<Option<(usize, usize)>> = vec![None; regions.len()];
+		for (outer, region_holes) in holes.iter().enumerate() {
+			for hole in region_holes {
+				for inner in hole.iter().filter_map(|pos| representatives.get(pos)) {
+					if parents[*inner].is_none_or(|(_, size)| hole.len() < size) { parents[*inner] = Some((outer, hole.len())); }
+				}
+			}
+		}
+
+		let mut nodes = regions.iter().zip(holes).map(|(region, holes)| RegionNode {
+			report: RegionReport::from(region),
+			holes: holes.into_iter().map(|hole| {
+				let mut hole = hole.into_iter().collect::<Vec<_>>();
+				hole.sort_by_key(|pos| (pos.y, pos.x));
+				hole
+			}).collect(),
+			parent: None,
+			children: Vec::new(),
+		}).collect::<Vec<_>>();
+		for (inner, parent) in parents.into_iter().enumerate() {
+			let Some((outer, _)) = parent else { continue };
+			nodes[inner].parent = Some(outer);
+			nodes[outer].children.push(inner);
+		}
+		nodes
+	}
+
+	/// Finds the regions bordering each region, sorted by index, along with the length of their shared fence. Regions
+	/// are in the same order as `analyze`, which the indices refer to.
+	pub fn adjacency(&self) -> Vec<Vec<RegionBorder>> {
+		let regions = self.calculate_regions();
+		let region_of: HashMap<Position, usize> = regions.iter().enumerate()
+			.flat_map(|(idx, region)| region.plots.iter().map(move |&pos| (pos, idx)))
+			.collect();
+
+		// Every side between plots of two different regions is one length of fence they share
+		regions.iter().enumerate().map(|(idx, region)| {
+			let mut borders = BTreeMap::new();
+			for plot in &region.plots {
+				for neighbor in plot.get_neighbors_in(region.wrap).into_iter().flatten() {
+					let Some(&other) = region_of.get(&neighbor) else { continue };
+					if other != idx { *borders.entry(other).or_insert(0) += 1; }
+				}
+			}
+			borders.into_iter().map(|(region, length)| RegionBorder { region, length }).collect()
+		}).collect()
+	}
+
+	/// Traces the outline of every region, in the same order as `analyze`.
+	pub fn polygons(&self) -> Vec<RegionPolygon> {
+		self.calculate_regions().iter().map(RegionPolygon::from).collect()
+	}
+
+	/// Exports every region as a GeoJSON feature collection, with each region's plant, area, perimeter, and sides as
+	/// properties. Rows are flipped so north is the top of the garden, and wrapping is ignored.
+	pub fn to_geojson(&self) -> String {
+		let features = self.polygons().iter().map(|region| {
+			let report = &region.report;
+			let polygons = region.polygons.iter().map(|polygon| {
+				let rings = polygon.iter().map(|ring| {
+					// GeoJSON rings repeat their first point to close
+					let points = ring.iter().chain(ring.first()).map(|(x, y)| format!("[{x},{y}]")).collect::<Vec<_>>();
+					format!("[{}]", points.join(","))
+				}).collect::<Vec<_>>();
+				format!("[{}]", rings.join(","))
+			}).collect::<Vec<_>>();
+			let geometry = match &polygons[..] {
+				[polygon] => format!(r#"{{"type":"Polygon","coordinates":{polygon}}}"#),
+				polygons => format!(r#"{{"type":"MultiPolygon","coordinates":[{}]}}"#, polygons.join(",")),
+			};
+			let plant = report.plant.to_string().replace('\\', "\\\\").replace('"', "\\\"");
+			format!(
+				r#"{{"type":"Feature","properties":{{"plant":"{plant}","area":{},"perimeter":{},"sides":{}}},"geometry":{geometry}}}"#,
+				report.area, report.perimeter, report.sides,
+			)
+		}).collect::<Vec<_>>();
+		format!(r#"{{"type":"FeatureCollection","features":[{}]}}"#, features.join(","))
+	}
+}
+
+/// Finds which regions border which, see `Garden::adjacency`.
+pub fn adjacency(input: &str) -> Vec<Vec<RegionBorder>> {
+	Garden::from(input).adjacency()
+}
+
+/// Measures every region of the garden, in reading order of their first plot.
+pub fn analyze(input: &str) -> Vec<RegionReport> {
+	Garden::from(input).analyze()
+}
+
+/// Finds the holes in every region and which regions enclose which, see `Garden::containment_tree`.
+pub fn containment_tree(input: &str) -> Vec<RegionNode> {
+	Garden::from(input).containment_tree()
+}
+
+/// Prices a region by its area multiplied by its perimeter, as in part 1.
+pub fn perimeter_price(report: &RegionReport) -> u64 {
+	(report.area * report.perimeter) as u64
+}
+
+/// Prices a region by its area multiplied by its number of sides, as in part 2.
+pub fn sides_price(report: &RegionReport) -> u64 {
+	(report.area * report.sides) as u64
+}
+
+/// Calculates the sum of the prices of every region in the garden, priced by `pricer`.
+pub fn total_price(input: &str, pricer: impl Fn(&RegionReport) -> u64) -> u64 {
+	Garden::from(input).total_price(pricer)
+}
+
+/// A structural problem with a garden, found by `validate`. Lines count from 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputProblem {
+	/// The input has no lines.
+	Empty,
+	/// A line isn't as long as the first, so the garden isn't rectangular.
+	RaggedLine { line: usize, length: usize, expected: usize },
+}
+
+impl Display for InputProblem {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		match self {
+			InputProblem::Empty => write!(f, "The garden is empty"),
+			InputProblem::RaggedLine { line, length, expected } => {
+				write!(f, "Line {line} is {length} long, expected {expected} like the first line")
+			},
+		}
+	}
+}
+
+/// Checks a garden is rectangular without solving it, reporting every problem rather than stopping at the first. Any
+/// character is a plant, so there are no unknown characters.
+pub fn validate(input: &str) -> Vec<InputProblem> {
+	let lines = input.lines().collect::<Vec<_>>();
+	let Some(first) = lines.first() else { return vec![InputProblem::Empty] };
+	let expected = first.chars().count();
+	lines.iter().enumerate()
+		.map(|(idx, text)| (idx + 1, text.chars().count()))
+		.filter(|&(_, length)| length != expected)
+		.map(|(line, length)| InputProblem::RaggedLine { line, length, expected })
+		.collect()
 }
 
 /// Calculates the sum of products of the perimeter and area of all regions.
-fn part1_solution(input: &str) -> usize {
-	Garden::from(input).calculate_regions()
-		.iter()
-		.map(|region| region.plots.len() * region.calculate_perimeter())
-		.sum()
+fn part1_solution(input: &str) -> u64 {
+	total_price(input, perimeter_price)
 }
 
 /// Calculates the sum of products of the sides and area of all regions.
-fn part2_solution(input: &str) -> usize {
-	Garden::from(input).calculate_regions()
-		.iter()
-		.map(|region| region.plots.len() * region.calculate_sides())
-		.sum()
+fn part2_solution(input: &str) -> u64 {
+	total_price(input, sides_price)
 }
 
 /// Entry point
@@ -144,7 +565,111 @@ VVIIICJJEE
 MIIIIIJJEE
 MIIISIJEEE
 MMMISSJEEE";
-	let input = include_str!("day12.txt");
+	// Solve another input instead of the bundled one with `--file <path>`
+	let args = std::env::args().collect::<Vec<_>>();
+	let file = args.iter().skip_while(|&arg| arg != "--file").nth(1)
+		.map(|path| std::fs::read_to_string(path).expect("Failed to read input file"));
+	let input = file.as_deref().unwrap_or(include_str!("day12.txt"));
+
+	// Check a file for problems without solving it with `--validate <path>`
+	if let Some(path) = args.iter().skip_while(|&arg| arg != "--validate").nth(1) {
+		validate_file(path, validate);
+		return;
+	}
+
+	// Total a garden too large to load by streaming it from `--stream <path>`, forgetting finished regions every
+	// `--band <rows>` rows
+	if let Some(path) = args.iter().skip_while(|&arg| arg != "--stream").nth(1) {
+		let band_height = args.iter().skip_while(|&arg| arg != "--band").nth(1)
+			.map_or(1024, |rows| rows.parse().expect("Band height must be a number of rows"));
+		let file = std::fs::File::open(path).expect("Failed to open input file");
+		match stream_totals(BufReader::new(file), band_height) {
+			Ok(totals) => {
+				println!("Regions: {}", totals.regions);
+				println!("Perimeter price: {}", totals.perimeter_price);
+				println!("Sides price: {}", totals.sides_price);
+			},
+			Err(e) => { eprintln!("{e}"); std::process::exit(1) },
+		}
+		return;
+	}
+
+	// Print the measurements of every region, add `--diagonal` to join plots which only touch diagonally, and `--wrap`
+	// to wrap the garden's edges
+	let connectivity = if args.iter().any(|arg| arg == "--diagonal") { Connectivity::Eight } else { Connectivity::Four };
+	let garden = Garden::from(if args.iter().any(|arg| arg == "--example") { example } else { input })
+		.with_connectivity(connectivity)
+		.with_wrapping(args.iter().any(|arg| arg == "--wrap"));
+	if args.iter().any(|arg| arg == "--report") {
+		for report in garden.analyze() {
+			let BoundingBox { top_left, bottom_right } = report.bounding_box;
+			println!(
+				"{} at ({}, {}): area {}, perimeter {}, sides {}, bounds ({}, {}) to ({}, {})",
+				report.plant, report.representative.x, report.representative.y, report.area, report.perimeter, report.sides,
+				top_left.x, top_left.y, bottom_right.x, bottom_right.y,
+			);
+		}
+		return;
+	}
+
+	// Price the garden with another formula, `perimeter`, `sides`, `perimeter-squared`, or `area-plus-sides`
+	if let Some(pricing) = args.iter().skip_while(|&arg| arg != "--pricing").nth(1) {
+		let pricer: fn(&RegionReport) -> u64 = match pricing.as_str() {
+			"perimeter" => perimeter_price,
+			"sides" => sides_price,
+			"perimeter-squared" => |report| (report.perimeter * report.perimeter) as u64,
+			"area-plus-sides" => |report| (report.area + report.sides) as u64,
+			_ => panic!("Unknown pricing {pricing}"),
+		};
+		println!("Total price: {}", garden.total_price(pricer));
+		return;
+	}
+
+	// Print the regions bordering each region, and the touching pair with the largest combined area
+	if args.iter().any(|arg| arg == "--adjacency") {
+		let reports = garden.analyze();
+		let adjacency = garden.adjacency();
+		for (report, borders) in reports.iter().zip(&adjacency) {
+			let borders = borders.iter().map(|border| {
+				let other = &reports[border.region];
+				format!("{} at ({}, {}) by {}", other.plant, other.representative.x, other.representative.y, border.length)
+			}).collect::<Vec<_>>();
+			println!(
+				"{} at ({}, {}): borders [{}]",
+				report.plant, report.representative.x, report.representative.y, borders.join(", "),
+			);
+		}
+		let largest = adjacency.iter().enumerate()
+			.flat_map(|(idx, borders)| borders.iter().map(move |border| (idx, border.region)))
+			.max_by_key(|&(a, b)| reports[a].area + reports[b].area);
+		if let Some((a, b)) = largest {
+			println!("Largest touching pair: {} and {}, area {}", reports[a].plant, reports[b].plant, reports[a].area + reports[b].area);
+		}
+		return;
+	}
+
+	// Print every region's outline as GeoJSON
+	if args.iter().any(|arg| arg == "--geojson") {
+		println!("{}", garden.to_geojson());
+		return;
+	}
+
+	// Print every region with holes, and the regions inside them
+	if args.iter().any(|arg| arg == "--holes") {
+		let nodes = garden.containment_tree();
+		for node in nodes.iter().filter(|node| !node.holes.is_empty()) {
+			let children = node.children.iter().map(|&child| {
+				let report = &nodes[child].report;
+				format!("{} at ({}, {})", report.plant, report.representative.x, report.representative.y)
+			}).collect::<Vec<_>>();
+			let report = &node.report;
+			println!(
+				"{} at ({}, {}): {} holes, surrounds [{}]",
+				report.plant, report.representative.x, report.representative.y, node.holes.len(), children.join(", "),
+			);
+		}
+		return;
+	}
 
 	println!("Part 1 Solution on Example: {:#?}", part1_solution(example));
 	println!("Part 1 Solution on Input: {:#?}", part1_solution(input));
@@ -157,45 +682,53 @@ MMMISSJEEE";
 mod tests {
 
 	use super::*;
+	use stream::{GardenTotals, StreamError};
 
 	/// Tests various use cases of calculating sides
 	#[test]
 	fn test_calculate_sides() {
 		// Test single block
-		let region = Region { plots: HashSet::from([ Position { x: 0, y: 0 } ]) };
+		let region = Region { plant: Plant { variant: 'A' }, plots: HashSet::from([ Position { x: 0, y: 0 } ]), wrap: None };
 		assert_eq!(region.calculate_sides(), 4);
 
 		// Test trivial square (tests right turns & ending on right turn)
 		let region = Region {
+			plant: Plant { variant: 'A' },
 			plots: HashSet::from([
 				Position { x: 2, y: 2 },
 				Position { x: 3, y: 2 },
 				Position { x: 3, y: 3 },
 				Position { x: 2, y: 3 },
-			])
+			]),
+			wrap: None,
 		};
 		assert_eq!(region.calculate_sides(), 4);
 
 		// Test cross (+ shape) - (tests left turns, right turns, ending on left turn)
 		let region = Region {
+			plant: Plant { variant: 'A' },
 			plots: HashSet::from([
 				Position { x: 1, y: 2 },
 				Position { x: 2, y: 2 },
 				Position { x: 3, y: 2 },
 				Position { x: 2, y: 3 },
 				Position { x: 2, y: 1 },
-			])
+			]),
+			wrap: None,
 		};
 		assert_eq!(region.calculate_sides(), 12);
 
 		// Test larger square (tests right turns & ending in middle of edge)
 		let region = Region {
-			plots: (0..5).flat_map(|x| (0..5).map(move |y| Position { x, y })).collect()
+			plant: Plant { variant: 'A' },
+			plots: (0..5).flat_map(|x| (0..5).map(move |y| Position { x, y })).collect(),
+			wrap: None,
 		};
 		assert_eq!(region.calculate_sides(), 4);
 
 		// Test hole - [] shape
 		let region = Region {
+			plant: Plant { variant: 'A' },
 			plots: HashSet::from([
 				Position { x: 0, y: 0 },
 				Position { x: 1, y: 0 },
@@ -205,7 +738,8 @@ mod tests {
 				Position { x: 1, y: 2 },
 				Position { x: 0, y: 2 },
 				Position { x: 0, y: 1 },
-			])
+			]),
+			wrap: None,
 		};
 		assert_eq!(region.calculate_sides(), 8);
 	}
@@ -226,4 +760,125 @@ mod tests {
 		assert_eq!(part2_solution(garden), 368);
 	}
 
+	/// Tests the per region report keeps each region's plant and measurements
+	#[test]
+	fn test_analyze() {
+		let reports = analyze("AAAA\nBBCD\nBBCC\nEEEC");
+		let summary = reports.iter()
+			.map(|report| (report.plant, report.area, report.perimeter, report.sides))
+			.collect::<Vec<_>>();
+		assert_eq!(summary, [('A', 4, 10, 4), ('B', 4, 8, 4), ('C', 4, 10, 8), ('D', 1, 4, 4), ('E', 3, 8, 4)]);
+		assert_eq!(reports[2].representative, Position { x: 2, y: 1 });
+		assert_eq!(reports[2].bounding_box, BoundingBox { top_left: Position { x: 2, y: 1 }, bottom_right: Position { x: 3, y: 3 } });
+	}
+
+	/// Tests holes are found and regions are nested under the innermost region surrounding them
+	#[test]
+	fn test_containment_tree() {
+		let nodes = containment_tree("AAAAA\nABBBA\nABCBA\nABBBA\nAAAAA\nDDDDD");
+		let plants = nodes.iter().map(|node| node.report.plant).collect::<Vec<_>>();
+		assert_eq!(plants, ['A', 'B', 'C', 'D']);
+		assert_eq!(nodes[0].holes.len(), 1);
+		assert_eq!(nodes[0].holes[0].len(), 9);
+		assert_eq!(nodes[1].holes, [[Position { x: 2, y: 2 }]]);
+		assert!(nodes[2].holes.is_empty() && nodes[3].holes.is_empty());
+		let tree = nodes.iter().map(|node| (node.parent, node.children.clone())).collect::<Vec<_>>();
+		assert_eq!(tree, [(None, vec![1]), (Some(0), vec![2]), (Some(1), vec![]), (None, vec![])]);
+
+		// The corner plot is outside the region, only the middle plot is a hole
+		let nodes = containment_tree("AAA\nA.A\nAA.");
+		assert_eq!(nodes[0].holes, [[Position { x: 1, y: 1 }]]);
+		assert_eq!(nodes.iter().map(|node| node.parent).collect::<Vec<_>>(), [None, Some(0), None]);
+	}
+
+	/// Tests diagonal plots only join into a region with eight way connectivity
+	#[test]
+	fn test_connectivity() {
+		let garden = "AB\nBA";
+		assert_eq!(Garden::from(garden).analyze().len(), 4);
+		let reports = Garden::from(garden).with_connectivity(Connectivity::Eight).analyze();
+		let summary = reports.iter().map(|report| (report.plant, report.area, report.perimeter)).collect::<Vec<_>>();
+		assert_eq!(summary, [('A', 2, 8), ('B', 2, 8)]);
+	}
+
+	/// Tests regions and fences continue across the edges of a wrapping garden
+	#[test]
+	fn test_wrapping() {
+		let garden = "ABA\nBBB\nCCC";
+		assert_eq!(Garden::from(garden).analyze().len(), 4);
+		let reports = Garden::from(garden).with_wrapping(true).analyze();
+		let summary = reports.iter().map(|report| (report.plant, report.area, report.perimeter, report.sides)).collect::<Vec<_>>();
+		// The A plots join across the edge, the C row loops around with one side above and one below, and the fence above
+		// the B row joins across the edge
+		assert_eq!(summary, [('A', 2, 6, 4), ('B', 4, 8, 5), ('C', 3, 6, 2)]);
+	}
+
+	/// Tests custom pricers see every region
+	#[test]
+	fn test_total_price() {
+		let garden = "AAAA\nBBCD\nBBCC\nEEEC";
+		assert_eq!(total_price(garden, perimeter_price), 140);
+		assert_eq!(total_price(garden, sides_price), 80);
+		assert_eq!(total_price(garden, |report| (report.perimeter * report.perimeter) as u64), 100 + 64 + 100 + 16 + 64);
+		assert_eq!(total_price(garden, |_| 1), 5);
+	}
+
+	/// Tests regions border each other symmetrically with the length of their shared fence
+	#[test]
+	fn test_adjacency() {
+		let borders = adjacency("AAAA\nBBCD\nBBCC\nEEEC");
+		let border = |region, length| RegionBorder { region, length };
+		// Regions in reading order are A, B, C, D, E
+		assert_eq!(borders[0], [border(1, 2), border(2, 1), border(3, 1)]);
+		assert_eq!(borders[1], [border(0, 2), border(2, 2), border(4, 2)]);
+		assert_eq!(borders[2], [border(0, 1), border(1, 2), border(3, 2), border(4, 2)]);
+		assert_eq!(borders[3], [border(0, 1), border(2, 2)]);
+		assert_eq!(borders[4], [border(1, 2), border(2, 2)]);
+	}
+
+	/// Tests region outlines have a corner per side and enclose the region's area, and export to GeoJSON
+	#[test]
+	fn test_polygons() {
+		let garden = Garden::from("RRRRIICCFF\nRRRRIICCCF\nVVRRRCCFFF\nVVRCCCJFFF\nVVVVCJJCFE\nVVIVCCJJEE\nVVIIICJJEE\nMIIIIIJJEE\nMIIISIJEEE\nMMMISSJEEE");
+		for region in garden.polygons().iter().chain(Garden::from("AAAAAA\nAAABBA\nAAABBA\nABBAAA\nABBAAA\nAAAAAA").polygons().iter()) {
+			let rings = region.polygons.iter().flatten();
+			assert_eq!(rings.clone().map(Vec::len).sum::<usize>(), region.report.sides);
+			assert_eq!(rings.map(|ring| signed_area(ring)).sum::<i64>(), region.report.area as i64);
+		}
+
+		let garden = Garden::from("OOO\nOXO\nOOO");
+		let polygons = garden.polygons();
+		assert_eq!(polygons[0].polygons, [[vec![(0, 0), (0, -3), (3, -3), (3, 0)], vec![(1, -1), (2, -1), (2, -2), (1, -2)]]]);
+		assert_eq!(polygons[1].polygons, [[vec![(1, -1), (1, -2), (2, -2), (2, -1)]]]);
+		assert!(garden.to_geojson().starts_with(r#"{"type":"FeatureCollection","features":[{"type":"Feature","properties":{"plant":"O","area":8,"perimeter":16,"sides":8},"geometry":{"type":"Polygon","coordinates":[[[0,0],[0,-3],[3,-3],[3,0],[0,0]],"#));
+	}
+
+	/// Tests validation reports every line breaking the garden's shape
+	#[test]
+	fn test_validate() {
+		assert!(validate("AAAA\nBBCD").is_empty());
+		assert_eq!(validate(""), [InputProblem::Empty]);
+		assert_eq!(validate("AAAA\nBBC\nBBCC\nEEEEE"), [
+			InputProblem::RaggedLine { line: 2, length: 3, expected: 4 },
+			InputProblem::RaggedLine { line: 4, length: 5, expected: 4 },
+		]);
+	}
+
+	/// Tests streaming a garden in bands of any height gives the same totals as loading it
+	#[test]
+	fn test_stream_totals() {
+		let example = "RRRRIICCFF\nRRRRIICCCF\nVVRRRCCFFF\nVVRCCCJFFF\nVVVVCJJCFE\nVVIVCCJJEE\nVVIIICJJEE\nMIIIIIJJEE\nMIIISIJEEE\nMMMISSJEEE";
+		for input in [example, "AAAAAA\nAAABBA\nAAABBA\nABBAAA\nABBAAA\nAAAAAA", "OOOOO\nOXOXO\nOOOOO\nOXOXO\nOOOOO", "ABA\nBAB\nABA"] {
+			let expected = GardenTotals {
+				regions: analyze(input).len(),
+				perimeter_price: part1_solution(input),
+				sides_price: part2_solution(input),
+			};
+			for band_height in [1, 2, 3, 100] {
+				assert_eq!(stream_totals(input.as_bytes(), band_height).unwrap(), expected);
+			}
+		}
+		assert!(matches!(stream_totals("AAAA\nBBC".as_bytes(), 1), Err(StreamError::RaggedLine { line: 2, length: 3, expected: 4 })));
+	}
+
 }
@@ -0,0 +1,177 @@
+//! Pricing gardens too large to hold in memory by streaming them a row at a time.
+
+use std::{fmt::{self, Display, Formatter}, io::{self, BufRead}};
+
+/// Running measurements of part of a region. Parts which turn out to be the same region have their measurements summed.
+#[derive(Debug, Clone, Copy, Default)]
+struct Fragment {
+	area: usize,
+	perimeter: usize,
+	/// Every corner of the region's outline, which is the same as its number of sides.
+	corners: usize,
+}
+
+/// A union-find of region fragments, where each set's root holds the measurements of the whole set.
+#[derive(Debug, Default)]
+struct Fragments {
+	parent: Vec<usize>,
+	measurements: Vec<Fragment>,
+}
+
+impl Fragments {
+	/// Starts a new fragment, returning its label.
+	fn add(&mut self) -> usize {
+		self.parent.push(self.parent.len());
+		self.measurements.push(Fragment::default());
+		self.parent.len() - 1
+	}
+
+	/// Finds the label of the root of a fragment's set.
+	fn find(&mut self, mut label: usize) -> usize {
+		while self.parent[label] != label {
+			self.parent[label] = self.parent[self.parent[label]];
+			label = self.parent[label];
+		}
+		label
+	}
+
+	/// Joins the sets of two fragments, returning the label of the joined set's root.
+	fn union(&mut self, a: usize, b: usize) -> usize {
+		let (a, b) = (self.find(a), self.find(b));
+		if a == b { return a }
+		self.parent[b] = a;
+		let other = self.measurements[b];
+		let root = &mut self.measurements[a];
+		root.area += other.area;
+		root.perimeter += other.perimeter;
+		root.corners += other.corners;
+		a
+	}
+
+	/// The measurements of the set a fragment is in.
+	fn measurements(&mut self, label: usize) -> &mut Fragment {
+		let root = self.find(label);
+		&mut self.measurements[root]
+	}
+
+	/// Adds every set without a fragment in `live` to the totals, as its region can't grow any further, then forgets
+	/// them. The remaining sets are relabelled from 0, returning the new labels of `live`.
+	fn retire(&mut self, live: &[usize], totals: &mut GardenTotals) -> Vec<usize> {
+		// The new label of each live root, indexed by its old label
+		let mut relabelled = vec![None; self.parent.len()];
+		let mut measurements = Vec::new();
+		let labels = live.iter().map(|&label| {
+			let root = self.find(label);
+			*relabelled[root].get_or_insert_with(|| { measurements.push(self.measurements[root]); measurements.len() - 1 })
+		}).collect();
+		for (label, relabel) in relabelled.iter().enumerate() {
+			if self.parent[label] != label || relabel.is_some() { continue }
+			let Fragment { area, perimeter, corners } = self.measurements[label];
+			totals.regions += 1;
+			totals.perimeter_price += (area * perimeter) as u64;
+			totals.sides_price += (area * corners) as u64;
+		}
+		self.parent = (0..measurements.len()).collect();
+		self.measurements = measurements;
+		labels
+	}
+}
+
+/// The number of regions in a garden and its total prices.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GardenTotals {
+	pub regions: usize,
+	/// The sum of every region's area multiplied by its perimeter, as in part 1.
+	pub perimeter_price: u64,
+	/// The sum of every region's area multiplied by its number of sides, as in part 2.
+	pub sides_price: u64,
+}
+
+/// Possible errors when streaming a garden. Lines count from 1.
+#[derive(Debug)]
+pub enum StreamError {
+	/// A line couldn't be read.
+	Read { line: usize, error: io::Error },
+	/// A line isn't as long as the first, so the garden isn't rectangular.
+	RaggedLine { line: usize, length: usize, expected: usize },
+}
+
+impl Display for StreamError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		match self {
+			StreamError::Read { line, error } => write!(f, "Failed to read line {line}: {error}"),
+			StreamError::RaggedLine { line, length, expected } => {
+				write!(f, "Line {line} is {length} long, expected {expected} like the first line")
+			},
+		}
+	}
+}
+
+/// Counts the corners where the plots of one row meet the plots of the row below, adding each to the fragment of the
+/// plot it belongs to. Either row may be empty at the top or bottom of the garden.
+fn count_corners(above: &[char], above_labels: &[usize], below: &[char], below_labels: &[usize], fragments: &mut Fragments) {
+	let width = above.len().max(below.len());
+	for x in 0..=width {
+		// The four plots around the point, any of which may be outside the garden
+		let plot = |row: &[char], x: Option<usize>| x.and_then(|x| row.get(x)).copied();
+		let (left, right) = (x.checked_sub(1), Some(x));
+		let window = [
+			(plot(above, left), above_labels, left),
+			(plot(above, right), above_labels, right),
+			(plot(below, left), below_labels, left),
+			(plot(below, right), below_labels, right),
+		];
+
+		// A plot has a corner here if neither plot beside it around the point is the same plant, or both are and the
+		// plot diagonally opposite isn't
+		for (idx, &(plant, labels, x)) in window.iter().enumerate() {
+			let (Some(plant), Some(x)) = (plant, x) else { continue };
+			let (horizontal, vertical, diagonal) = (window[idx ^ 1].0, window[idx ^ 2].0, window[idx ^ 3].0);
+			let convex = horizontal != Some(plant) && vertical != Some(plant);
+			let concave = horizontal == Some(plant) && vertical == Some(plant) && diagonal != Some(plant);
+			if convex || concave { fragments.measurements(labels[x]).corners += 1; }
+		}
+	}
+}
+
+/// Measures a garden read a row at a time, so it never has to fit in memory. Each row's plots are joined to the same
+/// plant to their left and above as fragments of regions, which are merged with union-find when they meet. At the end
+/// of every band of `band_height` rows, regions which didn't reach the band's last row are finished, so they're totalled
+/// and forgotten. Memory is bounded by the width of the garden and the height of a band, not the size of the garden.
+/// Regions are joined by the sides of plots as in the puzzle, and the garden doesn't wrap.
+pub fn stream_totals<R: BufRead>(reader: R, band_height: usize) -> Result<GardenTotals, StreamError> {
+	let mut totals = GardenTotals::default();
+	let mut fragments = Fragments::default();
+	let (mut previous, mut previous_labels) = (Vec::new(), Vec::new());
+	let mut width = None;
+	for (idx, line) in reader.lines().enumerate() {
+		let row = line.map_err(|error| StreamError::Read { line: idx + 1, error })?.chars().collect::<Vec<char>>();
+		let expected = *width.get_or_insert(row.len());
+		if row.len() != expected { return Err(StreamError::RaggedLine { line: idx + 1, length: row.len(), expected }) }
+
+		let mut labels: Vec<usize> = Vec::with_capacity(row.len());
+		for (x, &plant) in row.iter().enumerate() {
+			let left = (x > 0 && row[x - 1] == plant).then(|| labels[x - 1]);
+			let above = (previous.get(x) == Some(&plant)).then(|| previous_labels[x]);
+			let label = match (left, above) {
+				(Some(left), Some(above)) => fragments.union(left, above),
+				(Some(label), None) | (None, Some(label)) => label,
+				(None, None) => fragments.add(),
+			};
+
+			// Each side shared with the plot to the left or above takes a fence away from both plots
+			let shared = usize::from(left.is_some()) + usize::from(above.is_some());
+			let measurements = fragments.measurements(label);
+			measurements.area += 1;
+			measurements.perimeter += 4 - 2 * shared;
+			labels.push(label);
+		}
+
+		count_corners(&previous, &previous_labels, &row, &labels, &mut fragments);
+		(previous, previous_labels) = (row, labels);
+		if (idx + 1) % band_height.max(1) == 0 { previous_labels = fragments.retire(&previous_labels, &mut totals); }
+	}
+	count_corners(&previous, &previous_labels, &[], &[], &mut fragments);
+	fragments.retire(&[], &mut totals);
+	Ok(totals)
+}
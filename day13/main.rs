@@ -1,61 +1,199 @@
-use std::num::ParseIntError;
+use std::{error::Error, fmt::{self, Display, Formatter}, num::ParseIntError};
+
+use common::report::report;
+use num_rational::Ratio;
 use regex::Regex;
 
 /// Represents a direction vector
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-struct Vector2 { x: i64, y: i64 }
+pub struct Vector2 { pub x: i64, pub y: i64 }
 
 impl Vector2 {
 	/// Creates a new Vector2
 	fn new(x: i64, y: i64) -> Self {
 		Self { x, y }
 	}
+
+	/// Gets this vector minus `other` scaled by `times`
+	fn minus_times(self, other: Vector2, times: usize) -> Self {
+		Self { x: self.x - other.x * times as i64, y: self.y - other.y * times as i64 }
+	}
 }
 
-/// Describes a single slot machine - the change in position by button A, B, and the prize location.
-#[derive(Debug)]
-struct SlotMachine {
-	button_a: Vector2,
-	button_b: Vector2,
-	prize: Vector2,
+/// A button on a slot machine, how far it moves the claw and how many tokens pressing it costs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Button {
+	pub movement: Vector2,
+	pub cost: usize,
+}
+
+/// Describes a single slot machine - the buttons moving the claw, and the prize location.
+#[derive(Debug, Clone)]
+pub struct SlotMachine {
+	pub buttons: Vec<Button>,
+	pub prize: Vector2,
+}
+
+/// The presses of button A and B winning a two button machine, or why it can't be won.
+pub type TwoButtonPresses = Result<(usize, usize), UnsolvableReason>;
+
+/// How the presses of a two button machine are solved for, each should give the same result.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Arithmetic {
+	/// Cramer's rule with integer division, checking the remainders.
+	#[default]
+	Integer,
+	/// Cramer's rule with floating point division, rounding and checking the rounded presses reach the prize.
+	Float,
+	/// Gaussian elimination with exact fractions, only checking the presses are whole at the end.
+	Rational,
+}
+
+impl Arithmetic {
+	/// Every way of solving for the presses.
+	pub const ALL: [Arithmetic; 3] = [Arithmetic::Integer, Arithmetic::Float, Arithmetic::Rational];
+
+	/// Solves `a * presses_a + b * presses_b = prize` for the presses of two buttons.
+	pub fn solve(self, a: Vector2, b: Vector2, prize: Vector2) -> TwoButtonPresses {
+		let (presses_a, presses_b) = match self {
+			Arithmetic::Integer => {
+				let determinant = a.x * b.y - b.x * a.y;
+				if determinant == 0 { return Err(UnsolvableReason::Singular); }
+
+				let (a_numerator, b_numerator) = (prize.x * b.y - b.x * prize.y, a.x * prize.y - prize.x * a.y);
+				if a_numerator % determinant != 0 || b_numerator % determinant != 0 { return Err(UnsolvableReason::NonInteger); }
+				(a_numerator / determinant, b_numerator / determinant)
+			},
+			Arithmetic::Float => {
+				let determinant = (a.x * b.y - b.x * a.y) as f64;
+				if determinant == 0.0 { return Err(UnsolvableReason::Singular); }
+
+				let presses_a = ((prize.x * b.y - b.x * prize.y) as f64 / determinant).round() as i64;
+				let presses_b = ((a.x * prize.y - prize.x * a.y) as f64 / determinant).round() as i64;
+				if a.x * presses_a + b.x * presses_b != prize.x || a.y * presses_a + b.y * presses_b != prize.y {
+					return Err(UnsolvableReason::NonInteger);
+				}
+				(presses_a, presses_b)
+			},
+			Arithmetic::Rational => {
+				let ratio = |value: i64| Ratio::from_integer(value as i128);
+				let mut rows = [[ratio(a.x), ratio(b.x), ratio(prize.x)], [ratio(a.y), ratio(b.y), ratio(prize.y)]];
+				if rows[0][0] == ratio(0) { rows.swap(0, 1); }
+				if rows[0][0] == ratio(0) { return Err(UnsolvableReason::Singular); }
+
+				// Eliminate button A from the second row, leaving only button B
+				let factor = rows[1][0] / rows[0][0];
+				let [first, second] = &mut rows;
+				second.iter_mut().zip(first.iter()).for_each(|(value, above)| *value -= factor * above);
+				if rows[1][1] == ratio(0) { return Err(UnsolvableReason::Singular); }
+
+				let presses_b = rows[1][2] / rows[1][1];
+				let presses_a = (rows[0][2] - rows[0][1] * presses_b) / rows[0][0];
+				if !presses_a.is_integer() || !presses_b.is_integer() { return Err(UnsolvableReason::NonInteger); }
+				(presses_a.to_integer() as i64, presses_b.to_integer() as i64)
+			},
+		};
+		if presses_a < 0 || presses_b < 0 { return Err(UnsolvableReason::NegativePresses); }
+		Ok((presses_a as usize, presses_b as usize))
+	}
+}
+
+/// Solves `a * presses_a + b * presses_b = prize` for the presses of two buttons with Cramer's rule.
+fn solve_two_buttons(a: Vector2, b: Vector2, prize: Vector2) -> TwoButtonPresses {
+	Arithmetic::Integer.solve(a, b, prize)
 }
 
 impl SlotMachine {
-	/// Calculates the button presses needed on button A, and B to achieve the prize
-	fn calculate_presses(&self) -> Option<(usize, usize)> {
-		// System of linear equations:
-		// self.button_a.x * a + self.button_b.x * b = self.prize.x
-		// self.button_a.y * a + self.button_b.y * b = self.prize.y
-
-		if self.button_b.x == 0 { return None; }
-
-		let (ax, ay, bx, by, px, py) = (
-			self.button_a.x as f64, self.button_a.y as f64,
-			self.button_b.x as f64, self.button_b.y as f64,
-			self.prize.x as f64, self.prize.y as f64,
-		);
+	/// Calculates the presses of each button winning the prize with the fewest tokens, or why it can't be won. Machines
+	/// with two buttons are solved directly unless the buttons move in parallel, any other machine is searched within
+	/// the press cap, or within how far the claw can go before passing the prize when no button moves it backwards.
+	fn calculate_presses(&self, press_cap: Option<usize>) -> Result<Vec<usize>, UnsolvableReason> {
+		// System of linear equations, solved with Cramer's rule:
+		// button_a.x * a + button_b.x * b = prize.x
+		// button_a.y * a + button_b.y * b = prize.y
+		if let [button_a, button_b] = self.buttons.as_slice() {
+			match solve_two_buttons(button_a.movement, button_b.movement, self.prize) {
+				Ok((a, b)) if press_cap.is_some_and(|cap| a > cap || b > cap) => return Err(UnsolvableReason::ExceedsPressCap),
+				Ok((a, b)) => return Ok(vec![a, b]),
+				// Parallel buttons may still win with several combinations of presses, which are searched below
+				Err(UnsolvableReason::Singular) => {},
+				Err(reason) => return Err(reason),
+			}
+		}
+
+		let bounds = self.buttons.iter()
+			.map(|button| self.press_bound(button.movement, press_cap))
+			.collect::<Option<Vec<_>>>()
+			.ok_or(UnsolvableReason::Unbounded)?;
+		let mut best = None;
+		self.search_presses(&bounds, &mut Vec::new(), self.prize, &mut best);
+		best.map(|(_, presses)| presses).ok_or(UnsolvableReason::NoCombination)
+	}
+
+	/// Gets the most times a button can be pressed, or None if it could be pressed without limit.
+	fn press_bound(&self, movement: Vector2, press_cap: Option<usize>) -> Option<usize> {
+		// Along an axis no button moves backwards on, the claw can't go past the prize
+		let forward_x = self.buttons.iter().all(|button| button.movement.x >= 0);
+		let forward_y = self.buttons.iter().all(|button| button.movement.y >= 0);
+		[
+			press_cap,
+			(forward_x && movement.x > 0).then(|| self.prize.x.max(0) as usize / movement.x as usize),
+			(forward_y && movement.y > 0).then(|| self.prize.y.max(0) as usize / movement.y as usize),
+		].into_iter().flatten().min()
+	}
+
+	/// Tries every number of presses of each button in turn, solving the last two directly when possible, and keeps the
+	/// cheapest presses reaching the prize in `best` as `(tokens, presses)`.
+	fn search_presses(&self, bounds: &[usize], presses: &mut Vec<usize>, remaining: Vector2, best: &mut Option<(usize, Vec<usize>)>) {
+		let buttons = &self.buttons[presses.len()..];
+		let mut found = |presses: Vec<usize>| {
+			if presses.iter().zip(bounds).any(|(count, bound)| count > bound) { return; }
+			let tokens = presses.iter().zip(&self.buttons).map(|(count, button)| count * button.cost).sum::<usize>();
+			if best.as_ref().is_none_or(|(best_tokens, _)| tokens < *best_tokens) { *best = Some((tokens, presses)); }
+		};
 
-		let a_denom = ay - by * ax / bx;
-		let a = (py - by * px / bx) / a_denom;
-		let b = (px - ax * a) / bx;
+		match buttons {
+			[] => if remaining == Vector2::new(0, 0) { found(presses.clone()) },
+			[a, b] => match solve_two_buttons(a.movement, b.movement, remaining) {
+				Ok((a, b)) => found([presses.as_slice(), &[a, b]].concat()),
+				Err(UnsolvableReason::Singular) => self.search_each(bounds, presses, remaining, best),
+				Err(_) => {},
+			},
+			_ => self.search_each(bounds, presses, remaining, best),
+		}
+	}
 
-		let (a, b) = (a.round() as i64, b.round() as i64);
-		if self.button_a.x * a + self.button_b.x * b != self.prize.x ||
-			self.button_a.y * a + self.button_b.y * b != self.prize.y { return None }
-		Some((usize::try_from(a).ok()?, usize::try_from(b).ok()?))
+	/// Tries every number of presses of the next button, searching the rest of the buttons for each.
+	fn search_each(&self, bounds: &[usize], presses: &mut Vec<usize>, remaining: Vector2, best: &mut Option<(usize, Vec<usize>)>) {
+		let button = self.buttons[presses.len()];
+		for count in 0..=bounds[presses.len()] {
+			presses.push(count);
+			self.search_presses(bounds, presses, remaining.minus_times(button.movement, count), best);
+			presses.pop();
+		}
 	}
 }
 
 /// Possible errors when parsing a slot machine values
 #[derive(Debug)]
-enum SlotMachineParseError {
-	#[allow(dead_code)]
+pub enum SlotMachineParseError {
 	RegexParseError(regex::Error),
-	#[allow(dead_code)]
 	IntegerParseError { value: String, error: ParseIntError },
 	InvalidVectorCount,
 }
 
+impl Display for SlotMachineParseError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		match self {
+			SlotMachineParseError::RegexParseError(error) => write!(f, "Invalid machine pattern, {error}"),
+			SlotMachineParseError::IntegerParseError { value, error } => write!(f, "Invalid number '{value}', {error}"),
+			SlotMachineParseError::InvalidVectorCount => write!(f, "Expected one or more buttons followed by a prize"),
+		}
+	}
+}
+
+impl Error for SlotMachineParseError {}
+
 impl TryFrom<&str> for SlotMachine {
     type Error = SlotMachineParseError;
 
@@ -65,18 +203,22 @@ impl TryFrom<&str> for SlotMachine {
 	/// Button B: X+22, Y+67
 	/// Prize: X=8400, Y=5400
 	/// ```
+	/// With any number of buttons, which may end with their cost as `, Cost=2`. Otherwise the first button costs 3
+	/// tokens and the rest cost 1.
     fn try_from(value: &str) -> Result<Self, SlotMachineParseError> {
-		let regex = Regex::new("X=?([+-]?[0-9]+), Y=?([+-]?[0-9]+)").map_err(SlotMachineParseError::RegexParseError)?;
-		let vectors = regex.captures_iter(value).map(|capture| -> Result<Vector2, SlotMachineParseError> {
-			let (_, [x, y]) = capture.extract();
-			let (x, y) = (
-				x.parse::<i64>().map_err(|error| SlotMachineParseError::IntegerParseError { value: String::from(x), error })?,
-				y.parse::<i64>().map_err(|error| SlotMachineParseError::IntegerParseError { value: String::from(y), error })?,
-			);
-			Ok(Vector2::new(x, y))
+		let regex = Regex::new("X=?([+-]?[0-9]+), Y=?([+-]?[0-9]+)(?:, Cost=([0-9]+))?").map_err(SlotMachineParseError::RegexParseError)?;
+		let parse = |value: &str| value.parse::<i64>()
+			.map_err(|error| SlotMachineParseError::IntegerParseError { value: String::from(value), error });
+		let mut vectors = regex.captures_iter(value).map(|capture| -> Result<(Vector2, Option<usize>), SlotMachineParseError> {
+			let cost = capture.get(3).map(|cost| parse(cost.as_str())).transpose()?;
+			Ok((Vector2::new(parse(&capture[1])?, parse(&capture[2])?), cost.map(|cost| cost as usize)))
 		}).collect::<Result<Vec<_>, _>>()?;
-		let [button_a, button_b, prize] = vectors.as_slice() else { return Err(SlotMachineParseError::InvalidVectorCount) };
-		Ok(Self { button_a: *button_a, button_b: *button_b, prize: *prize })
+		let Some((prize, None)) = vectors.pop() else { return Err(SlotMachineParseError::InvalidVectorCount) };
+		if vectors.is_empty() { return Err(SlotMachineParseError::InvalidVectorCount) }
+		let buttons = vectors.into_iter().enumerate()
+			.map(|(idx, (movement, cost))| Button { movement, cost: cost.unwrap_or(if idx == 0 { 3 } else { 1 }) })
+			.collect();
+		Ok(Self { buttons, prize })
     }
 }
 
@@ -85,23 +227,144 @@ fn parse_slot_machines(input: &str) -> Result<Vec<SlotMachine>, SlotMachineParse
 	input.split("\n\n").map(SlotMachine::try_from).collect()
 }
 
+/// Why a slot machine can't be won.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnsolvableReason {
+	/// The buttons move the claw in parallel directions, so there isn't a single solution. Only returned by
+	/// `Arithmetic::solve`, machines with parallel buttons are searched instead.
+	Singular,
+	/// The only solution needs a fractional number of presses.
+	NonInteger,
+	/// The only solution needs a negative number of presses.
+	NegativePresses,
+	/// The only solution needs more presses of a button than allowed.
+	ExceedsPressCap,
+	/// A button could be pressed any number of times, so there's no limit to search presses within.
+	Unbounded,
+	/// No combination of presses within the limits reaches the prize.
+	NoCombination,
+}
+
+/// Whether a slot machine can be won, and how.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MachineStatus {
+	/// The prize is won by pressing each button the number of times in `presses`, costing `tokens`.
+	Won { presses: Vec<usize>, tokens: usize },
+	/// No combination of presses wins the prize. Machines with parallel buttons are searched, so they're never
+	/// unsolvable because of `UnsolvableReason::Singular`.
+	Unsolvable(UnsolvableReason),
+}
+
+/// The outcome of a single slot machine.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MachineSolution {
+	/// The index of the machine in the input.
+	pub index: usize,
+	pub status: MachineStatus,
+}
+
+impl MachineSolution {
+	/// Gets the tokens needed to win the machine, or 0 if it can't be won.
+	pub fn tokens(&self) -> usize {
+		match self.status {
+			MachineStatus::Won { tokens, .. } => tokens,
+			MachineStatus::Unsolvable(_) => 0,
+		}
+	}
+}
+
+/// Solves every machine, treating those needing more than `press_cap` presses of either button as unsolvable.
+fn solve_all(machines: &[SlotMachine], press_cap: Option<usize>) -> Vec<MachineSolution> {
+	machines.iter().enumerate().map(|(index, machine)| {
+		let status = match machine.calculate_presses(press_cap) {
+			Ok(presses) => {
+				let tokens = presses.iter().zip(&machine.buttons).map(|(count, button)| count * button.cost).sum();
+				MachineStatus::Won { presses, tokens }
+			},
+			Err(reason) => MachineStatus::Unsolvable(reason),
+		};
+		MachineSolution { index, status }
+	}).collect()
+}
+
+/// How far the prizes are moved, and how many presses of each button are allowed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PrizeRules {
+	/// Added to both coordinates of every prize.
+	pub prize_offset: i64,
+	/// The most times each button may be pressed, if limited.
+	pub press_cap: Option<usize>,
+}
+
+impl PrizeRules {
+	/// The rules for part 1, prizes where they're listed and at most 100 presses per button.
+	pub const PART1: PrizeRules = PrizeRules { prize_offset: 0, press_cap: Some(100) };
+	/// The rules for part 2, prizes 10000000000000 further along both axes and unlimited presses.
+	pub const PART2: PrizeRules = PrizeRules { prize_offset: 10000000000000, press_cap: None };
+}
+
+impl Default for PrizeRules {
+	fn default() -> Self {
+		Self::PART1
+	}
+}
+
+/// Solves every slot machine with the part 1 rules, giving the presses and tokens needed for each.
+pub fn solve_machines(input: &str) -> Result<Vec<MachineSolution>, SlotMachineParseError> {
+	solve_machines_with(input, PrizeRules::PART1)
+}
+
+/// Solves every slot machine with the given rules, giving the presses and tokens needed for each.
+pub fn solve_machines_with(input: &str, rules: PrizeRules) -> Result<Vec<MachineSolution>, SlotMachineParseError> {
+	let mut machines = parse_slot_machines(input)?;
+	for machine in &mut machines { machine.prize.x += rules.prize_offset; machine.prize.y += rules.prize_offset; }
+	Ok(solve_all(&machines, rules.press_cap))
+}
+
+/// Calculates the tokens needed to win all given slot machines which can be won with the given rules.
+pub fn total_tokens(input: &str, rules: PrizeRules) -> Result<usize, SlotMachineParseError> {
+	Ok(solve_machines_with(input, rules)?.iter().map(MachineSolution::tokens).sum())
+}
+
+/// A two button machine which the ways of solving for presses disagree on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArithmeticDiscrepancy {
+	/// The index of the machine in the input.
+	pub index: usize,
+	/// The prize location solved for, which is offset for part 2.
+	pub prize: Vector2,
+	pub results: Vec<(Arithmetic, TwoButtonPresses)>,
+}
+
+/// Solves every two button machine with each kind of arithmetic, for both the part 1 and part 2 prize locations, and
+/// returns those where they disagree.
+pub fn compare_arithmetic(input: &str) -> Result<Vec<ArithmeticDiscrepancy>, SlotMachineParseError> {
+	let machines = parse_slot_machines(input)?;
+	Ok(machines.iter().enumerate()
+		.filter_map(|(index, machine)| match machine.buttons.as_slice() {
+			[a, b] => Some((index, a.movement, b.movement, machine.prize)),
+			_ => None,
+		})
+		.flat_map(|(index, a, b, prize)| {
+			let offset = PrizeRules::PART2.prize_offset;
+			[prize, Vector2::new(prize.x + offset, prize.y + offset)].map(|prize| ArithmeticDiscrepancy {
+				index,
+				prize,
+				results: Arithmetic::ALL.into_iter().map(|arithmetic| (arithmetic, arithmetic.solve(a, b, prize))).collect(),
+			})
+		})
+		.filter(|discrepancy| discrepancy.results.windows(2).any(|pair| pair[0].1 != pair[1].1))
+		.collect())
+}
+
 /// Calculates the tokens needed to win all given slot machines
 fn part1_solution(input: &str) -> Result<usize, SlotMachineParseError> {
-	let machines = parse_slot_machines(input)?;
-	Ok(machines.iter()
-		.flat_map(|machine| machine.calculate_presses())
-		.filter_map(|(a, b)| (a <= 100 && b <= 100).then_some(a * 3 + b))
-		.sum())
+	total_tokens(input, PrizeRules::PART1)
 }
 
 /// Calculates the tokens needed to win all given slot machines when the prize location is +10000000000000
 fn part2_solution(input: &str) -> Result<usize, SlotMachineParseError> {
-	let mut machines = parse_slot_machines(input)?;
-	for machine in &mut machines { machine.prize.x += 10000000000000i64; machine.prize.y += 10000000000000i64; }
-	Ok(machines.iter()
-		.flat_map(|machine| machine.calculate_presses())
-		.map(|(a, b)| a * 3 + b)
-		.sum())
+	total_tokens(input, PrizeRules::PART2)
 }
 
 /// Entry point
@@ -121,11 +384,144 @@ Prize: X=7870, Y=6450
 Button A: X+69, Y+23
 Button B: X+27, Y+71
 Prize: X=18641, Y=10279";
-	let input = include_str!("day13.txt");
+	// Solve another input instead of the bundled one with `--file <path>`
+	let args = std::env::args().collect::<Vec<_>>();
+	let file = args.iter().skip_while(|&arg| arg != "--file").nth(1)
+		.map(|path| std::fs::read_to_string(path).expect("Failed to read input file"));
+	let input = file.as_deref().unwrap_or(include_str!("day13.txt"));
 
-	println!("Part 1 Solution on Example: {:#?}", part1_solution(example));
-	println!("Part 1 Solution on Input: {:#?}", part1_solution(input));
+	// Print how each machine is won with the part 1 rules, add `--example` to use the example input, and `--offset N`
+	// or `--cap N` (or `--cap none`) to change the rules
+	let selected = if args.iter().any(|arg| arg == "--example") { example } else { input };
+	let mut rules = PrizeRules::PART1;
+	if let Some(offset) = args.iter().skip_while(|&arg| arg != "--offset").nth(1) {
+		rules.prize_offset = offset.parse().expect("Offset should be an integer");
+	}
+	if let Some(cap) = args.iter().skip_while(|&arg| arg != "--cap").nth(1) {
+		rules.press_cap = (cap != "none").then(|| cap.parse().expect("Press cap should be a positive integer or none"));
+	}
+	if args.iter().any(|arg| arg == "--machines") {
+		let solutions = solve_machines_with(selected, rules);
+		for solution in solutions.expect("Input should parse") {
+			match solution.status {
+				MachineStatus::Won { presses, tokens } => {
+					let presses = presses.iter().enumerate()
+						.map(|(idx, count)| format!("{} x{count}", char::from(b'A' + idx as u8)))
+						.collect::<Vec<_>>();
+					println!("Machine {}: {}, {tokens} tokens", solution.index, presses.join(", "));
+				},
+				MachineStatus::Unsolvable(reason) => println!("Machine {}: unsolvable, {reason:?}", solution.index),
+			}
+		}
+		return;
+	}
+
+	// Cross-check the integer, floating point, and exact fraction solvers against each other
+	if args.iter().any(|arg| arg == "--compare") {
+		let discrepancies = compare_arithmetic(selected);
+		let discrepancies = discrepancies.expect("Input should parse");
+		for discrepancy in &discrepancies {
+			println!("Machine {} with prize at ({}, {}):", discrepancy.index, discrepancy.prize.x, discrepancy.prize.y);
+			for (arithmetic, result) in &discrepancy.results { println!("\t{arithmetic:?}: {result:?}"); }
+		}
+		println!("{} discrepancies", discrepancies.len());
+		return;
+	}
+
+	// Total the tokens with custom rules
+	if args.iter().any(|arg| arg == "--offset" || arg == "--cap") {
+		println!("Tokens needed: {}", total_tokens(selected, rules).expect("Input should parse"));
+		return;
+	}
+
+	println!("Part 1 Solution on Example: {}", report(part1_solution(example), "solving day 13 part 1"));
+	println!("Part 1 Solution on Input: {}", report(part1_solution(input), "solving day 13 part 1"));
+
+	println!("Part 2 Solution on Example: {}", report(part2_solution(example), "solving day 13 part 2"));
+	println!("Part 2 Solution on Input: {}", report(part2_solution(input), "solving day 13 part 2"));
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const EXAMPLE: &str = "Button A: X+94, Y+34
+Button B: X+22, Y+67
+Prize: X=8400, Y=5400
+
+Button A: X+26, Y+66
+Button B: X+67, Y+21
+Prize: X=12748, Y=12176
+
+Button A: X+17, Y+86
+Button B: X+84, Y+37
+Prize: X=7870, Y=6450
+
+Button A: X+69, Y+23
+Button B: X+27, Y+71
+Prize: X=18641, Y=10279";
+
+	/// Tests each example machine matches the puzzle text
+	#[test]
+	fn test_solve_machines() {
+		let statuses = solve_machines(EXAMPLE).unwrap().into_iter().map(|solution| solution.status).collect::<Vec<_>>();
+		assert_eq!(statuses, [
+			MachineStatus::Won { presses: vec![80, 40], tokens: 280 },
+			MachineStatus::Unsolvable(UnsolvableReason::NonInteger),
+			MachineStatus::Won { presses: vec![38, 86], tokens: 200 },
+			MachineStatus::Unsolvable(UnsolvableReason::NonInteger),
+		]);
+	}
+
+	/// Tests each reason a machine can't be won is reported
+	#[test]
+	fn test_unsolvable_reasons() {
+		let status = |machine: &str| solve_machines(machine).unwrap()[0].status.clone();
+		assert_eq!(status("Button A: X+1, Y+1\nButton B: X+2, Y+2\nPrize: X=3, Y=3"), MachineStatus::Won { presses: vec![1, 1], tokens: 4 });
+		assert_eq!(status("Button A: X+2, Y+2\nButton B: X+4, Y+4\nPrize: X=3, Y=3"), MachineStatus::Unsolvable(UnsolvableReason::NoCombination));
+		assert_eq!(status("Button A: X+2, Y+0\nButton B: X+0, Y+2\nPrize: X=3, Y=4"), MachineStatus::Unsolvable(UnsolvableReason::NonInteger));
+		assert_eq!(status("Button A: X+1, Y+0\nButton B: X+0, Y+1\nPrize: X=-3, Y=4"), MachineStatus::Unsolvable(UnsolvableReason::NegativePresses));
+		assert_eq!(status("Button A: X+1, Y+0\nButton B: X+0, Y+1\nPrize: X=300, Y=4"), MachineStatus::Unsolvable(UnsolvableReason::ExceedsPressCap));
+	}
+
+	/// Tests machines with other numbers of buttons find the cheapest presses
+	#[test]
+	fn test_any_button_count() {
+		let status = |machine: &str| solve_machines(machine).unwrap()[0].status.clone();
+		assert_eq!(status("Button A: X+2, Y+3\nPrize: X=10, Y=15"), MachineStatus::Won { presses: vec![5], tokens: 15 });
+		assert_eq!(
+			status("Button A: X+1, Y+1\nButton B: X+3, Y+0\nButton C: X+0, Y+3, Cost=2\nPrize: X=7, Y=4"),
+			MachineStatus::Won { presses: vec![1, 2, 1], tokens: 7 },
+		);
+		assert_eq!(
+			status("Button A: X+2, Y+0, Cost=1\nButton B: X+0, Y+2\nButton C: X+2, Y+2\nPrize: X=3, Y=3"),
+			MachineStatus::Unsolvable(UnsolvableReason::NoCombination),
+		);
+		let backwards = SlotMachine::try_from("Button A: X+1, Y+0\nButton B: X-1, Y+1\nButton C: X+0, Y-1\nPrize: X=1, Y=1").unwrap();
+		assert_eq!(backwards.calculate_presses(Some(100)), Ok(vec![2, 1, 0]));
+		assert_eq!(backwards.calculate_presses(None), Err(UnsolvableReason::Unbounded));
+	}
+
+	/// Tests each kind of arithmetic agrees, including on the part 2 prizes and machines needing a row swap
+	#[test]
+	fn test_compare_arithmetic() {
+		assert!(compare_arithmetic(EXAMPLE).unwrap().is_empty());
+		for arithmetic in Arithmetic::ALL {
+			assert_eq!(arithmetic.solve(Vector2::new(0, 3), Vector2::new(2, 1), Vector2::new(4, 5)), Ok((1, 2)));
+			assert_eq!(arithmetic.solve(Vector2::new(1, 2), Vector2::new(2, 4), Vector2::new(3, 6)), Err(UnsolvableReason::Singular));
+			assert_eq!(arithmetic.solve(Vector2::new(2, 0), Vector2::new(0, 2), Vector2::new(3, 4)), Err(UnsolvableReason::NonInteger));
+			assert_eq!(arithmetic.solve(Vector2::new(1, 0), Vector2::new(0, 1), Vector2::new(-3, 4)), Err(UnsolvableReason::NegativePresses));
+		}
+	}
+
+	/// Tests the prize offset and press cap can be changed
+	#[test]
+	fn test_prize_rules() {
+		assert_eq!(total_tokens(EXAMPLE, PrizeRules::default()).unwrap(), 480);
+		assert_eq!(total_tokens(EXAMPLE, PrizeRules { press_cap: Some(80), ..PrizeRules::PART1 }).unwrap(), 280);
+		let part2 = solve_machines_with(EXAMPLE, PrizeRules::PART2).unwrap();
+		assert_eq!(part2.iter().map(|solution| solution.tokens() > 0).collect::<Vec<_>>(), [false, true, false, true]);
+		assert_eq!(total_tokens(EXAMPLE, PrizeRules { prize_offset: 0, press_cap: None }).unwrap(), 480);
+	}
 
-	println!("Part 2 Solution on Example: {:#?}", part2_solution(example));
-	println!("Part 2 Solution on Input: {:#?}", part2_solution(input));
 }
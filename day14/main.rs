@@ -1,31 +1,53 @@
-use std::{cmp, fmt::{self, Display, Formatter}, num::ParseIntError};
+use std::{cmp, error::Error, fmt::{self, Display, Formatter}, fs::File, io::{self, BufWriter, Write}, num::ParseIntError, ops::Range, time::Duration};
+
+use common::{cancel::CancelToken, debug::debug, report::report, validate::validate_file};
 
 /// Represents a 2d direction vector
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-struct Vec2 { x: i32, y: i32 }
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Vec2 { pub x: i32, pub y: i32 }
 
 /// A bounding box containing a section of space
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-struct Bounds { left: i32, top: i32, right: i32, bottom: i32 }
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Bounds { pub left: i32, pub top: i32, pub right: i32, pub bottom: i32 }
 
 impl Bounds {
+	/// The bounds of the example in the puzzle text.
+	pub const EXAMPLE: Bounds = Bounds { left: 0, top: 0, right: 11, bottom: 7 };
+	/// The bounds of every real puzzle input.
+	pub const INPUT: Bounds = Bounds { left: 0, top: 0, right: 101, bottom: 103 };
+
+	/// Creates bounds from 0,0 of the given size
+	pub fn sized(width: i32, height: i32) -> Self {
+		Self { left: 0, top: 0, right: width, bottom: height }
+	}
+
 	/// The width of the bounding box
 	fn width(&self) -> i32 { self.right - self.left }
 
 	/// The height of the bounding box
-	fn height(&self) -> i32 { self.top - self.bottom }
+	fn height(&self) -> i32 { self.bottom - self.top }
 
-	/// Gets 4 quadrants within the current bounds. If the bounds are uneven, the middle axes are removed.
-	fn get_quadrants(&self) -> [Bounds; 4] {
-		let Self { left, top, right, bottom } = *self;
-		let (width, height) = (self.width(), self.height());
-		let (m_right, m_bottom, m_left, m_top) = (left + width / 2, bottom + height / 2 - 1, right - width / 2, top - height / 2 + 1);
-		[
-			Bounds { left, top, right: m_right, bottom: m_bottom }, // Top-left
-			Bounds { left: m_left, top, right, bottom: m_bottom }, // Top-right
-			Bounds { left, top: m_top, right: m_right, bottom }, // Bottom-left
-			Bounds { left: m_left, top: m_top, right, bottom }, // Bottom-right
-		]
+	/// Splits `start..end` into `parts` ranges. When excluding boundaries and the parts can be the same size with a single
+	/// line between each, those lines are left out of every range, otherwise the ranges are as even as possible.
+	fn split(start: i32, end: i32, parts: i32, exclude_boundaries: bool) -> Vec<(i32, i32)> {
+		let length = end - start;
+		if exclude_boundaries && (length - (parts - 1)) % parts == 0 {
+			let part = (length - (parts - 1)) / parts;
+			return (0..parts).map(|idx| (start + idx * (part + 1), start + idx * (part + 1) + part)).collect();
+		}
+		(0..parts).map(|idx| (start + idx * length / parts, start + (idx + 1) * length / parts)).collect()
+	}
+
+	/// Splits the bounds into a grid of `rows` by `cols` cells, in reading order. If `exclude_boundaries` is set and the
+	/// cells can be the same size with a single line between them, like the middle axes of the quadrants, those lines are
+	/// left out of every cell.
+	fn partition(&self, rows: usize, cols: usize, exclude_boundaries: bool) -> Vec<Bounds> {
+		let xs = Self::split(self.left, self.right, cols as i32, exclude_boundaries);
+		Self::split(self.top, self.bottom, rows as i32, exclude_boundaries).into_iter()
+			.flat_map(|(top, bottom)| xs.iter().map(move |&(left, right)| Bounds { left, top, right, bottom }))
+			.collect()
 	}
 
 	/// Checks whether or not this bounding box contains a certain position.
@@ -37,133 +59,393 @@ impl Bounds {
 
 /// A single robot, its position, and its movement velocity
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-struct Robot {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Robot {
 	position: Vec2,
 	velocity: Vec2,
 }
 
 impl Robot {
 	/// Constrains (**not clamps**) the position within a min/max by "looping" the value from an edge to the opposite side
-	/// to fit within the bounds.
-	fn constrain(mut pos: i32, min: i32, max: i32) -> i32 {
-		let width = max - min;
-		if pos < 0 { pos += width * (-pos / width + 1) }
-		min + (pos % width)
+	/// to fit within the bounds. The position is widened so moving it can't overflow first.
+	fn constrain(pos: i64, min: i32, max: i32) -> i32 {
+		(i64::from(min) + (pos - i64::from(min)).rem_euclid(i64::from(max - min))) as i32
+	}
+
+	/// Gets where the robot will be after a number of steps, without moving it.
+	pub fn position_at(&self, steps: usize, bounds: Bounds) -> Vec2 {
+		// Only the steps within one cycle around each axis matter, and in i64 the multiplication can't overflow
+		let (steps_x, steps_y) = ((steps % bounds.width() as usize) as i64, (steps % bounds.height() as usize) as i64);
+		Vec2 {
+			x: Self::constrain(i64::from(self.position.x) + i64::from(self.velocity.x) * steps_x, bounds.left, bounds.right),
+			y: Self::constrain(i64::from(self.position.y) + i64::from(self.velocity.y) * steps_y, bounds.top, bounds.bottom),
+		}
+	}
+}
+
+/// Every robot on a map, stored as a struct of arrays with each coordinate in its own slice. Stepping works over a whole
+/// axis at once without branching, so the compiler can vectorize it across robots.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Swarm {
+	xs: Vec<i32>,
+	ys: Vec<i32>,
+	velocity_xs: Vec<i32>,
+	velocity_ys: Vec<i32>,
+}
+
+impl Swarm {
+	/// The number of robots in the swarm.
+	pub fn len(&self) -> usize { self.xs.len() }
+
+	/// Whether or not the swarm has no robots.
+	pub fn is_empty(&self) -> bool { self.xs.is_empty() }
+
+	/// Gets a robot by its index, or None if there's no robot with that index.
+	pub fn get(&self, index: usize) -> Option<Robot> {
+		Some(Robot {
+			position: Vec2 { x: *self.xs.get(index)?, y: self.ys[index] },
+			velocity: Vec2 { x: self.velocity_xs[index], y: self.velocity_ys[index] },
+		})
+	}
+
+	/// Iterates over every robot in order.
+	pub fn iter(&self) -> impl Iterator<Item = Robot> + '_ {
+		(0..self.len()).filter_map(|index| self.get(index))
+	}
+
+	/// Moves every position along one axis by a number of steps, wrapping within `min..max` the same way as
+	/// `Robot::constrain`.
+	fn step_axis(positions: &mut [i32], velocities: &[i32], steps: usize, min: i32, max: i32) {
+		let (min, width) = (i64::from(min), i64::from(max - min));
+		let steps = (steps % width as usize) as i64;
+		for (position, &velocity) in positions.iter_mut().zip(velocities) {
+			*position = (min + (i64::from(*position) - min + i64::from(velocity) * steps).rem_euclid(width)) as i32;
+		}
+	}
+
+	/// Simulates a number of steps on every robot, an axis at a time.
+	pub fn step_n(&mut self, bounds: Bounds, steps: usize) {
+		Self::step_axis(&mut self.xs, &self.velocity_xs, steps, bounds.left, bounds.right);
+		Self::step_axis(&mut self.ys, &self.velocity_ys, steps, bounds.top, bounds.bottom);
+	}
+
+	/// Gets where every robot will be after a number of steps, without moving them.
+	pub fn position_at(&self, steps: usize, bounds: Bounds) -> Self {
+		let mut swarm = self.clone();
+		swarm.step_n(bounds, steps);
+		swarm
+	}
+}
+
+impl FromIterator<Robot> for Swarm {
+	fn from_iter<I: IntoIterator<Item = Robot>>(iter: I) -> Self {
+		let mut swarm = Self::default();
+		for Robot { position, velocity } in iter {
+			swarm.xs.push(position.x);
+			swarm.ys.push(position.y);
+			swarm.velocity_xs.push(velocity.x);
+			swarm.velocity_ys.push(velocity.y);
+		}
+		swarm
+	}
+}
+
+/// The fields describing each robot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+	Position,
+	Velocity,
+}
+
+impl Field {
+	/// The prefix of the field, such as `p=`.
+	fn prefix(&self) -> &'static str {
+		match self {
+			Field::Position => "p=",
+			Field::Velocity => "v=",
+		}
+	}
+}
+
+/// Possible errors when parsing the map, lines are 1-indexed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MapParseError {
+	/// A line ended before a field.
+	MissingField { line: usize, field: Field },
+	/// A field wasn't in the form `p=x,y` or `v=x,y`.
+	InvalidField { line: usize, field: Field, text: String },
+	/// A coordinate of a field wasn't an integer.
+	IntegerParseError { line: usize, field: Field, text: String, error: ParseIntError },
+	/// A line had more after the velocity.
+	TrailingText { line: usize, text: String },
+	/// There were no robots to infer the bounds from.
+	NoRobots,
+}
+
+impl Display for MapParseError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		match self {
+			MapParseError::MissingField { line, field } => write!(f, "Line {line}: missing {field:?}"),
+			MapParseError::InvalidField { line, field, text } => {
+				write!(f, "Line {line}: invalid {field:?} '{text}', expected {}x,y", field.prefix())
+			},
+			MapParseError::IntegerParseError { line, field, text, error } => {
+				write!(f, "Line {line}: invalid {field:?} coordinate '{text}', {error}")
+			},
+			MapParseError::TrailingText { line, text } => write!(f, "Line {line}: unexpected '{text}' after the velocity"),
+			MapParseError::NoRobots => write!(f, "No robots to infer the bounds from"),
+		}
 	}
+}
 
-	/// Simulates a number of steps on the robot and updates the robot accordingly
-	fn step_n(&mut self, bounds: Bounds, steps: usize) {
-		self.position.x = Self::constrain(self.position.x + self.velocity.x * steps as i32, bounds.left, bounds.right);
-		self.position.y = Self::constrain(self.position.y + self.velocity.y * steps as i32, bounds.top, bounds.bottom);
+impl Error for MapParseError {}
+
+/// How robots stack up on the same cells at some time.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OverlapStats {
+	/// The number of cells with 2 or more robots.
+	pub overlapping_cells: usize,
+	/// The most robots on any single cell.
+	pub max_stack: usize,
+	/// The number of robots on every cell, indexed by row then column from the top-left of the bounds.
+	pub counts: Vec<Vec<usize>>,
+}
+
+/// Possible errors when solving part 2.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Part2Error {
+	/// The robots couldn't be parsed.
+	Parse(MapParseError),
+	/// The frame scan was cancelled before the tree was found.
+	Cancelled,
+}
+
+impl Display for Part2Error {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		match self {
+			Part2Error::Parse(_) => write!(f, "failed to parse the robots"),
+			Part2Error::Cancelled => write!(f, "the frame scan was cancelled"),
+		}
+	}
+}
+
+impl Error for Part2Error {
+	fn source(&self) -> Option<&(dyn Error + 'static)> {
+		match self {
+			Part2Error::Parse(error) => Some(error),
+			Part2Error::Cancelled => None,
+		}
 	}
 }
 
-/// Possible errors when parsing the map
-#[derive(Debug)]
-#[allow(dead_code)]
-enum MapParseError {
-	InvalidPosition { string: String },
-	IntegerParseError { error: ParseIntError, string: String },
-	InvalidVectors { string: String },
+impl From<MapParseError> for Part2Error {
+	fn from(error: MapParseError) -> Self { Part2Error::Parse(error) }
 }
 
+
 /// A full map where robots are simulated on
 #[derive(Debug, Clone)]
-struct Map {
-	robots: Vec<Robot>,
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Map {
+	robots: Swarm,
 	bounds: Bounds,
 }
 
 impl Map {
-	/// Parses a map from a string, and given the bounds.
-	fn parse(input: &str, bounds: Bounds) -> Result<Self, (usize, MapParseError)> {
-		// Loop through all lines - each line is a robot
-		let robots = input.lines().enumerate().map(|(line_num, line)| {
+	/// Parses a single `p=x,y` or `v=x,y` field of a robot on a line.
+	fn parse_field(text: &str, field: Field, line: usize) -> Result<Vec2, MapParseError> {
+		let invalid = || MapParseError::InvalidField { line, field, text: text.into() };
+		let (x, y) = text.strip_prefix(field.prefix()).and_then(|vector| vector.split_once(',')).ok_or_else(invalid)?;
+		let parse = |coordinate: &str| coordinate.parse::<i32>()
+			.map_err(|error| MapParseError::IntegerParseError { line, field, text: coordinate.into(), error });
+		Ok(Vec2 { x: parse(x)?, y: parse(y)? })
+	}
 
-			// Loop through each vector - each line / robot has a position and a velocity
-			let vecs = line.replace("p=", "").replace("v=", "").split(" ").map(|pos_str| {
+	/// Parses a map from a string, and given the bounds. Each robot is on its own line as `p=x,y v=x,y`, separated by any
+	/// whitespace, blank lines are skipped.
+	pub fn parse(input: &str, bounds: Bounds) -> Result<Self, MapParseError> {
+		let robots = input.lines().enumerate().filter(|(_, line)| !line.trim().is_empty())
+			.map(|(idx, line)| Self::parse_robot(line, idx + 1))
+			.collect::<Result<Swarm, _>>()?;
+
+		Ok(Self { robots, bounds })
+	}
 
-				// Loop through each numeric value in the vector and parse it
-				let values = pos_str.split(",").map(|num_str| {
-					num_str.parse::<i32>()
-						.map_err(|error| MapParseError::IntegerParseError { error, string: num_str.into() })
-				}).collect::<Result<Vec<_>, _>>()?;
+	/// Parses a single robot `p=x,y v=x,y` on the given line.
+	fn parse_robot(text: &str, line: usize) -> Result<Robot, MapParseError> {
+		let mut fields = text.split_whitespace();
+		let mut next_field = |field| {
+			let text = fields.next().ok_or(MapParseError::MissingField { line, field })?;
+			Self::parse_field(text, field, line)
+		};
+		let (position, velocity) = (next_field(Field::Position)?, next_field(Field::Velocity)?);
+		let rest = fields.collect::<Vec<_>>();
+		if !rest.is_empty() { return Err(MapParseError::TrailingText { line, text: rest.join(" ") }) }
+		Ok(Robot { position, velocity })
+	}
 
-				// Ensure there are only 2 numeric values per vector
-				let [x, y] = *values.as_slice() else {
-					return Err(MapParseError::InvalidPosition { string: pos_str.into() })
-				};
+	/// Parses a map from a string, using `bounds` if given, otherwise the smallest bounds from 0,0 containing every robot.
+	/// Pass `Bounds::EXAMPLE` or `Bounds::INPUT` when the robots may not reach the edges of the real space.
+	pub fn parse_with_inferred_bounds(input: &str, bounds: Option<Bounds>) -> Result<Self, MapParseError> {
+		let mut map = Self::parse(input, bounds.unwrap_or(Bounds { left: 0, top: 0, right: 0, bottom: 0 }))?;
+		if bounds.is_none() {
+			let right = map.robots.iter().map(|robot| robot.position.x + 1).max().ok_or(MapParseError::NoRobots)?;
+			let bottom = map.robots.iter().map(|robot| robot.position.y + 1).max().ok_or(MapParseError::NoRobots)?;
+			map.bounds = Bounds::sized(right, bottom);
+		}
+		Ok(map)
+	}
 
-				Ok(Vec2 { x, y })
+	/// Simulates n steps on the map, all robots will be moved by n steps.
+	fn step_n(&mut self, steps: usize) {
+		self.robots.step_n(self.bounds, steps);
+	}
 
-			}).collect::<Result<Vec<_>, _>>().map_err(|err| (line_num, err))?; // Report errors with line the number
-			
-			// Each robot should only have 2 vectors
-			let [position, velocity] = *vecs.as_slice() else {
-				return Err((line_num, MapParseError::InvalidVectors { string: line.into() }))
-			};
+	/// Gets the map after n steps, without moving the robots on this map.
+	fn at(&self, steps: usize) -> Self {
+		Self { robots: self.robots.position_at(steps, self.bounds), bounds: self.bounds }
+	}
 
-			Ok(Robot { position, velocity })
+	/// Counts the robots in each cell of the bounds split into a grid of `rows` by `cols`, see `Bounds::partition`.
+	pub fn partition_counts(&self, rows: usize, cols: usize, exclude_boundaries: bool) -> Vec<Vec<usize>> {
+		let cells = self.bounds.partition(rows, cols, exclude_boundaries);
+		cells.chunks(cols.max(1)).map(|row| {
+			row.iter().map(|cell| self.robots.iter().filter(|robot| cell.contains(robot.position)).count()).collect()
+		}).collect()
+	}
 
-		}).collect::<Result<Vec<_>, _>>()?;
+	/// Counts the robots on every cell, indexed by row then column from the top-left of the bounds.
+	fn count_grid(&self) -> Vec<Vec<usize>> {
+		let mut counts = vec![vec![0; self.bounds.width() as usize]; self.bounds.height() as usize];
+		for robot in self.robots.iter().filter(|robot| self.bounds.contains(robot.position)) {
+			counts[(robot.position.y - self.bounds.top) as usize][(robot.position.x - self.bounds.left) as usize] += 1;
+		}
+		counts
+	}
 
-		Ok(Self { robots, bounds })
+	/// Gets how the robots overlap after n steps, without moving the robots on this map.
+	pub fn overlap_stats(&self, steps: usize) -> OverlapStats {
+		let counts = self.at(steps).count_grid();
+		OverlapStats {
+			overlapping_cells: counts.iter().flatten().filter(|&&count| count >= 2).count(),
+			max_stack: counts.iter().flatten().copied().max().unwrap_or(0),
+			counts,
+		}
 	}
 
-	/// Simulates n steps on the map, all robots will be moved by n steps.
-	fn step_n(&mut self, steps: usize) {
-		for robot in &mut self.robots { robot.step_n(self.bounds, steps); }
+	/// Gets the positions of a robot after each number of steps in `steps`, without moving it, or None if there's no
+	/// robot with that index.
+	pub fn trajectory(&self, robot_index: usize, steps: Range<usize>) -> Option<Vec<Vec2>> {
+		let robot = self.robots.get(robot_index)?;
+		Some(steps.map(|step| robot.position_at(step, self.bounds)).collect())
 	}
 
-	/// Gets all robots in the map, divided into their individual quadrants
-	fn get_robots_by_quadrants(&self) -> [Vec<Robot>; 4] {
-		self.bounds.get_quadrants().map(|quad| {
-			self.robots.iter().cloned().filter(|robot| quad.contains(robot.position)).collect()
-		})
+	/// Product of the number of robots in each quadrant after n steps, without moving the robots on this map. If the
+	/// bounds are uneven, the robots on the middle axes aren't in any quadrant.
+	fn safety_factor(&self, steps: usize) -> usize {
+		self.at(steps).partition_counts(2, 2, true).iter().flatten().product()
 	}
 
 	/// Gets the standard deviation x and y of the robot's positions.
 	fn get_robot_deviation(&self) -> (f32, f32) {
-		let xs: Vec<_> = self.robots.iter().map(|robot| robot.position.x as f32).collect();
-		let ys: Vec<_> = self.robots.iter().map(|robot| robot.position.y as f32).collect();
+		let xs: Vec<_> = self.robots.xs.iter().map(|&x| x as f32).collect();
+		let ys: Vec<_> = self.robots.ys.iter().map(|&y| y as f32).collect();
 		(
 			statistical::standard_deviation(xs.as_slice(), None),
 			statistical::standard_deviation(ys.as_slice(), None),
 		)
 	}
+
+	/// Finds the first of the next `max_steps` steps where the standard deviation of the robots' positions is below
+	/// `max_deviation` on both axes, without moving the robots on this map. The robots draw the christmas tree when they
+	/// cluster together. Checks the token before every step.
+	pub fn find_clustered_step(&self, max_steps: usize, max_deviation: f32, cancel: &CancelToken) -> Result<Option<usize>, Part2Error> {
+		let mut map = self.clone();
+		for step in 1..=max_steps {
+			if cancel.is_cancelled() { return Err(Part2Error::Cancelled) }
+			map.step_n(1);
+			let (x_deviation, y_deviation) = map.get_robot_deviation();
+			if x_deviation < max_deviation && y_deviation < max_deviation { return Ok(Some(step)) }
+		}
+		Ok(None)
+	}
 }
 
 impl Display for Map {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        let string = (self.bounds.left..self.bounds.right).map(|y| {
-			(self.bounds.top..self.bounds.bottom).map(|x| {
-				let pos = Vec2 { x, y };
-				match self.robots.iter().filter(|robot| robot.position == pos).count() {
-					0 => '.',
-					num => char::from_digit(cmp::min(num as u32, 9), 10).unwrap(),
-				}
+        let string = self.count_grid().iter().map(|row| {
+			row.iter().map(|&count| match count {
+				0 => '.',
+				num => char::from_digit(cmp::min(num as u32, 9), 10).unwrap(),
 			}).collect::<String>()
 		}).collect::<Vec<_>>().join("\n");
 		f.write_str(string.as_str())
     }
 }
 
+/// Product of the number of robots in each quadrant of the bounds after a number of steps.
+pub fn safety_factor(input: &str, bounds: Bounds, steps: usize) -> Result<usize, MapParseError> {
+	Ok(Map::parse(input, bounds)?.safety_factor(steps))
+}
+
+/// A problem with the robots, found by `validate`. Lines count from 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputProblem {
+	/// A line couldn't be parsed into a robot.
+	Parse(MapParseError),
+	/// A robot starts outside the bounds.
+	OutOfBounds { line: usize, position: Vec2 },
+}
+
+impl Display for InputProblem {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		match self {
+			InputProblem::Parse(error) => write!(f, "{error}"),
+			InputProblem::OutOfBounds { line, position } => {
+				write!(f, "Line {line}: robot starts at {},{} outside the bounds", position.x, position.y)
+			},
+		}
+	}
+}
+
+/// Checks every robot parses and starts within the bounds without simulating, reporting every problem rather than
+/// stopping at the first.
+pub fn validate(input: &str, bounds: Bounds) -> Vec<InputProblem> {
+	let lines = input.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()).collect::<Vec<_>>();
+	if lines.is_empty() { return vec![InputProblem::Parse(MapParseError::NoRobots)] }
+	lines.into_iter().filter_map(|(idx, text)| match Map::parse_robot(text, idx + 1) {
+		Err(error) => Some(InputProblem::Parse(error)),
+		Ok(Robot { position, .. }) if !bounds.contains(position) => Some(InputProblem::OutOfBounds { line: idx + 1, position }),
+		Ok(_) => None,
+	}).collect()
+}
+
 /// Part 1 solution - product of the number of robots in each quadrant after 100 steps.
-fn part1_solution(input: &str, bounds: Bounds) -> Result<usize, (usize, MapParseError)> {
-	let mut map = Map::parse(input, bounds)?;
-	map.step_n(100);
-	Ok(map.get_robots_by_quadrants().iter().map(|quad| quad.len()).product())
+fn part1_solution(input: &str, bounds: Bounds) -> Result<usize, MapParseError> {
+	safety_factor(input, bounds, 100)
 }
 
 /// Part 2 solution - Simulates 10,000 steps of the robots, and prints them when the
 /// standard deviation of the robots is within a certain threshold to find the christmas tree.
-fn part2_solution(input: &str, bounds: Bounds) -> Result<(), (usize, MapParseError)> {
+fn part2_solution(input: &str, bounds: Bounds, cancel: &CancelToken) -> Result<Option<usize>, Part2Error> {
 	let mut map = Map::parse(input, bounds)?;
-	for i in 1..10000 {
-		map.step_n(1);
-		let (x_deviation, y_deviation) = map.get_robot_deviation();
-		// Manually adjusted deviation threshold to find the tree.
-		if x_deviation < 20.0 && y_deviation < 20.0 { println!("Step {i} - \n{map}"); break; }
+	// Manually adjusted deviation threshold to find the tree.
+	let step = map.find_clustered_step(10000, 20.0, cancel)?;
+	if let Some(step) = step {
+		map.step_n(step);
+		println!("Step {step} - \n{map}");
+	}
+	Ok(step)
+}
+
+/// Simulates a number of steps, writing a JSON line with every robot's position for the starting state and each step.
+fn write_trace(map: &mut Map, steps: usize, out: &mut impl Write) -> io::Result<()> {
+	for step in 0..=steps {
+		if step > 0 { map.step_n(1); }
+		let positions = map.robots.iter().map(|robot| [robot.position.x, robot.position.y]).collect::<Vec<_>>();
+		writeln!(out, "{}", serde_json::json!({ "step": step, "robots": positions }))?;
 	}
 	Ok(())
 }
@@ -182,13 +464,272 @@ p=9,3 v=2,3
 p=7,3 v=-1,2
 p=2,4 v=2,-3
 p=9,5 v=-3,-3";
-	let example_bounds = Bounds { left: 0, top: 0, right: 11, bottom: 7 };
+	let example_bounds = Bounds::EXAMPLE;
 	let input_robots = include_str!("day14.txt");
-	let input_bounds = Bounds { left: 0, top: 0, right: 101, bottom: 103 };
+	let input_bounds = Bounds::INPUT;
+
+	let args = std::env::args().collect::<Vec<_>>();
+	let (robots, bounds) = if args.iter().any(|arg| arg == "--example") {
+		(example_robots, example_bounds)
+	} else { (input_robots, input_bounds) };
+
+	let flag = |name: &str| args.iter().skip_while(|&arg| arg != name).nth(1)
+		.map(|value| value.parse::<i32>().unwrap_or_else(|_| panic!("Invalid {name}, expected a number")));
+	let size = flag("--width").zip(flag("--height")).map(|(width, height)| Bounds::sized(width, height));
+
+	// Check a file for problems without simulating it with `--validate <path>`, against `--width`/`--height` if given
+	if let Some(path) = args.iter().skip_while(|&arg| arg != "--validate").nth(1) {
+		validate_file(path, |input| validate(input, size.unwrap_or(bounds)));
+		return;
+	}
+
+	// Print where a robot is after each step, up to `--steps N` (100 by default)
+	if let Some(index) = args.iter().skip_while(|&arg| arg != "--trajectory").nth(1) {
+		let steps = flag("--steps").unwrap_or(100) as usize;
+		let map = Map::parse(robots, size.unwrap_or(bounds)).expect("Failed to parse map");
+		let trajectory = map.trajectory(index.parse().expect("Invalid --trajectory, expected a robot index"), 0..steps + 1);
+		for (step, position) in trajectory.expect("No robot with that index").into_iter().enumerate() {
+			println!("Step {step}: {},{}", position.x, position.y);
+		}
+		return;
+	}
 
-	println!("Part 1 Solution on Example: {:#?}", part1_solution(example_robots, example_bounds));
-	println!("Part 1 Solution on Input: {:#?}", part1_solution(input_robots, input_bounds));
+	// Print the robots stacked on each cell after `--steps N` steps (100 by default)
+	if args.iter().any(|arg| arg == "--overlaps") {
+		let steps = flag("--steps").unwrap_or(100) as usize;
+		let map = Map::parse(robots, size.unwrap_or(bounds)).expect("Failed to parse map");
+		let stats = map.overlap_stats(steps);
+		println!("{}", map.at(steps));
+		println!("{} cells with 2+ robots, at most {} on one cell", stats.overlapping_cells, stats.max_stack);
+		return;
+	}
+
+	// Write each robot's position as JSON lines with `--trace <path>`, for `--trace-steps N` steps (100 by default)
+	if let Some(path) = args.iter().skip_while(|&arg| arg != "--trace").nth(1) {
+		let steps = args.iter().skip_while(|&arg| arg != "--trace-steps").nth(1)
+			.map(|steps| steps.parse().expect("Invalid --trace-steps, expected a number"))
+			.unwrap_or(100);
+		let mut map = Map::parse(robots, size.unwrap_or(bounds)).expect("Failed to parse map");
+		let mut out = BufWriter::new(File::create(path).expect("Failed to create trace file"));
+		write_trace(&mut map, steps, &mut out).and_then(|_| out.flush()).expect("Failed to write trace");
+		return;
+	}
+
+	// Step through the robots interactively with `--debug`
+	if args.iter().any(|arg| arg == "--debug") {
+		let map = Map::parse(robots, size.unwrap_or(bounds)).expect("Failed to parse map");
+		debug(map, |map| { map.step_n(1); true }, |map| {
+			let (x_deviation, y_deviation) = map.get_robot_deviation();
+			format!("{map}\n{} robots, deviation x: {x_deviation:.2}, y: {y_deviation:.2}", map.robots.len())
+		});
+		return;
+	}
+
+	// Calculate the safety factor after `--steps N` steps (100 by default) within `--width W --height H` bounds, for
+	// any `--file <path>` whose bounds are inferred from the robots unless `--input-bounds` or a size is given
+	let file = args.iter().skip_while(|&arg| arg != "--file").nth(1);
+	if file.is_some() || size.is_some() || args.iter().any(|arg| arg == "--steps" || arg == "--partition") {
+		let steps = flag("--steps").unwrap_or(100) as usize;
+		let map = match file {
+			Some(path) => {
+				let robots = std::fs::read_to_string(path).expect("Failed to read input file");
+				let known = size.or(args.iter().any(|arg| arg == "--input-bounds").then_some(Bounds::INPUT));
+				Map::parse_with_inferred_bounds(&robots, known)
+			},
+			None => Map::parse(robots, size.unwrap_or(bounds)),
+		}.expect("Failed to parse map");
+		println!("Bounds: {}x{}", map.bounds.width(), map.bounds.height());
+		println!("Safety factor after {steps} steps: {}", map.safety_factor(steps));
+
+		// Count the robots over a `--partition RxC` grid, keeping the lines between cells with `--keep-boundaries`
+		if let Some(grid) = args.iter().skip_while(|&arg| arg != "--partition").nth(1) {
+			let (rows, cols) = grid.split_once('x').expect("Invalid --partition, expected RxC");
+			let (rows, cols) = (rows.parse().expect("Invalid partition rows"), cols.parse().expect("Invalid partition columns"));
+			let mut map = map;
+			map.step_n(steps);
+			for row in map.partition_counts(rows, cols, !args.iter().any(|arg| arg == "--keep-boundaries")) {
+				println!("{}", row.iter().map(|count| format!("{count:>4}")).collect::<String>());
+			}
+		}
+		return;
+	}
+	println!("Part 1 Solution on Example: {}", report(part1_solution(example_robots, example_bounds), "solving day 14 part 1"));
+	println!("Part 1 Solution on Input: {}", report(part1_solution(input_robots, input_bounds), "solving day 14 part 1"));
 	
 	// dbg!(part1_solution(example_robots, example_bounds)); - The solution does not exist for example inputs
-	println!("{:#?}", part2_solution(input_robots, input_bounds)); // Print in case of error
+	// Give up on finding the tree after a number of seconds with `--timeout <seconds>`
+	let cancel = CancelToken::default();
+	if let Some(seconds) = args.iter().skip_while(|&arg| arg != "--timeout").nth(1) {
+		cancel.cancel_after(Duration::from_secs_f64(seconds.parse().expect("Invalid timeout")));
+	}
+	println!("{}", report(part2_solution(input_robots, input_bounds, &cancel), "solving day 14 part 2")); // Print in case of error
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const EXAMPLE: &str = "p=0,4 v=3,-3
+p=6,3 v=-1,-3
+p=10,3 v=-1,2
+p=2,0 v=2,-1
+p=0,0 v=1,3
+p=3,0 v=-2,-2
+p=7,6 v=-1,-3
+p=3,0 v=-1,-2
+p=9,3 v=2,3
+p=7,3 v=-1,2
+p=2,4 v=2,-3
+p=9,5 v=-3,-3";
+
+	/// Tests the bounds are inferred from the furthest robots, unless they're given
+	#[test]
+	fn test_inferred_bounds() {
+		assert_eq!(Map::parse_with_inferred_bounds(EXAMPLE, None).unwrap().bounds, Bounds::EXAMPLE);
+		assert_eq!(Map::parse_with_inferred_bounds(EXAMPLE, Some(Bounds::INPUT)).unwrap().bounds, Bounds::INPUT);
+		assert!(matches!(Map::parse_with_inferred_bounds("", None), Err(MapParseError::NoRobots)));
+	}
+
+	/// Tests the trace has a JSON line with every robot's position for the start and each step
+	#[test]
+	fn test_write_trace() {
+		let mut map = Map::parse("p=0,0 v=1,1\np=2,1 v=0,0", Bounds::sized(3, 3)).unwrap();
+		let mut out = Vec::new();
+		write_trace(&mut map, 1, &mut out).unwrap();
+		let trace = String::from_utf8(out).unwrap();
+		assert_eq!(trace.lines().collect::<Vec<_>>(), [r#"{"robots":[[0,0],[2,1]],"step":0}"#, r#"{"robots":[[1,1],[2,1]],"step":1}"#]);
+	}
+
+	/// Tests the safety factor at other times and on other grid sizes
+	#[test]
+	fn test_safety_factor() {
+		assert_eq!(safety_factor(EXAMPLE, Bounds::EXAMPLE, 100).unwrap(), 12);
+		// No robots start in the top-right quadrant
+		assert_eq!(safety_factor(EXAMPLE, Bounds::EXAMPLE, 0).unwrap(), 0);
+		// A robot at 0,0 moving diagonally visits each quadrant of a 3x3 grid, skipping the middle axes
+		assert_eq!(safety_factor("p=0,0 v=1,1\np=2,2 v=0,0\np=0,2 v=0,0\np=2,0 v=0,0", Bounds::sized(3, 3), 1).unwrap(), 0);
+		assert_eq!(safety_factor("p=0,0 v=1,1\np=2,2 v=0,0\np=0,2 v=0,0\np=2,0 v=0,0", Bounds::sized(3, 3), 3).unwrap(), 1);
+	}
+
+	/// Tests partitions with and without the lines between cells, with quadrants as a special case
+	#[test]
+	fn test_partition_counts() {
+		let mut map = Map::parse(EXAMPLE, Bounds::EXAMPLE).unwrap();
+		map.step_n(100);
+		assert_eq!(map.partition_counts(2, 2, true), [[1, 3], [4, 1]]);
+		assert_eq!(map.partition_counts(1, 1, true), [[12]]);
+		assert_eq!(map.partition_counts(2, 2, false).iter().flatten().sum::<usize>(), 12);
+		// 11 columns split into 3 of 3 with 2 lines between, 7 rows into 2 of 3 with the middle line between
+		let cells = Bounds::EXAMPLE.partition(2, 3, true);
+		assert_eq!(cells.iter().map(|cell| (cell.left, cell.right)).take(3).collect::<Vec<_>>(), [(0, 3), (4, 7), (8, 11)]);
+		assert_eq!(Bounds::sized(10, 7).partition(2, 1, true).iter().map(|cell| (cell.top, cell.bottom)).collect::<Vec<_>>(), [(0, 3), (4, 7)]);
+	}
+
+	/// Tests a robot's trajectory matches the puzzle text without moving it
+	#[test]
+	fn test_trajectory() {
+		let map = Map::parse("p=2,4 v=2,-3", Bounds::EXAMPLE).unwrap();
+		let trajectory = map.trajectory(0, 0..6).unwrap();
+		assert_eq!(trajectory, [(2, 4), (4, 1), (6, 5), (8, 2), (10, 6), (1, 3)].map(|(x, y)| Vec2 { x, y }));
+		assert_eq!(map.robots.get(0).unwrap().position, Vec2 { x: 2, y: 4 });
+		assert_eq!(map.robots.get(0).unwrap().position_at(5 + 77 * 1_000_000_000_000, Bounds::EXAMPLE), Vec2 { x: 1, y: 3 });
+		assert_eq!(map.trajectory(1, 0..6), None);
+	}
+
+	/// Tests overlaps match the robot counts in the puzzle text after 100 steps
+	#[test]
+	fn test_overlap_stats() {
+		let map = Map::parse(EXAMPLE, Bounds::EXAMPLE).unwrap();
+		let stats = map.overlap_stats(100);
+		assert_eq!(stats.overlapping_cells, 2);
+		assert_eq!(stats.max_stack, 2);
+		assert_eq!(stats.counts[0], [0, 0, 0, 0, 0, 0, 2, 0, 0, 1, 0]);
+		assert_eq!(stats.counts.iter().flatten().sum::<usize>(), 12);
+	}
+
+	/// Tests parse errors name the line, field, and offending text
+	#[test]
+	fn test_parse_errors() {
+		let error = |input| Map::parse(input, Bounds::EXAMPLE).unwrap_err().to_string();
+		assert_eq!(error("p=0,4 v=3,-3\np=6,3"), "Line 2: missing Velocity");
+		assert_eq!(error("p=0,4 v=3,-3\n\np=6;3 v=-1,-3"), "Line 3: invalid Position 'p=6;3', expected p=x,y");
+		assert_eq!(error("v=3,-3 p=0,4"), "Line 1: invalid Position 'v=3,-3', expected p=x,y");
+		assert_eq!(error("p=0,4 v=3,x"), "Line 1: invalid Velocity coordinate 'x', invalid digit found in string");
+		assert_eq!(error("p=0,4 v=3,-3 q=1,1"), "Line 1: unexpected 'q=1,1' after the velocity");
+		assert_eq!(Map::parse("  p=0,4\tv=3,-3  \n", Bounds::EXAMPLE).unwrap().robots.len(), 1);
+	}
+
+	/// Tests the frame scan stops once cancelled
+	#[test]
+	fn test_find_clustered_step() {
+		let map = Map::parse("p=0,0 v=1,1\np=2,2 v=-1,-1", Bounds::EXAMPLE).unwrap();
+		let cancel = CancelToken::default();
+		assert_eq!(map.find_clustered_step(10, 0.5, &cancel), Ok(Some(1)));
+		assert_eq!(map.find_clustered_step(10, 0.0, &cancel), Ok(None));
+		cancel.cancel();
+		assert_eq!(map.find_clustered_step(10, 0.5, &cancel), Err(Part2Error::Cancelled));
+	}
+
+	/// Tests validation reports every problem with the robots at once
+	#[test]
+	fn test_validate() {
+		assert!(validate("p=0,4 v=3,-3\np=6,3 v=-1,-3", Bounds::EXAMPLE).is_empty());
+		assert_eq!(validate("", Bounds::EXAMPLE), [InputProblem::Parse(MapParseError::NoRobots)]);
+		assert_eq!(validate("p=0,4\np=11,3 v=-1,-3\np=1,1 v=1,1 x", Bounds::EXAMPLE), [
+			InputProblem::Parse(MapParseError::MissingField { line: 1, field: Field::Velocity }),
+			InputProblem::OutOfBounds { line: 2, position: Vec2 { x: 11, y: 3 } },
+			InputProblem::Parse(MapParseError::TrailingText { line: 3, text: String::from("x") }),
+		]);
+	}
+
+	/// Tests error reports describe what failed to parse
+	#[test]
+	fn test_report() {
+		assert_eq!(report(part1_solution(EXAMPLE, Bounds::EXAMPLE), "solving part 1"), "12");
+		assert_eq!(
+			report(part1_solution("p=0,4 v=3", Bounds::EXAMPLE), "solving part 1"),
+			"error while solving part 1: Line 1: invalid Velocity 'v=3', expected v=x,y",
+		);
+		assert_eq!(
+			report(part2_solution("p=0,4", Bounds::EXAMPLE, &CancelToken::default()), "solving part 2"),
+			"error while solving part 2: failed to parse the robots: Line 1: missing Velocity",
+		);
+		let cancel = CancelToken::default();
+		cancel.cancel();
+		assert_eq!(report(part2_solution(EXAMPLE, Bounds::EXAMPLE, &cancel), "solving part 2"), "error while solving part 2: the frame scan was cancelled");
+	}
+
+	/// Tests stepping the whole swarm at once puts every robot where stepping it alone would
+	#[test]
+	fn test_swarm_step() {
+		let map = Map::parse(&format!("{EXAMPLE}\np=-3,9 v=-12,25"), Bounds::EXAMPLE).unwrap();
+		for steps in [0, 1, 5, 100, 77, 10_000, 5 + 77 * 1_000_000_000_000] {
+			let swarm = map.robots.position_at(steps, map.bounds);
+			let expected = map.robots.iter().map(|robot| robot.position_at(steps, map.bounds)).collect::<Vec<_>>();
+			assert_eq!(swarm.iter().map(|robot| robot.position).collect::<Vec<_>>(), expected);
+		}
+
+		let mut stepped = map.clone();
+		for _ in 0..100 { stepped.step_n(1); }
+		assert_eq!(stepped.robots, map.at(100).robots);
+		assert_eq!(map.robots.len(), 13);
+		assert_eq!(map.robots.get(13), None);
+	}
+
+	/// Tests robots wrap within bounds which don't start at 0,0, and fast robots don't overflow
+	#[test]
+	fn test_offset_bounds() {
+		let bounds = Bounds { left: 5, top: -3, right: 10, bottom: 2 };
+		let map = Map::parse("p=7,0 v=1,-1\np=9,-3 v=2147483647,-2147483648", bounds).unwrap();
+		let robot = map.robots.get(0).unwrap();
+		assert_eq!(robot.position_at(3, bounds), Vec2 { x: 5, y: -3 });
+		assert_eq!(robot.position_at(4, bounds), Vec2 { x: 6, y: 1 });
+		assert_eq!(map.robots.position_at(4, bounds).get(0).unwrap().position, Vec2 { x: 6, y: 1 });
+
+		// 2147483647 and -2147483648 are both 2 more than a multiple of 5, so each step moves 2 right and 2 down
+		let fast = map.robots.get(1).unwrap();
+		assert_eq!(fast.position_at(2, bounds), Vec2 { x: 8, y: 1 });
+		assert_eq!(map.robots.position_at(2, bounds).get(1).unwrap().position, Vec2 { x: 8, y: 1 });
+	}
+
 }
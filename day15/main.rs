@@ -0,0 +1,281 @@
+use std::{collections::HashSet, error::Error, fmt::{self, Display, Formatter}};
+
+use common::report::report;
+
+/// A direction the robot tries to move in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction { Up, Right, Down, Left }
+
+impl Direction {
+	/// Gets the change in x and y of moving in this direction, y increases down.
+	fn offset(&self) -> (isize, isize) {
+		match self {
+			Direction::Up => (0, -1),
+			Direction::Right => (1, 0),
+			Direction::Down => (0, 1),
+			Direction::Left => (-1, 0),
+		}
+	}
+
+	/// Whether this direction moves up or down.
+	fn is_vertical(&self) -> bool {
+		matches!(self, Direction::Up | Direction::Down)
+	}
+}
+
+impl TryFrom<char> for Direction {
+	type Error = WarehouseParseError;
+
+	fn try_from(value: char) -> Result<Self, WarehouseParseError> {
+		match value {
+			'^' => Ok(Direction::Up),
+			'>' => Ok(Direction::Right),
+			'v' => Ok(Direction::Down),
+			'<' => Ok(Direction::Left),
+			character => Err(WarehouseParseError::UnknownMove { character }),
+		}
+	}
+}
+
+/// A single position in the warehouse, 0,0 is at the top-left
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Position { pub x: usize, pub y: usize }
+
+impl Position {
+	/// Gets the position one step in a direction. Walls surround the warehouse, so this never goes negative.
+	fn step(&self, direction: Direction) -> Position {
+		let (d_x, d_y) = direction.offset();
+		Position { x: self.x.wrapping_add_signed(d_x), y: self.y.wrapping_add_signed(d_y) }
+	}
+}
+
+/// What's on a single tile of the warehouse
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tile { Empty, Wall, Box, BoxLeft, BoxRight }
+
+/// Possible errors when parsing the warehouse and moves
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WarehouseParseError {
+	UnknownTile { line: usize, column: usize, character: char },
+	UnknownMove { character: char },
+	MissingRobot,
+}
+
+impl Display for WarehouseParseError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		match self {
+			WarehouseParseError::UnknownTile { line, column, character } => write!(f, "Line {line}: unknown tile {character:?} at column {column}"),
+			WarehouseParseError::UnknownMove { character } => write!(f, "Unknown move {character:?}"),
+			WarehouseParseError::MissingRobot => write!(f, "The warehouse has no robot"),
+		}
+	}
+}
+
+impl Error for WarehouseParseError {}
+
+/// The warehouse the robot pushes boxes around in
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Warehouse {
+	tiles: Vec<Vec<Tile>>,
+	robot: Position,
+}
+
+impl TryFrom<&str> for Warehouse {
+	type Error = WarehouseParseError;
+
+	/// Converts from the warehouse map, `#` walls, `O` boxes, `@` the robot, and `.` empty tiles.
+	fn try_from(value: &str) -> Result<Self, WarehouseParseError> {
+		let mut robot = None;
+		let tiles = value.lines().enumerate().map(|(y, line)| {
+			line.chars().enumerate().map(|(x, character)| match character {
+				'#' => Ok(Tile::Wall),
+				'O' => Ok(Tile::Box),
+				'[' => Ok(Tile::BoxLeft),
+				']' => Ok(Tile::BoxRight),
+				'.' => Ok(Tile::Empty),
+				'@' => { robot = Some(Position { x, y }); Ok(Tile::Empty) },
+				character => Err(WarehouseParseError::UnknownTile { line: y + 1, column: x + 1, character }),
+			}).collect()
+		}).collect::<Result<_, _>>()?;
+		Ok(Self { tiles, robot: robot.ok_or(WarehouseParseError::MissingRobot)? })
+	}
+}
+
+impl Warehouse {
+	/// Gets the tile at a position.
+	fn tile(&self, pos: Position) -> Tile {
+		self.tiles[pos.y][pos.x]
+	}
+
+	/// Gets the warehouse with everything but the robot twice as wide, boxes becoming `[]`.
+	pub fn widen(&self) -> Self {
+		let tiles = self.tiles.iter().map(|row| row.iter().flat_map(|tile| match tile {
+			Tile::Box => [Tile::BoxLeft, Tile::BoxRight],
+			&tile => [tile, tile],
+		}).collect()).collect();
+		Self { tiles, robot: Position { x: self.robot.x * 2, y: self.robot.y } }
+	}
+
+	/// Finds every position holding part of a box that would be pushed by the robot moving in a direction, in the
+	/// order they're reached. Returns None if the push is blocked by a wall.
+	pub fn push_chain(&self, direction: Direction) -> Option<Vec<Position>> {
+		let mut chain = Vec::new();
+		let mut seen = HashSet::new();
+		let mut frontier = vec![self.robot];
+		while let Some(pos) = frontier.pop() {
+			let next = pos.step(direction);
+			let mut add = |pos: Position| if seen.insert(pos) { chain.push(pos); frontier.push(pos); };
+			match self.tile(next) {
+				Tile::Wall => return None,
+				Tile::Empty => {},
+				Tile::Box => add(next),
+				// Pushing half of a wide box up or down pushes the other half too
+				Tile::BoxLeft => { add(next); if direction.is_vertical() { add(next.step(Direction::Right)) } },
+				Tile::BoxRight => { add(next); if direction.is_vertical() { add(next.step(Direction::Left)) } },
+			}
+		}
+		Some(chain)
+	}
+
+	/// Tries to move the robot in a direction, pushing any boxes in the way. Returns whether the robot moved.
+	pub fn step(&mut self, direction: Direction) -> bool {
+		let Some(chain) = self.push_chain(direction) else { return false };
+		let moved = chain.iter().map(|&pos| (pos.step(direction), self.tile(pos))).collect::<Vec<_>>();
+		for pos in &chain { self.tiles[pos.y][pos.x] = Tile::Empty; }
+		for (pos, tile) in moved { self.tiles[pos.y][pos.x] = tile; }
+		self.robot = self.robot.step(direction);
+		true
+	}
+
+	/// Sum of the GPS coordinates of every box, 100 times its distance from the top plus its distance from the left.
+	pub fn gps_sum(&self) -> usize {
+		self.tiles.iter().enumerate().flat_map(|(y, row)| {
+			row.iter().enumerate().filter(|(_, tile)| matches!(tile, Tile::Box | Tile::BoxLeft)).map(move |(x, _)| 100 * y + x)
+		}).sum()
+	}
+}
+
+impl Display for Warehouse {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		let string = self.tiles.iter().enumerate().map(|(y, row)| {
+			row.iter().enumerate().map(|(x, tile)| match tile {
+				_ if self.robot == (Position { x, y }) => '@',
+				Tile::Empty => '.',
+				Tile::Wall => '#',
+				Tile::Box => 'O',
+				Tile::BoxLeft => '[',
+				Tile::BoxRight => ']',
+			}).collect::<String>()
+		}).collect::<Vec<_>>().join("\n");
+		f.write_str(string.as_str())
+	}
+}
+
+/// Parses the warehouse, then a blank line, then the moves which may be split over many lines.
+pub fn parse(input: &str) -> Result<(Warehouse, Vec<Direction>), WarehouseParseError> {
+	let (map, moves) = input.split_once("\n\n").unwrap_or((input, ""));
+	let moves = moves.chars().filter(|character| !character.is_whitespace()).map(Direction::try_from).collect::<Result<_, _>>()?;
+	Ok((Warehouse::try_from(map)?, moves))
+}
+
+/// Part 1 solution - sum of the GPS coordinates of every box after the robot finishes moving.
+fn part1_solution(input: &str) -> Result<usize, WarehouseParseError> {
+	let (mut warehouse, moves) = parse(input)?;
+	for direction in moves { warehouse.step(direction); }
+	Ok(warehouse.gps_sum())
+}
+
+/// Part 2 solution - sum of the GPS coordinates of every box after the robot finishes moving in the wide warehouse.
+fn part2_solution(input: &str) -> Result<usize, WarehouseParseError> {
+	let (warehouse, moves) = parse(input)?;
+	let mut warehouse = warehouse.widen();
+	for direction in moves { warehouse.step(direction); }
+	Ok(warehouse.gps_sum())
+}
+
+/// Entry point
+fn main() {
+	let small_example = "########
+#..O.O.#
+##@.O..#
+#...O..#
+#.#.O..#
+#...O..#
+#......#
+########
+
+<^^>>>vv<v>>v<<";
+	let example = "##########
+#..O..O.O#
+#......O.#
+#.OO..O.O#
+#..O@..O.#
+#O#..O...#
+#O..O..O.#
+#.OO.O.OO#
+#....O...#
+##########
+
+<vv>^<v^>v>^vv^v>v<>v^v<v<^vv<<<^><<><>>v<vvv<>^v^>^<<<><<v<<<v^vv^v>^
+vvv<<^>^v^^><<>>><>^<<><^vv^^<>vvv<>><^^v>^>vv<>v<<<<v<^v>^<^^>>>^<v<v
+><>vv>v^v^<>><>>>><^^>vv>v<^^^>>v^v^<^^>v^^>v^<^v>v<>>v^v^<v>v^^<^^vv<
+<<v<^>>^^^^>>>v^<>vvv^><v<<<>^^^vv^<vvv>^>v<^^^^v<>^>vvvv><>>v^<<^^^^^
+^><^><>>><>^^<<^^v>>><^<v>^<vv>>v>>>^v><>^v><<<<v>>v<v<v>vvv>^<><<>^><
+^>><>^v<><^vvv<^^<><v<<<<<><^v<<<><<<^^<v<^^^><^>>^<v^><<<^>>^v<v^v<v^
+>^>>^v>vv>^<<^v<>><<><<v<<v><>v<^vv<<<>^^v^>^^>>><<^v>>v^v><^^>>^<>vv^
+<><^^>^^^<><vvvvv^v<v<<>^v<v>v<<^><<><<><<<^^<<<^<<>><<><^^^>^^<>^>v<>
+^^>vv<^v^v<vv>^<><v<^v>^^^>>>^^vvv^>vvv<>>>^<^>>>>>^<<^v>^vvv<>^<><<v>
+v^^>>><<^^<>>^v^<v^vv<>v^<<>^<^v^v><^<<<><<^<v><v<>vv>>v><v^<vv<>v^<<^";
+	println!("Part 1 Solution on Small Example: {}", report(part1_solution(small_example), "solving day 15 part 1"));
+	println!("Part 1 Solution on Example: {}", report(part1_solution(example), "solving day 15 part 1"));
+	println!("Part 2 Solution on Example: {}", report(part2_solution(example), "solving day 15 part 2"));
+
+	// Pass the input with `--file <path>`, and add `--animate` (with `--wide` for part 2) to print every move
+	let args = std::env::args().collect::<Vec<_>>();
+	let input = args.iter().skip_while(|&arg| arg != "--file").nth(1)
+		.map(|path| std::fs::read_to_string(path).expect("Failed to read input file"));
+	if args.iter().any(|arg| arg == "--animate") {
+		let (warehouse, moves) = parse(input.as_deref().unwrap_or(example)).expect("Failed to parse warehouse");
+		let mut warehouse = if args.iter().any(|arg| arg == "--wide") { warehouse.widen() } else { warehouse };
+		println!("Initial state:\n{warehouse}");
+		for (idx, direction) in moves.into_iter().enumerate() {
+			let pushed = warehouse.push_chain(direction).map_or(0, |chain| chain.len());
+			let moved = warehouse.step(direction);
+			println!("\nMove {} {direction:?}: {}, pushing {pushed} box tiles\n{warehouse}", idx + 1, if moved { "moved" } else { "blocked" });
+		}
+		return;
+	}
+	if let Some(input) = input {
+		println!("Part 1 Solution on Input: {}", report(part1_solution(&input), "solving day 15 part 1"));
+		println!("Part 2 Solution on Input: {}", report(part2_solution(&input), "solving day 15 part 2"));
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Tests a wide box push chain spreads out diagonally and is blocked by any wall
+	#[test]
+	fn test_push_chain() {
+		let (warehouse, moves) = parse("#######\n#...#.#\n#.....#\n#..OO@#\n#..O..#\n#.....#\n#######\n\n<vv<<^^<<^^").unwrap();
+		let mut warehouse = warehouse.widen();
+		for direction in moves { warehouse.step(direction); }
+		assert_eq!(warehouse.to_string(), "##############
+##...[].##..##
+##...@.[]...##
+##....[]....##
+##..........##
+##..........##
+##############");
+		assert_eq!(warehouse.push_chain(Direction::Up), None);
+		assert_eq!(warehouse.push_chain(Direction::Right), Some(Vec::new()));
+
+		let (warehouse, _) = parse("######\n#....#\n#.O..#\n#.O..#\n#.@..#\n######").unwrap();
+		let mut warehouse = warehouse.widen();
+		assert_eq!(warehouse.push_chain(Direction::Up).map(|chain| chain.len()), Some(4));
+		assert!(warehouse.step(Direction::Up));
+		assert!(!warehouse.step(Direction::Up));
+	}
+
+}
@@ -0,0 +1,200 @@
+use std::{collections::HashSet, error::Error, fmt::{self, Display, Formatter}};
+
+use common::{pathfinding::{self, Position}, report::report};
+
+/// A direction the reindeer can face
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Direction { North, East, South, West }
+
+impl Direction {
+	/// Every direction, clockwise from north.
+	const ALL: [Direction; 4] = [Direction::North, Direction::East, Direction::South, Direction::West];
+
+	/// Gets the change in x and y of moving in this direction, y increases down.
+	fn offset(&self) -> (isize, isize) {
+		match self {
+			Direction::North => (0, -1),
+			Direction::East => (1, 0),
+			Direction::South => (0, 1),
+			Direction::West => (-1, 0),
+		}
+	}
+
+	/// Gets the directions after turning 90 degrees either way.
+	fn turns(&self) -> [Direction; 2] {
+		let idx = Self::ALL.iter().position(|direction| direction == self).expect("Every direction is in ALL");
+		[Self::ALL[(idx + 1) % 4], Self::ALL[(idx + 3) % 4]]
+	}
+
+	/// Gets the position one step from `pos` in this direction, or backwards if `forwards` is false. Walls surround the
+	/// maze, so this never goes negative.
+	fn step(&self, pos: Position, forwards: bool) -> Position {
+		let (d_x, d_y) = self.offset();
+		let sign = if forwards { 1 } else { -1 };
+		Position { x: pos.x.wrapping_add_signed(d_x * sign), y: pos.y.wrapping_add_signed(d_y * sign) }
+	}
+}
+
+/// Where the reindeer is and which way it's facing, the nodes searched over
+type State = (Position, Direction);
+
+/// The cost of stepping forward one tile.
+const STEP_COST: u64 = 1;
+/// The cost of turning 90 degrees.
+const TURN_COST: u64 = 1000;
+
+/// Possible errors when parsing the maze
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MazeParseError {
+	UnknownTile { line: usize, column: usize, character: char },
+	MissingStart,
+	MissingEnd,
+}
+
+impl Display for MazeParseError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		match self {
+			MazeParseError::UnknownTile { line, column, character } => write!(f, "Line {line}: unknown tile {character:?} at column {column}"),
+			MazeParseError::MissingStart => write!(f, "The maze has no start"),
+			MazeParseError::MissingEnd => write!(f, "The maze has no end"),
+		}
+	}
+}
+
+impl Error for MazeParseError {}
+
+/// The reindeer maze, walls, the start tile, and the end tile
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Maze {
+	walls: HashSet<Position>,
+	start: Position,
+	end: Position,
+}
+
+impl TryFrom<&str> for Maze {
+	type Error = MazeParseError;
+
+	/// Converts from the map, `#` walls, `.` open tiles, `S` the start, and `E` the end.
+	fn try_from(value: &str) -> Result<Self, MazeParseError> {
+		let (mut walls, mut start, mut end) = (HashSet::new(), None, None);
+		for (y, line) in value.lines().enumerate() {
+			for (x, character) in line.chars().enumerate() {
+				let pos = Position { x, y };
+				match character {
+					'#' => { walls.insert(pos); },
+					'.' => {},
+					'S' => start = Some(pos),
+					'E' => end = Some(pos),
+					character => return Err(MazeParseError::UnknownTile { line: y + 1, column: x + 1, character }),
+				}
+			}
+		}
+		Ok(Self { walls, start: start.ok_or(MazeParseError::MissingStart)?, end: end.ok_or(MazeParseError::MissingEnd)? })
+	}
+}
+
+impl Maze {
+	/// Gets the states one move from a state and their costs, or one move before it if `forwards` is false.
+	fn moves(&self, (pos, direction): State, forwards: bool) -> Vec<(State, u64)> {
+		let mut moves = direction.turns().map(|turned| ((pos, turned), TURN_COST)).to_vec();
+		let next = direction.step(pos, forwards);
+		if !self.walls.contains(&next) { moves.push(((next, direction), STEP_COST)); }
+		moves
+	}
+
+	/// Gets the lowest score of any path from the start, facing east, to the end.
+	pub fn lowest_score(&self) -> Option<u64> {
+		pathfinding::shortest_cost([(self.start, Direction::East)], |state| self.moves(state, true), |(pos, _)| pos == self.end)
+	}
+
+	/// Gets every tile on at least one path with the lowest score, or an empty set if the end can't be reached.
+	pub fn best_path_tiles(&self) -> HashSet<Position> {
+		// A state is on a best path when the cheapest way to it plus the cheapest way from it to the end is the best score
+		let from_start = pathfinding::dijkstra([(self.start, Direction::East)], |state| self.moves(state, true));
+		let to_end = pathfinding::dijkstra(Direction::ALL.map(|direction| (self.end, direction)), |state| self.moves(state, false));
+		let Some(best) = Direction::ALL.iter().filter_map(|&direction| from_start.get(&(self.end, direction))).min() else {
+			return HashSet::new()
+		};
+		from_start.iter()
+			.filter(|(state, cost)| to_end.get(state).is_some_and(|remaining| *cost + remaining == *best))
+			.map(|((pos, _), _)| *pos)
+			.collect()
+	}
+}
+
+/// Part 1 solution - the lowest score a reindeer could get.
+fn part1_solution(input: &str) -> Result<Option<u64>, MazeParseError> {
+	Ok(Maze::try_from(input)?.lowest_score())
+}
+
+/// Part 2 solution - the number of tiles on any of the best paths.
+fn part2_solution(input: &str) -> Result<usize, MazeParseError> {
+	Ok(Maze::try_from(input)?.best_path_tiles().len())
+}
+
+/// Entry point
+fn main() {
+	let example = "###############
+#.......#....E#
+#.#.###.#.###.#
+#.....#.#...#.#
+#.###.#####.#.#
+#.#.#.......#.#
+#.#.#####.###.#
+#...........#.#
+###.#.#####.#.#
+#...#.....#.#.#
+#.#.#.###.#.#.#
+#.....#...#.#.#
+#.###.#.#.#.#.#
+#S..#.....#...#
+###############";
+	let example2 = "#################
+#...#...#...#..E#
+#.#.#.#.#.#.#.#.#
+#.#.#.#...#...#.#
+#.#.#.#.###.#.#.#
+#...#.#.#.....#.#
+#.#.#.#.#.#####.#
+#.#...#.#.#.....#
+#.#.#####.#.###.#
+#.#.#.......#...#
+#.#.###.#####.###
+#.#.#...#.....#.#
+#.#.#.#####.###.#
+#.#.#.........#.#
+#.#.#.#########.#
+#S#.............#
+#################";
+
+	println!("Part 1 Solution on Example: {}", report(part1_solution(example), "solving day 16 part 1"));
+	println!("Part 1 Solution on Second Example: {}", report(part1_solution(example2), "solving day 16 part 1"));
+	println!("Part 2 Solution on Example: {}", report(part2_solution(example), "solving day 16 part 2"));
+	println!("Part 2 Solution on Second Example: {}", report(part2_solution(example2), "solving day 16 part 2"));
+
+	// Pass the input with `--file <path>`
+	let args = std::env::args().collect::<Vec<_>>();
+	if let Some(path) = args.iter().skip_while(|&arg| arg != "--file").nth(1) {
+		let input = std::fs::read_to_string(path).expect("Failed to read input file");
+		println!("Part 1 Solution on Input: {}", report(part1_solution(&input), "solving day 16 part 1"));
+		println!("Part 2 Solution on Input: {}", report(part2_solution(&input), "solving day 16 part 2"));
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Tests turns cost more than steps, including paths needing several turns and unreachable ends
+	#[test]
+	fn test_best_paths() {
+		let maze = Maze::try_from("#####\n#..E#\n#.#.#\n#S..#\n#####").unwrap();
+		assert_eq!(maze.lowest_score(), Some(1004));
+		assert_eq!(maze.best_path_tiles().len(), 5);
+		let maze = Maze::try_from("#####\n#...#\n#.#.#\n#S#E#\n#####").unwrap();
+		assert_eq!(maze.lowest_score(), Some(3006));
+		assert_eq!(maze.best_path_tiles().len(), 7);
+		assert_eq!(Maze::try_from("#####\n#S#E#\n#####").unwrap().lowest_score(), None);
+	}
+
+}
@@ -0,0 +1,148 @@
+mod vm;
+
+use std::{error::Error, fmt::{self, Display, Formatter}, num::ParseIntError};
+
+use common::report::report;
+use vm::{Machine, Registers};
+
+/// Possible errors when parsing the computer's registers and program
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ComputerParseError {
+	MissingField { name: &'static str },
+	IntegerParseError { value: String, error: ParseIntError },
+	InvalidInstruction { value: u8 },
+}
+
+impl Display for ComputerParseError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		match self {
+			ComputerParseError::MissingField { name } => write!(f, "Missing {name}"),
+			ComputerParseError::IntegerParseError { value, error } => write!(f, "Invalid number '{value}', {error}"),
+			ComputerParseError::InvalidInstruction { value } => write!(f, "Invalid instruction {value}, expected 0 to 7"),
+		}
+	}
+}
+
+impl Error for ComputerParseError {}
+
+/// Parses a computer from its registers and program, in format:
+/// ```txt
+/// Register A: 729
+/// Register B: 0
+/// Register C: 0
+///
+/// Program: 0,1,5,4,3,0
+/// ```
+pub fn parse_machine(input: &str) -> Result<Machine, ComputerParseError> {
+	let field = |name: &'static str| input.lines()
+		.find_map(|line| line.strip_prefix(name))
+		.map(str::trim)
+		.ok_or(ComputerParseError::MissingField { name });
+	let parse = |value: &str| value.parse::<u64>()
+		.map_err(|error| ComputerParseError::IntegerParseError { value: value.into(), error });
+
+	let registers = Registers { a: parse(field("Register A:")?)?, b: parse(field("Register B:")?)?, c: parse(field("Register C:")?)? };
+	let program = field("Program:")?.split(',').map(|value| match parse(value.trim())? {
+		value @ 0..=7 => Ok(value as u8),
+		value => Err(ComputerParseError::InvalidInstruction { value: value.min(u8::MAX as u64) as u8 }),
+	}).collect::<Result<_, _>>()?;
+	Ok(Machine::new(registers, program))
+}
+
+/// Finds the lowest value of register A which makes the program output a copy of itself, or None if there isn't one.
+///
+/// This relies on the program being a loop which outputs once per iteration and then shifts A right by 3 bits, so the
+/// last output only depends on the highest 3 bits of A. Working backwards from the last output, each output adds the
+/// next 3 bits of A, keeping every candidate which matches the end of the program so far.
+pub fn find_quine(machine: &Machine) -> Option<u64> {
+	let program = &machine.program;
+	let output_for = |a: u64| Machine::new(Registers { a, ..machine.registers }, program.clone()).run().to_vec();
+	let mut candidates = vec![0u64];
+	for matched in 1..=program.len() {
+		candidates = candidates.into_iter()
+			.flat_map(|prefix| (0..8).map(move |bits| (prefix << 3) | bits))
+			.filter(|&a| output_for(a) == program[program.len() - matched..])
+			.collect();
+	}
+	candidates.into_iter().filter(|&a| a != 0 || program.is_empty()).min()
+}
+
+/// Part 1 solution - the program's output, joined with commas.
+fn part1_solution(input: &str) -> Result<String, ComputerParseError> {
+	let mut machine = parse_machine(input)?;
+	Ok(machine.run().iter().map(u8::to_string).collect::<Vec<_>>().join(","))
+}
+
+/// Part 2 solution - the lowest value of register A which makes the program output itself.
+fn part2_solution(input: &str) -> Result<Option<u64>, ComputerParseError> {
+	Ok(find_quine(&parse_machine(input)?))
+}
+
+/// Entry point
+fn main() {
+	let example = "Register A: 729
+Register B: 0
+Register C: 0
+
+Program: 0,1,5,4,3,0";
+	let example2 = "Register A: 2024
+Register B: 0
+Register C: 0
+
+Program: 0,3,5,4,3,0";
+
+	println!("Part 1 Solution on Example: {}", report(part1_solution(example), "solving day 17 part 1"));
+	println!("Part 2 Solution on Example: {}", report(part2_solution(example2), "solving day 17 part 2"));
+
+	// Pass the input with `--file <path>`, and add `--trace` to print every instruction executed
+	let args = std::env::args().collect::<Vec<_>>();
+	let Some(path) = args.iter().skip_while(|&arg| arg != "--file").nth(1) else { return };
+	let input = std::fs::read_to_string(path).expect("Failed to read input file");
+	if args.iter().any(|arg| arg == "--trace") {
+		let mut machine = parse_machine(&input).expect("Failed to parse computer");
+		for entry in machine.trace() { println!("{entry}"); }
+		return;
+	}
+	println!("Part 1 Solution on Input: {}", report(part1_solution(&input), "solving day 17 part 1"));
+	println!("Part 2 Solution on Input: {}", report(part2_solution(&input), "solving day 17 part 2"));
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use vm::Opcode;
+
+	/// Runs a program from the puzzle text's small examples
+	fn run(a: u64, b: u64, c: u64, program: &[u8]) -> Machine {
+		let mut machine = Machine::new(Registers { a, b, c }, program.to_vec());
+		machine.run();
+		machine
+	}
+
+	/// Tests the small examples of each instruction from the puzzle text
+	#[test]
+	fn test_instructions() {
+		assert_eq!(run(0, 0, 9, &[2, 6]).registers.b, 1);
+		assert_eq!(run(10, 0, 0, &[5, 0, 5, 1, 5, 4]).output, [0, 1, 2]);
+		let machine = run(2024, 0, 0, &[0, 1, 5, 4, 3, 0]);
+		assert_eq!(machine.output, [4, 2, 5, 6, 7, 7, 7, 7, 3, 1, 0]);
+		assert_eq!(machine.registers.a, 0);
+		assert_eq!(run(0, 29, 0, &[1, 7]).registers.b, 26);
+		assert_eq!(run(0, 2024, 43690, &[4, 0]).registers.b, 44354);
+	}
+
+	/// Tests the trace records each instruction and the quine search finds the puzzle's answer
+	#[test]
+	fn test_trace_and_quine() {
+		let mut machine = Machine::new(Registers { a: 10, b: 0, c: 0 }, vec![5, 0, 5, 1]);
+		let trace = machine.trace();
+		assert_eq!(trace.iter().map(|entry| (entry.pointer, entry.opcode, entry.output)).collect::<Vec<_>>(), [
+			(0, Opcode::Out, Some(0)),
+			(2, Opcode::Out, Some(1)),
+		]);
+		assert_eq!(machine.step(), None);
+		let machine = Machine::new(Registers::default(), vec![0, 3, 5, 4, 3, 0]);
+		assert_eq!(find_quine(&machine), Some(117440));
+	}
+
+}
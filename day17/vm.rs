@@ -0,0 +1,125 @@
+//! The 3-bit computer: three registers, a program of 3-bit numbers, and eight instructions which each take an operand.
+
+use std::fmt::{self, Display, Formatter};
+
+/// The eight instructions, each followed by an operand in the program
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Opcode {
+	/// `A = A >> combo`
+	Adv,
+	/// `B = B ^ literal`
+	Bxl,
+	/// `B = combo % 8`
+	Bst,
+	/// Jumps to `literal` if A isn't 0.
+	Jnz,
+	/// `B = B ^ C`, ignoring the operand.
+	Bxc,
+	/// Outputs `combo % 8`.
+	Out,
+	/// `B = A >> combo`
+	Bdv,
+	/// `C = A >> combo`
+	Cdv,
+}
+
+impl From<u8> for Opcode {
+	/// Converts from the lowest 3 bits of a number.
+	fn from(value: u8) -> Self {
+		[Opcode::Adv, Opcode::Bxl, Opcode::Bst, Opcode::Jnz, Opcode::Bxc, Opcode::Out, Opcode::Bdv, Opcode::Cdv][value as usize & 7]
+	}
+}
+
+/// The A, B, and C registers
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Registers { pub a: u64, pub b: u64, pub c: u64 }
+
+/// What happened when executing a single instruction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TraceEntry {
+	/// Where the instruction was in the program.
+	pub pointer: usize,
+	pub opcode: Opcode,
+	pub operand: u8,
+	/// The registers after the instruction executed.
+	pub registers: Registers,
+	/// The value the instruction output, if it was `out`.
+	pub output: Option<u8>,
+}
+
+impl Display for TraceEntry {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		let Registers { a, b, c } = self.registers;
+		write!(f, "{:>3}: {:?} {} -> A={a} B={b} C={c}", self.pointer, self.opcode, self.operand)?;
+		if let Some(output) = self.output { write!(f, ", out {output}")?; }
+		Ok(())
+	}
+}
+
+/// A computer running a program, which can be stepped one instruction at a time
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Machine {
+	pub registers: Registers,
+	pub program: Vec<u8>,
+	/// The index of the next instruction's opcode in the program.
+	pub pointer: usize,
+	/// Everything output so far.
+	pub output: Vec<u8>,
+}
+
+impl Machine {
+	/// Creates a machine at the start of a program
+	pub fn new(registers: Registers, program: Vec<u8>) -> Self {
+		Self { registers, program, pointer: 0, output: Vec::new() }
+	}
+
+	/// Whether the machine has halted, by reading past the end of the program.
+	pub fn halted(&self) -> bool {
+		self.pointer + 1 >= self.program.len()
+	}
+
+	/// Gets the value of a combo operand, 0-3 are literal and 4-6 are the registers. 7 is reserved, and is treated as
+	/// 0 rather than failing.
+	fn combo(&self, operand: u8) -> u64 {
+		match operand {
+			0..=3 => operand as u64,
+			4 => self.registers.a,
+			5 => self.registers.b,
+			6 => self.registers.c,
+			_ => 0,
+		}
+	}
+
+	/// Executes the next instruction, or returns None if the machine has halted.
+	pub fn step(&mut self) -> Option<TraceEntry> {
+		if self.halted() { return None }
+		let (pointer, opcode, operand) = (self.pointer, Opcode::from(self.program[self.pointer]), self.program[self.pointer + 1]);
+		// Shifting by 64 or more clears every bit, rather than overflowing
+		let shifted = |value: u64, shift: u64| value.checked_shr(shift.min(u32::MAX as u64) as u32).unwrap_or(0);
+		let mut output = None;
+		self.pointer += 2;
+		match opcode {
+			Opcode::Adv => self.registers.a = shifted(self.registers.a, self.combo(operand)),
+			Opcode::Bxl => self.registers.b ^= operand as u64,
+			Opcode::Bst => self.registers.b = self.combo(operand) % 8,
+			Opcode::Jnz => if self.registers.a != 0 { self.pointer = operand as usize },
+			Opcode::Bxc => self.registers.b ^= self.registers.c,
+			Opcode::Out => output = Some((self.combo(operand) % 8) as u8),
+			Opcode::Bdv => self.registers.b = shifted(self.registers.a, self.combo(operand)),
+			Opcode::Cdv => self.registers.c = shifted(self.registers.a, self.combo(operand)),
+		}
+		self.output.extend(output);
+		Some(TraceEntry { pointer, opcode, operand, registers: self.registers, output })
+	}
+
+	/// Runs until the machine halts, returning the trace of every instruction executed.
+	pub fn trace(&mut self) -> Vec<TraceEntry> {
+		std::iter::from_fn(|| self.step()).collect()
+	}
+
+	/// Runs until the machine halts, returning everything output.
+	pub fn run(&mut self) -> &[u8] {
+		while self.step().is_some() {}
+		&self.output
+	}
+}
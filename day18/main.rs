@@ -0,0 +1,152 @@
+use std::{error::Error, fmt::{self, Display, Formatter}, num::ParseIntError};
+
+use common::{pathfinding::{distance_field, Position}, report::report};
+
+/// A square grid of bits, one per position, packed into words
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BitGrid {
+	words: Vec<u64>,
+	size: usize,
+}
+
+impl BitGrid {
+	/// Creates a grid of `size` by `size` unset bits
+	pub fn new(size: usize) -> Self {
+		Self { words: vec![0; (size * size).div_ceil(64)], size }
+	}
+
+	/// Gets the bit at a position, positions outside the grid are unset.
+	pub fn get(&self, pos: Position) -> bool {
+		if pos.x >= self.size || pos.y >= self.size { return false }
+		let idx = pos.y * self.size + pos.x;
+		self.words[idx / 64] & (1 << (idx % 64)) != 0
+	}
+
+	/// Sets the bit at a position, positions outside the grid are ignored.
+	pub fn set(&mut self, pos: Position) {
+		if pos.x >= self.size || pos.y >= self.size { return }
+		let idx = pos.y * self.size + pos.x;
+		self.words[idx / 64] |= 1 << (idx % 64);
+	}
+}
+
+/// Possible errors when parsing the falling bytes, lines are 1-indexed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ByteParseError {
+	InvalidPosition { line: usize, text: String },
+	IntegerParseError { line: usize, text: String, error: ParseIntError },
+}
+
+impl Display for ByteParseError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		match self {
+			ByteParseError::InvalidPosition { line, text } => write!(f, "Line {line}: invalid position '{text}', expected x,y"),
+			ByteParseError::IntegerParseError { line, text, error } => write!(f, "Line {line}: invalid coordinate '{text}', {error}"),
+		}
+	}
+}
+
+impl Error for ByteParseError {}
+
+/// Parses the positions bytes fall at, one `x,y` per line.
+pub fn parse_bytes(input: &str) -> Result<Vec<Position>, ByteParseError> {
+	input.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()).map(|(idx, line)| {
+		let parse = |text: &str| text.trim().parse::<usize>()
+			.map_err(|error| ByteParseError::IntegerParseError { line: idx + 1, text: text.into(), error });
+		let (x, y) = line.split_once(',').ok_or_else(|| ByteParseError::InvalidPosition { line: idx + 1, text: line.into() })?;
+		Ok(Position { x: parse(x)?, y: parse(y)? })
+	}).collect()
+}
+
+/// Gets the fewest steps from the top-left to the bottom-right of a `size` by `size` grid avoiding corrupted positions,
+/// or None if there's no way through.
+pub fn shortest_path(corrupted: &BitGrid, size: usize) -> Option<usize> {
+	let distances = distance_field(size, size, Position { x: 0, y: 0 }, |pos| !corrupted.get(pos));
+	distances.last().and_then(|row| row.last()).copied().flatten()
+}
+
+/// Corrupts the first `count` bytes on a `size` by `size` grid.
+fn corrupt(bytes: &[Position], count: usize, size: usize) -> BitGrid {
+	let mut grid = BitGrid::new(size);
+	for &pos in bytes.iter().take(count) { grid.set(pos); }
+	grid
+}
+
+/// Finds the first byte which cuts off the bottom-right from the top-left, or None if they always stay connected.
+///
+/// Once blocked, the path stays blocked as more bytes fall, so the number of bytes is binary searched.
+pub fn first_blocking_byte(bytes: &[Position], size: usize) -> Option<Position> {
+	let blocked = (0..=bytes.len()).collect::<Vec<_>>()
+		.partition_point(|&count| shortest_path(&corrupt(bytes, count, size), size).is_some());
+	blocked.checked_sub(1).and_then(|idx| bytes.get(idx)).copied()
+}
+
+/// Part 1 solution - the fewest steps to the exit after `count` bytes have fallen.
+fn part1_solution(input: &str, size: usize, count: usize) -> Result<Option<usize>, ByteParseError> {
+	Ok(shortest_path(&corrupt(&parse_bytes(input)?, count, size), size))
+}
+
+/// Part 2 solution - the coordinates of the first byte which blocks the exit, as `x,y`.
+fn part2_solution(input: &str, size: usize) -> Result<Option<String>, ByteParseError> {
+	Ok(first_blocking_byte(&parse_bytes(input)?, size).map(|pos| format!("{},{}", pos.x, pos.y)))
+}
+
+/// Entry point
+fn main() {
+	let example = "5,4
+4,2
+4,5
+3,0
+2,1
+6,3
+2,4
+1,5
+0,6
+3,3
+2,6
+5,1
+1,2
+5,5
+2,5
+6,5
+1,4
+0,4
+6,4
+1,1
+6,1
+1,0
+0,5
+1,6
+2,0";
+
+	println!("Part 1 Solution on Example: {}", report(part1_solution(example, 7, 12), "solving day 18 part 1"));
+	println!("Part 2 Solution on Example: {}", report(part2_solution(example, 7), "solving day 18 part 2"));
+
+	// Pass the input with `--file <path>`, on a 71 by 71 grid after 1024 bytes unless `--size N` or `--bytes N` is given
+	let args = std::env::args().collect::<Vec<_>>();
+	let flag = |name: &str, default: usize| args.iter().skip_while(|&arg| arg != name).nth(1)
+		.map_or(default, |value| value.parse().unwrap_or_else(|_| panic!("Invalid {name}, expected a number")));
+	if let Some(path) = args.iter().skip_while(|&arg| arg != "--file").nth(1) {
+		let input = std::fs::read_to_string(path).expect("Failed to read input file");
+		let size = flag("--size", 71);
+		println!("Part 1 Solution on Input: {}", report(part1_solution(&input, size, flag("--bytes", 1024)), "solving day 18 part 1"));
+		println!("Part 2 Solution on Input: {}", report(part2_solution(&input, size), "solving day 18 part 2"));
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Tests the path goes around bytes, and the first byte closing the path off is found
+	#[test]
+	fn test_blocking() {
+		let bytes = parse_bytes("1,0\n1,1\n0,2\n2,1").unwrap();
+		assert_eq!(shortest_path(&corrupt(&bytes, 0, 3), 3), Some(4));
+		assert_eq!(shortest_path(&corrupt(&bytes, 2, 3), 3), Some(4));
+		assert_eq!(shortest_path(&corrupt(&bytes, 3, 3), 3), None);
+		assert_eq!(first_blocking_byte(&bytes, 3), Some(Position { x: 0, y: 2 }));
+		assert_eq!(first_blocking_byte(&bytes[..2], 3), None);
+	}
+
+}
@@ -0,0 +1,119 @@
+use std::collections::HashMap;
+
+/// A trie over the available towel patterns, to find every pattern starting a design in one pass
+#[derive(Debug, Clone, Default)]
+pub struct PatternTrie {
+	children: HashMap<char, PatternTrie>,
+	/// Whether a pattern ends at this node.
+	terminal: bool,
+}
+
+impl PatternTrie {
+	/// Builds a trie from the available patterns
+	pub fn new<'a>(patterns: impl IntoIterator<Item = &'a str>) -> Self {
+		let mut trie = Self::default();
+		for pattern in patterns {
+			let node = pattern.chars().fold(&mut trie, |node, stripe| node.children.entry(stripe).or_default());
+			node.terminal = true;
+		}
+		trie
+	}
+
+	/// Gets the lengths of every pattern which `stripes` starts with, in ascending order.
+	pub fn prefix_lengths<'a>(&'a self, stripes: &'a [char]) -> impl Iterator<Item = usize> + 'a {
+		stripes.iter()
+			.scan(self, |node, stripe| { *node = node.children.get(stripe)?; Some(node.terminal) })
+			.enumerate()
+			.filter_map(|(idx, terminal)| terminal.then_some(idx + 1))
+	}
+}
+
+/// Counts the ways a design can be made by lining up towel patterns, each of which can be used any number of times.
+///
+/// `ways[idx]` is the number of ways to make the design from `idx` onwards, built up from the end of the design so each
+/// suffix is only counted once.
+pub fn count_arrangements(patterns: &PatternTrie, design: &str) -> u64 {
+	let stripes = design.chars().collect::<Vec<_>>();
+	let mut ways = vec![0u64; stripes.len() + 1];
+	ways[stripes.len()] = 1;
+	for idx in (0..stripes.len()).rev() {
+		ways[idx] = patterns.prefix_lengths(&stripes[idx..]).map(|length| ways[idx + length]).sum();
+	}
+	ways[0]
+}
+
+/// Parses the comma separated patterns, then a blank line, then one design per line.
+pub fn parse(input: &str) -> (PatternTrie, Vec<&str>) {
+	let (patterns, designs) = input.split_once("\n\n").unwrap_or((input, ""));
+	let patterns = PatternTrie::new(patterns.split(',').map(str::trim).filter(|pattern| !pattern.is_empty()));
+	(patterns, designs.lines().map(str::trim).filter(|design| !design.is_empty()).collect())
+}
+
+/// Part 1 solution - the number of designs which can be made.
+fn part1_solution(input: &str) -> usize {
+	let (patterns, designs) = parse(input);
+	designs.iter().filter(|design| count_arrangements(&patterns, design) > 0).count()
+}
+
+/// Part 2 solution - the total number of ways every design can be made.
+fn part2_solution(input: &str) -> u64 {
+	let (patterns, designs) = parse(input);
+	designs.iter().map(|design| count_arrangements(&patterns, design)).sum()
+}
+
+/// Entry point
+fn main() {
+	let example = "r, wr, b, g, bwu, rb, gb, br
+
+brwrr
+bggr
+gbbr
+rrbgbr
+ubwu
+bwurrg
+brgr
+bbrgwb";
+
+	println!("Part 1 Solution on Example: {:#?}", part1_solution(example));
+	println!("Part 2 Solution on Example: {:#?}", part2_solution(example));
+
+	// Pass the input with `--file <path>`
+	let args = std::env::args().collect::<Vec<_>>();
+	if let Some(path) = args.iter().skip_while(|&arg| arg != "--file").nth(1) {
+		let input = std::fs::read_to_string(path).expect("Failed to read input file");
+		println!("Part 1 Solution on Input: {:#?}", part1_solution(&input));
+		println!("Part 2 Solution on Input: {:#?}", part2_solution(&input));
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Counts arrangements by trying every pattern at the start of the design, recursively
+	fn brute_force(patterns: &[&str], design: &str) -> u64 {
+		if design.is_empty() { return 1 }
+		patterns.iter().filter_map(|pattern| design.strip_prefix(pattern)).map(|rest| brute_force(patterns, rest)).sum()
+	}
+
+	/// Tests the example designs from the puzzle text
+	#[test]
+	fn test_example() {
+		let patterns = PatternTrie::new(["r", "wr", "b", "g", "bwu", "rb", "gb", "br"]);
+		let ways = ["brwrr", "bggr", "gbbr", "rrbgbr", "ubwu", "bwurrg", "brgr", "bbrgwb"].map(|design| count_arrangements(&patterns, design));
+		assert_eq!(ways, [2, 1, 4, 6, 0, 1, 2, 0]);
+	}
+
+	/// Tests counting matches brute force on every short design over a few stripes
+	#[test]
+	fn test_matches_brute_force() {
+		let patterns = ["w", "u", "wu", "uw", "wuw", "uu", "b"];
+		let trie = PatternTrie::new(patterns);
+		let mut designs = vec![String::new()];
+		for _ in 0..7 {
+			designs = designs.iter().flat_map(|design| ["w", "u", "b", "r"].map(|stripe| format!("{design}{stripe}"))).collect();
+			for design in &designs { assert_eq!(count_arrangements(&trie, design), brute_force(&patterns, design), "{design}"); }
+		}
+	}
+
+}
@@ -0,0 +1,116 @@
+use std::{error::Error, fmt::{self, Display, Formatter}, num::ParseIntError};
+
+use common::report::report;
+
+/// Possible errors when parsing the reports, lines are 1-indexed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReportParseError {
+	/// A level wasn't an integer.
+	IntegerParseError { line: usize, text: String, error: ParseIntError },
+}
+
+impl Display for ReportParseError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		match self {
+			ReportParseError::IntegerParseError { line, text, error } => write!(f, "Line {line}: invalid level '{text}', {error}"),
+		}
+	}
+}
+
+impl Error for ReportParseError {}
+
+/// Parses the reports, one per line with its levels separated by whitespace.
+pub fn parse_reports(input: &str) -> Result<Vec<Vec<i64>>, ReportParseError> {
+	input.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()).map(|(idx, line)| {
+		line.split_whitespace().map(|text| {
+			text.parse::<i64>().map_err(|error| ReportParseError::IntegerParseError { line: idx + 1, text: text.into(), error })
+		}).collect()
+	}).collect()
+}
+
+/// Checks whether a step between two adjacent levels is safe when the levels should be increasing (or decreasing).
+fn safe_step(from: i64, to: i64, increasing: bool) -> bool {
+	let change = if increasing { to - from } else { from - to };
+	(1..=3).contains(&change)
+}
+
+/// Finds the first index whose step to the next level isn't safe, skipping the level at index `skip` if given.
+fn first_unsafe_step(levels: &[i64], increasing: bool, skip: Option<usize>) -> Option<usize> {
+	let kept = levels.iter().enumerate().filter(|&(idx, _)| Some(idx) != skip).collect::<Vec<_>>();
+	kept.windows(2).find(|pair| !safe_step(*pair[0].1, *pair[1].1, increasing)).map(|pair| pair[0].0)
+}
+
+/// Checks whether a report is safe - the levels are all increasing or all decreasing, by 1 to 3 each step.
+pub fn is_safe(levels: &[i64]) -> bool {
+	[true, false].into_iter().any(|increasing| first_unsafe_step(levels, increasing, None).is_none())
+}
+
+/// Checks whether a report is safe with the Problem Dampener, which allows removing any single level.
+///
+/// Rather than trying every removal, the first unsafe step must involve the removed level since every other step is
+/// kept as is, so only the two levels of that step need to be tried for each direction.
+pub fn is_safe_with_dampener(levels: &[i64]) -> bool {
+	[true, false].into_iter().any(|increasing| {
+		let Some(idx) = first_unsafe_step(levels, increasing, None) else { return true };
+		[idx, idx + 1].into_iter().any(|skip| first_unsafe_step(levels, increasing, Some(skip)).is_none())
+	})
+}
+
+/// Part 1 solution - the number of safe reports.
+fn part1_solution(input: &str) -> Result<usize, ReportParseError> {
+	Ok(parse_reports(input)?.iter().filter(|levels| is_safe(levels)).count())
+}
+
+/// Part 2 solution - the number of safe reports when a single level may be removed from each.
+fn part2_solution(input: &str) -> Result<usize, ReportParseError> {
+	Ok(parse_reports(input)?.iter().filter(|levels| is_safe_with_dampener(levels)).count())
+}
+
+/// Entry point
+fn main() {
+	let example = "7 6 4 2 1
+1 2 7 8 9
+9 7 6 2 1
+1 3 2 4 5
+8 6 4 4 1
+1 3 6 7 9";
+	// Solve another input instead of the bundled one with `--file <path>`
+	let args = std::env::args().collect::<Vec<_>>();
+	let file = args.iter().skip_while(|&arg| arg != "--file").nth(1)
+		.map(|path| std::fs::read_to_string(path).expect("Failed to read input file"));
+	let input = file.as_deref().unwrap_or(include_str!("day2.txt"));
+
+	println!("Part 1 Solution on Example: {}", report(part1_solution(example), "solving day 2 part 1"));
+	println!("Part 1 Solution on Input: {}", report(part1_solution(input), "solving day 2 part 1"));
+
+	println!("Part 2 Solution on Example: {}", report(part2_solution(example), "solving day 2 part 2"));
+	println!("Part 2 Solution on Input: {}", report(part2_solution(input), "solving day 2 part 2"));
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Tests each example report against the puzzle text
+	#[test]
+	fn test_example_reports() {
+		let reports = parse_reports("7 6 4 2 1\n1 2 7 8 9\n9 7 6 2 1\n1 3 2 4 5\n8 6 4 4 1\n1 3 6 7 9").unwrap();
+		assert_eq!(reports.iter().map(|levels| is_safe(levels)).collect::<Vec<_>>(), [true, false, false, false, false, true]);
+		assert_eq!(
+			reports.iter().map(|levels| is_safe_with_dampener(levels)).collect::<Vec<_>>(),
+			[true, false, false, true, true, true],
+		);
+	}
+
+	/// Tests the dampener matches trying every removal, including removing the first or last level
+	#[test]
+	fn test_dampener_matches_brute_force() {
+		let brute_force = |levels: &[i64]| (0..levels.len()).any(|skip| {
+			is_safe(&levels.iter().enumerate().filter(|&(idx, _)| idx != skip).map(|(_, &level)| level).collect::<Vec<_>>())
+		});
+		for levels in [vec![9, 1, 2, 3], vec![1, 2, 3, 9], vec![5, 1, 2, 3, 4], vec![1, 5, 2, 3], vec![3, 2, 5, 6], vec![1, 1, 1]] {
+			assert_eq!(is_safe_with_dampener(&levels), brute_force(&levels), "{levels:?}");
+		}
+	}
+
+}
@@ -0,0 +1,157 @@
+use std::{error::Error, fmt::{self, Display, Formatter}};
+
+use common::{pathfinding::{distance_field, Position}, report::report};
+
+/// Possible errors when parsing the racetrack
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RacetrackParseError {
+	UnknownTile { line: usize, column: usize, character: char },
+	MissingStart,
+	MissingEnd,
+}
+
+impl Display for RacetrackParseError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		match self {
+			RacetrackParseError::UnknownTile { line, column, character } => write!(f, "Line {line}: unknown tile {character:?} at column {column}"),
+			RacetrackParseError::MissingStart => write!(f, "The racetrack has no start"),
+			RacetrackParseError::MissingEnd => write!(f, "The racetrack has no end"),
+		}
+	}
+}
+
+impl Error for RacetrackParseError {}
+
+/// The racetrack, its walls, start, and end
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Racetrack {
+	walls: Vec<Vec<bool>>,
+	start: Position,
+	end: Position,
+}
+
+impl TryFrom<&str> for Racetrack {
+	type Error = RacetrackParseError;
+
+	/// Converts from the map, `#` walls, `.` track, `S` the start, and `E` the end.
+	fn try_from(value: &str) -> Result<Self, RacetrackParseError> {
+		let (mut start, mut end) = (None, None);
+		let walls = value.lines().enumerate().map(|(y, line)| {
+			line.chars().enumerate().map(|(x, character)| match character {
+				'#' => Ok(true),
+				'.' => Ok(false),
+				'S' => { start = Some(Position { x, y }); Ok(false) },
+				'E' => { end = Some(Position { x, y }); Ok(false) },
+				character => Err(RacetrackParseError::UnknownTile { line: y + 1, column: x + 1, character }),
+			}).collect()
+		}).collect::<Result<_, _>>()?;
+		Ok(Self { walls, start: start.ok_or(RacetrackParseError::MissingStart)?, end: end.ok_or(RacetrackParseError::MissingEnd)? })
+	}
+}
+
+impl Racetrack {
+	/// Counts the cheats which save at least `min_saving` picoseconds, where a cheat moves through walls for up to
+	/// `max_length` picoseconds. Cheats are identified by where they start and end, so each pair is counted once.
+	///
+	/// With the fewest steps from the start and to the end at every cell, a cheat from `from` to `to` takes
+	/// `from_start[from] + distance + to_end[to]`, so only pairs of track cells within `max_length` need checking.
+	pub fn count_cheats(&self, max_length: usize, min_saving: usize) -> usize {
+		let (height, width) = (self.walls.len(), self.walls.first().map_or(0, Vec::len));
+		let open = |pos: Position| !self.walls[pos.y][pos.x];
+		let from_start = distance_field(width, height, self.start, open);
+		let to_end = distance_field(width, height, self.end, open);
+		let Some(baseline) = from_start[self.end.y][self.end.x] else { return 0 };
+
+		let radius = max_length as isize;
+		let mut count = 0;
+		for (y, row) in from_start.iter().enumerate() {
+			for (x, distance) in row.iter().enumerate() {
+				let Some(distance) = distance else { continue };
+				for d_y in -radius..=radius {
+					let remaining = radius - d_y.abs();
+					for d_x in -remaining..=remaining {
+						let (Some(to_x), Some(to_y)) = (x.checked_add_signed(d_x), y.checked_add_signed(d_y)) else { continue };
+						let Some(Some(left)) = to_end.get(to_y).and_then(|row| row.get(to_x)) else { continue };
+						let total = distance + d_x.unsigned_abs() + d_y.unsigned_abs() + left;
+						if total + min_saving <= baseline { count += 1; }
+					}
+				}
+			}
+		}
+		count
+	}
+}
+
+/// Part 1 solution - the number of cheats of up to 2 picoseconds saving at least `min_saving` picoseconds.
+fn part1_solution(input: &str, min_saving: usize) -> Result<usize, RacetrackParseError> {
+	Ok(Racetrack::try_from(input)?.count_cheats(2, min_saving))
+}
+
+/// Part 2 solution - the number of cheats of up to 20 picoseconds saving at least `min_saving` picoseconds.
+fn part2_solution(input: &str, min_saving: usize) -> Result<usize, RacetrackParseError> {
+	Ok(Racetrack::try_from(input)?.count_cheats(20, min_saving))
+}
+
+/// Entry point
+fn main() {
+	let example = "###############
+#...#...#.....#
+#.#.#.#.#.###.#
+#S#...#.#.#...#
+#######.#.#.###
+#######.#.#...#
+#######.#.###.#
+###..E#...#...#
+###.#######.###
+#...###...#...#
+#.#####.#.###.#
+#.#...#.#.#...#
+#.#.#.#.#.#.###
+#...#...#...###
+###############";
+
+	// The example doesn't have any cheats saving 100 picoseconds, so count those saving any time or at least 50
+	println!("Part 1 Solution on Example: {}", report(part1_solution(example, 1), "solving day 20 part 1"));
+	println!("Part 2 Solution on Example: {}", report(part2_solution(example, 50), "solving day 20 part 2"));
+
+	// Pass the input with `--file <path>`
+	let args = std::env::args().collect::<Vec<_>>();
+	if let Some(path) = args.iter().skip_while(|&arg| arg != "--file").nth(1) {
+		let input = std::fs::read_to_string(path).expect("Failed to read input file");
+		println!("Part 1 Solution on Input: {}", report(part1_solution(&input, 100), "solving day 20 part 1"));
+		println!("Part 2 Solution on Input: {}", report(part2_solution(&input, 100), "solving day 20 part 2"));
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const EXAMPLE: &str = "###############
+#...#...#.....#
+#.#.#.#.#.###.#
+#S#...#.#.#...#
+#######.#.#.###
+#######.#.#...#
+#######.#.###.#
+###..E#...#...#
+###.#######.###
+#...###...#...#
+#.#####.#.###.#
+#.#...#.#.#...#
+#.#.#.#.#.#.###
+#...#...#...###
+###############";
+
+	/// Tests the cheat counts against the puzzle text
+	#[test]
+	fn test_count_cheats() {
+		let track = Racetrack::try_from(EXAMPLE).unwrap();
+		assert_eq!(track.count_cheats(2, 64), 1);
+		assert_eq!(track.count_cheats(2, 20), 5);
+		assert_eq!(track.count_cheats(2, 1), 44);
+		assert_eq!(track.count_cheats(20, 76), 3);
+		assert_eq!(track.count_cheats(20, 50), 285);
+	}
+
+}
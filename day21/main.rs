@@ -0,0 +1,181 @@
+use std::{collections::HashMap, error::Error, fmt::{self, Display, Formatter}};
+
+use common::report::report;
+
+/// The numeric keypad on the door, a space marks the gap no robot arm may point at
+pub const NUMERIC_LAYOUT: &str = "789\n456\n123\n 0A";
+
+/// The directional keypad every robot (and you) presses
+pub const DIRECTIONAL_LAYOUT: &str = " ^A\n<v>";
+
+/// Possible errors when entering a code
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CodeError {
+	UnknownKey { line: usize, key: char },
+	MissingNumber { line: usize },
+}
+
+impl Display for CodeError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		match self {
+			CodeError::UnknownKey { line, key } => write!(f, "Line {line}: unknown key {key:?}"),
+			CodeError::MissingNumber { line } => write!(f, "Line {line}: the code has no number before the final A"),
+		}
+	}
+}
+
+impl Error for CodeError {}
+
+/// A keypad, mapping each key to its row and column
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Keypad {
+	keys: HashMap<char, (i32, i32)>,
+	gap: Option<(i32, i32)>,
+}
+
+impl Keypad {
+	/// Creates a keypad from its layout, one line per row with a space for the gap.
+	pub fn from_layout(layout: &str) -> Self {
+		let (mut keys, mut gap) = (HashMap::new(), None);
+		for (row, line) in layout.lines().enumerate() {
+			for (column, key) in line.chars().enumerate() {
+				let pos = (row as i32, column as i32);
+				if key == ' ' { gap = Some(pos) } else { keys.insert(key, pos); }
+			}
+		}
+		Self { keys, gap }
+	}
+
+	/// Whether the keypad has the given key
+	pub fn contains(&self, key: char) -> bool {
+		self.keys.contains_key(&key)
+	}
+
+	/// The directional presses worth considering to move the arm from one key to another, each ending in `A` to press
+	/// the key. Moving all horizontally then all vertically (or the reverse) is always at least as good as zig-zagging,
+	/// so at most two candidates are returned, skipping any which would sweep over the gap.
+	pub fn moves(&self, from: char, to: char) -> Vec<String> {
+		let (Some(&(from_row, from_col)), Some(&(to_row, to_col))) = (self.keys.get(&from), self.keys.get(&to)) else { return Vec::new() };
+		let vertical = if to_row > from_row { "v" } else { "^" }.repeat(from_row.abs_diff(to_row) as usize);
+		let horizontal = if to_col > from_col { ">" } else { "<" }.repeat(from_col.abs_diff(to_col) as usize);
+
+		let mut candidates = Vec::with_capacity(2);
+		if self.gap != Some((from_row, to_col)) { candidates.push(format!("{horizontal}{vertical}A")); }
+		if self.gap != Some((to_row, from_col)) { candidates.push(format!("{vertical}{horizontal}A")); }
+		candidates.dedup();
+		candidates
+	}
+}
+
+/// A numeric keypad operated through a chain of robots on directional keypads, with you on the last directional keypad.
+#[derive(Debug, Clone)]
+pub struct KeypadChain {
+	numeric: Keypad,
+	directional: Keypad,
+	robots: usize,
+	memo: HashMap<(char, char, usize), u64>,
+}
+
+impl KeypadChain {
+	/// Creates the chain with the given number of robots between you and the robot at the numeric keypad.
+	pub fn new(numeric: Keypad, directional: Keypad, robots: usize) -> Self {
+		Self { numeric, directional, robots, memo: HashMap::new() }
+	}
+
+	/// The puzzle's keypads with the given number of intermediate robots
+	pub fn with_robots(robots: usize) -> Self {
+		Self::new(Keypad::from_layout(NUMERIC_LAYOUT), Keypad::from_layout(DIRECTIONAL_LAYOUT), robots)
+	}
+
+	/// The fewest presses you need to get a sequence of directional presses entered `depth` directional keypads below
+	/// you, starting with every arm on `A`.
+	fn sequence_cost(&mut self, sequence: &str, depth: usize) -> u64 {
+		std::iter::once('A').chain(sequence.chars()).zip(sequence.chars()).map(|(from, to)| self.move_cost(from, to, depth)).sum()
+	}
+
+	/// The fewest presses you need to move an arm `depth` directional keypads below you from one key to another and press it.
+	/// Every arm above returns to `A` after each press, which is why the result can be memoized per pair of keys.
+	fn move_cost(&mut self, from: char, to: char, depth: usize) -> u64 {
+		if depth == 0 { return 1 }
+		if let Some(&cost) = self.memo.get(&(from, to, depth)) { return cost }
+		let cost = self.directional.moves(from, to).iter().map(|moves| self.sequence_cost(moves, depth - 1)).min().unwrap_or(u64::MAX);
+		self.memo.insert((from, to, depth), cost);
+		cost
+	}
+
+	/// The fewest presses you need to type the code on the numeric keypad
+	pub fn presses(&mut self, code: &str) -> u64 {
+		std::iter::once('A').chain(code.chars()).zip(code.chars()).map(|(from, to)| {
+			self.numeric.moves(from, to).iter().map(|moves| self.sequence_cost(moves, self.robots)).min().unwrap_or(u64::MAX)
+		}).sum()
+	}
+
+	/// The complexity of a code, its number of presses times its numeric part.
+	pub fn complexity(&mut self, code: &str, line: usize) -> Result<u64, CodeError> {
+		if let Some(key) = code.chars().find(|&key| !self.numeric.contains(key)) { return Err(CodeError::UnknownKey { line, key }) }
+		let number = code.trim_end_matches('A').parse::<u64>().map_err(|_| CodeError::MissingNumber { line })?;
+		Ok(self.presses(code) * number)
+	}
+}
+
+/// The sum of complexities of every code in the input, one per line, with the given number of intermediate robots.
+pub fn total_complexity(input: &str, robots: usize) -> Result<u64, CodeError> {
+	let mut chain = KeypadChain::with_robots(robots);
+	input.lines().enumerate().filter(|(_, line)| !line.trim().is_empty())
+		.map(|(index, line)| chain.complexity(line.trim(), index + 1)).sum()
+}
+
+/// Part 1 solution - the total complexity through two robots.
+fn part1_solution(input: &str) -> Result<u64, CodeError> {
+	total_complexity(input, 2)
+}
+
+/// Part 2 solution - the total complexity through twenty-five robots.
+fn part2_solution(input: &str) -> Result<u64, CodeError> {
+	total_complexity(input, 25)
+}
+
+/// Entry point
+fn main() {
+	let example = "029A\n980A\n179A\n456A\n379A";
+
+	println!("Part 1 Solution on Example: {}", report(part1_solution(example), "solving day 21 part 1"));
+	println!("Part 2 Solution on Example: {}", report(part2_solution(example), "solving day 21 part 2"));
+
+	// Pass the input with `--file <path>`
+	let args = std::env::args().collect::<Vec<_>>();
+	if let Some(path) = args.iter().skip_while(|&arg| arg != "--file").nth(1) {
+		let input = std::fs::read_to_string(path).expect("Failed to read input file");
+		println!("Part 1 Solution on Input: {}", report(part1_solution(&input), "solving day 21 part 1"));
+		println!("Part 2 Solution on Input: {}", report(part2_solution(&input), "solving day 21 part 2"));
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Tests the candidate moves avoid the gap on each keypad
+	#[test]
+	fn test_moves() {
+		let numeric = Keypad::from_layout(NUMERIC_LAYOUT);
+		assert_eq!(numeric.moves('A', '1'), vec!["^<<A"]);
+		assert_eq!(numeric.moves('7', '0'), vec![">vvvA"]);
+		assert_eq!(numeric.moves('2', '9'), vec![">^^A", "^^>A"]);
+		assert_eq!(numeric.moves('5', '5'), vec!["A"]);
+		let directional = Keypad::from_layout(DIRECTIONAL_LAYOUT);
+		assert_eq!(directional.moves('<', 'A'), vec![">>^A"]);
+	}
+
+	/// Tests the presses and complexities against the puzzle text
+	#[test]
+	fn test_presses() {
+		let mut chain = KeypadChain::with_robots(2);
+		assert_eq!(chain.presses("029A"), 68);
+		assert_eq!(chain.presses("379A"), 64);
+		assert_eq!(KeypadChain::with_robots(0).presses("029A"), 12);
+		assert_eq!(total_complexity("029A\n980A\n179A\n456A\n379A", 2), Ok(126384));
+		assert_eq!(total_complexity("02BA", 2), Err(CodeError::UnknownKey { line: 1, key: 'B' }));
+	}
+
+}
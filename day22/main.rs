@@ -0,0 +1,141 @@
+use std::{error::Error, fmt::{self, Display, Formatter}, num::ParseIntError};
+
+use common::report::report;
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+
+/// Every price change is between -9 and 9, so there are 19 possible changes
+const CHANGES: usize = 19;
+
+/// The number of distinct sequences of four price changes
+const SEQUENCES: usize = CHANGES.pow(4);
+
+/// Possible errors when parsing the initial secrets
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SecretParseError { pub line: usize, pub error: ParseIntError }
+
+impl Display for SecretParseError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		write!(f, "Line {}: invalid secret, {}", self.line, self.error)
+	}
+}
+
+impl Error for SecretParseError {}
+
+/// Parses the initial secret of each buyer, one per line.
+pub fn parse_secrets(input: &str) -> Result<Vec<u64>, SecretParseError> {
+	input.lines().enumerate().filter(|(_, line)| !line.trim().is_empty())
+		.map(|(index, line)| line.trim().parse().map_err(|error| SecretParseError { line: index + 1, error }))
+		.collect()
+}
+
+/// The next secret number after the given one
+pub fn next_secret(secret: u64) -> u64 {
+	const PRUNE: u64 = 16777216;
+	let secret = ((secret * 64) ^ secret) % PRUNE;
+	let secret = ((secret / 32) ^ secret) % PRUNE;
+	((secret * 2048) ^ secret) % PRUNE
+}
+
+/// The secrets following the given one, starting with the given one itself
+pub fn secrets(secret: u64) -> impl Iterator<Item = u64> {
+	std::iter::successors(Some(secret), |&secret| Some(next_secret(secret)))
+}
+
+/// Encodes four consecutive price changes into a single index below `SEQUENCES`.
+pub fn encode_changes(changes: [i8; 4]) -> usize {
+	changes.iter().fold(0, |index, &change| index * CHANGES + (change + 9) as usize)
+}
+
+/// Decodes an index from `encode_changes` back into the four price changes.
+pub fn decode_changes(mut index: usize) -> [i8; 4] {
+	let mut changes = [0; 4];
+	for change in changes.iter_mut().rev() {
+		*change = (index % CHANGES) as i8 - 9;
+		index /= CHANGES;
+	}
+	changes
+}
+
+/// Adds the price each buyer sells at for every sequence of four price changes into `totals`, counting only the first
+/// time a buyer sees the sequence. `seen` marks which sequences the buyer has sold on with `buyer`, which must be
+/// distinct for every buyer sharing the same `seen` array.
+fn add_prices(secret: u64, steps: usize, buyer: u32, totals: &mut [u32], seen: &mut [u32]) {
+	let prices = secrets(secret).take(steps + 1).map(|secret| (secret % 10) as u8).collect::<Vec<_>>();
+	for window in prices.windows(5) {
+		let changes = [0, 1, 2, 3].map(|i| window[i + 1] as i8 - window[i] as i8);
+		let index = encode_changes(changes);
+		if seen[index] == buyer { continue }
+		seen[index] = buyer;
+		totals[index] += window[4] as u32;
+	}
+}
+
+/// The total bananas each sequence of price changes earns across every buyer, indexed by `encode_changes`.
+pub fn sequence_totals(secrets: &[u64], steps: usize) -> Vec<u32> {
+	let buyers = secrets.iter().zip(1..).collect::<Vec<_>>();
+	buyers.par_iter()
+		.fold(|| (vec![0; SEQUENCES], vec![0; SEQUENCES]), |(mut totals, mut seen), &(&secret, buyer)| {
+			add_prices(secret, steps, buyer, &mut totals, &mut seen);
+			(totals, seen)
+		})
+		.map(|(totals, _)| totals)
+		.reduce(|| vec![0; SEQUENCES], |mut totals, other| {
+			totals.iter_mut().zip(other).for_each(|(total, other)| *total += other);
+			totals
+		})
+}
+
+/// The sequence of four price changes earning the most bananas, and how many it earns.
+pub fn best_sequence(secrets: &[u64], steps: usize) -> ([i8; 4], u32) {
+	let totals = sequence_totals(secrets, steps);
+	let (index, &bananas) = totals.iter().enumerate().max_by_key(|&(index, &total)| (total, std::cmp::Reverse(index)))
+		.expect("There is always at least one sequence");
+	(decode_changes(index), bananas)
+}
+
+/// Part 1 solution - the sum of each buyer's 2000th new secret.
+fn part1_solution(input: &str) -> Result<u64, SecretParseError> {
+	Ok(parse_secrets(input)?.par_iter().map(|&secret| secrets(secret).nth(2000).expect("Secrets never end")).sum())
+}
+
+/// Part 2 solution - the most bananas a single sequence of four price changes can earn.
+fn part2_solution(input: &str) -> Result<u32, SecretParseError> {
+	Ok(best_sequence(&parse_secrets(input)?, 2000).1)
+}
+
+/// Entry point
+fn main() {
+	println!("Part 1 Solution on Example: {}", report(part1_solution("1\n10\n100\n2024"), "solving day 22 part 1"));
+	println!("Part 2 Solution on Example: {}", report(part2_solution("1\n2\n3\n2024"), "solving day 22 part 2"));
+
+	// Pass the input with `--file <path>`
+	let args = std::env::args().collect::<Vec<_>>();
+	if let Some(path) = args.iter().skip_while(|&arg| arg != "--file").nth(1) {
+		let input = std::fs::read_to_string(path).expect("Failed to read input file");
+		println!("Part 1 Solution on Input: {}", report(part1_solution(&input), "solving day 22 part 1"));
+		println!("Part 2 Solution on Input: {}", report(part2_solution(&input), "solving day 22 part 2"));
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Tests the secret evolution against the puzzle text
+	#[test]
+	fn test_secrets() {
+		let expected = [15887950, 16495136, 527345, 704524, 1553684, 12683156, 11100544, 12249484, 7753432, 5908254];
+		assert!(secrets(123).skip(1).take(10).eq(expected));
+		assert_eq!(part1_solution("1\n10\n100\n2024"), Ok(37327623));
+	}
+
+	/// Tests the best sequence against the puzzle text, and that encoding round trips
+	#[test]
+	fn test_best_sequence() {
+		assert_eq!(best_sequence(&[1, 2, 3, 2024], 2000), ([-2, 1, -1, 3], 23));
+		for changes in [[-9, -9, -9, -9], [9, 9, 9, 9], [-2, 1, -1, 3]] {
+			assert_eq!(decode_changes(encode_changes(changes)), changes);
+		}
+	}
+
+}
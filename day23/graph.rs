@@ -0,0 +1,80 @@
+//! Undirected graphs and the cliques within them.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+/// An undirected graph, storing each node's neighbors
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Graph<N> {
+	adjacency: BTreeMap<N, BTreeSet<N>>,
+}
+
+impl<N> Default for Graph<N> {
+	fn default() -> Self {
+		Self { adjacency: BTreeMap::new() }
+	}
+}
+
+impl<N: Copy + Ord> Graph<N> {
+	/// Connects two nodes, adding either if they aren't in the graph yet.
+	pub fn connect(&mut self, a: N, b: N) {
+		self.adjacency.entry(a).or_default().insert(b);
+		self.adjacency.entry(b).or_default().insert(a);
+	}
+
+	/// Every node in the graph, in order
+	pub fn nodes(&self) -> impl Iterator<Item = N> + '_ {
+		self.adjacency.keys().copied()
+	}
+
+	/// The neighbors of a node, in order
+	pub fn neighbors(&self, node: N) -> impl Iterator<Item = N> + '_ {
+		self.adjacency.get(&node).into_iter().flatten().copied()
+	}
+
+	/// Whether two nodes are connected
+	pub fn connected(&self, a: N, b: N) -> bool {
+		self.adjacency.get(&a).is_some_and(|neighbors| neighbors.contains(&b))
+	}
+
+	/// Every set of three mutually connected nodes, each sorted, found by only walking from smaller to larger nodes.
+	pub fn triangles(&self) -> Vec<[N; 3]> {
+		let mut triangles = Vec::new();
+		for (&a, neighbors) in &self.adjacency {
+			for &b in neighbors.range(a..).skip_while(|&&b| b == a) {
+				for &c in neighbors.range(b..).skip_while(|&&c| c == b) {
+					if self.connected(b, c) { triangles.push([a, b, c]); }
+				}
+			}
+		}
+		triangles
+	}
+
+	/// The largest set of mutually connected nodes, sorted, found with the pivoting Bron–Kerbosch algorithm.
+	pub fn maximum_clique(&self) -> BTreeSet<N> {
+		let mut best = BTreeSet::new();
+		self.bron_kerbosch(BTreeSet::new(), self.nodes().collect(), BTreeSet::new(), &mut best);
+		best
+	}
+
+	/// Extends the clique `current` with `candidates`, never reporting a clique which could include a node in `excluded`.
+	fn bron_kerbosch(&self, current: BTreeSet<N>, mut candidates: BTreeSet<N>, mut excluded: BTreeSet<N>, best: &mut BTreeSet<N>) {
+		if candidates.is_empty() && excluded.is_empty() {
+			if current.len() > best.len() { *best = current; }
+			return;
+		}
+		if current.len() + candidates.len() <= best.len() { return }
+
+		// Any maximal clique contains either the pivot or one of its non-neighbors, so only those need trying
+		let pivot = candidates.union(&excluded).copied().max_by_key(|&node| self.neighbors(node).filter(|n| candidates.contains(n)).count())
+			.expect("Either set is non-empty");
+		let tries = candidates.iter().copied().filter(|&node| !self.connected(pivot, node)).collect::<Vec<_>>();
+		for node in tries {
+			let neighbors = self.adjacency.get(&node).expect("Candidates are in the graph");
+			let mut clique = current.clone();
+			clique.insert(node);
+			self.bron_kerbosch(clique, &candidates & neighbors, &excluded & neighbors, best);
+			candidates.remove(&node);
+			excluded.insert(node);
+		}
+	}
+}
@@ -0,0 +1,97 @@
+mod graph;
+
+use std::{error::Error, fmt::{self, Display, Formatter}};
+
+use common::report::report;
+use graph::Graph;
+
+/// Possible errors when parsing the network map
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NetworkParseError {
+	MissingDash { line: usize },
+	InvalidName { line: usize, name: String },
+}
+
+impl Display for NetworkParseError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		match self {
+			NetworkParseError::MissingDash { line } => write!(f, "Line {line}: expected two computers joined by '-'"),
+			NetworkParseError::InvalidName { line, name } => write!(f, "Line {line}: invalid computer name '{name}'"),
+		}
+	}
+}
+
+impl Error for NetworkParseError {}
+
+/// A computer's two-letter name
+pub type Computer = [u8; 2];
+
+/// Parses a computer's name, which must be two lowercase letters.
+fn parse_computer(name: &str, line: usize) -> Result<Computer, NetworkParseError> {
+	match name.as_bytes() {
+		&[a, b] if a.is_ascii_lowercase() && b.is_ascii_lowercase() => Ok([a, b]),
+		_ => Err(NetworkParseError::InvalidName { line, name: name.to_string() }),
+	}
+}
+
+/// Parses the network map, one connection `ab-cd` per line.
+pub fn parse_network(input: &str) -> Result<Graph<Computer>, NetworkParseError> {
+	let mut network = Graph::default();
+	for (index, line) in input.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()) {
+		let (a, b) = line.trim().split_once('-').ok_or(NetworkParseError::MissingDash { line: index + 1 })?;
+		network.connect(parse_computer(a, index + 1)?, parse_computer(b, index + 1)?);
+	}
+	Ok(network)
+}
+
+/// The LAN party's password, the names of its computers in order joined by commas.
+pub fn password(network: &Graph<Computer>) -> String {
+	network.maximum_clique().iter().map(|name| String::from_utf8_lossy(name).into_owned()).collect::<Vec<_>>().join(",")
+}
+
+/// Part 1 solution - the number of sets of three connected computers with a computer starting with `t`.
+fn part1_solution(input: &str) -> Result<usize, NetworkParseError> {
+	let network = parse_network(input)?;
+	Ok(network.triangles().iter().filter(|triangle| triangle.iter().any(|name| name[0] == b't')).count())
+}
+
+/// Part 2 solution - the password to the LAN party.
+fn part2_solution(input: &str) -> Result<String, NetworkParseError> {
+	Ok(password(&parse_network(input)?))
+}
+
+/// Entry point
+fn main() {
+	let example = "kh-tc\nqp-kh\nde-cg\nka-co\nyn-aq\nqp-ub\ncg-tb\nvc-aq\ntb-ka\nwh-tc\nyn-cg\nkh-ub\nta-co\nde-co\ntc-td\ntb-wq\nwh-td
+ta-ka\ntd-qp\naq-cg\nwq-ub\nub-vc\nde-ta\nwq-aq\nwq-vc\nwh-yn\nka-de\nkh-ta\nco-tc\nwh-qp\ntb-vc\ntd-yn";
+
+	println!("Part 1 Solution on Example: {}", report(part1_solution(example), "solving day 23 part 1"));
+	println!("Part 2 Solution on Example: {}", report(part2_solution(example), "solving day 23 part 2"));
+
+	// Pass the input with `--file <path>`
+	let args = std::env::args().collect::<Vec<_>>();
+	if let Some(path) = args.iter().skip_while(|&arg| arg != "--file").nth(1) {
+		let input = std::fs::read_to_string(path).expect("Failed to read input file");
+		println!("Part 1 Solution on Input: {}", report(part1_solution(&input), "solving day 23 part 1"));
+		println!("Part 2 Solution on Input: {}", report(part2_solution(&input), "solving day 23 part 2"));
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const EXAMPLE: &str = "kh-tc\nqp-kh\nde-cg\nka-co\nyn-aq\nqp-ub\ncg-tb\nvc-aq\ntb-ka\nwh-tc\nyn-cg\nkh-ub\nta-co\nde-co\ntc-td\ntb-wq\nwh-td
+ta-ka\ntd-qp\naq-cg\nwq-ub\nub-vc\nde-ta\nwq-aq\nwq-vc\nwh-yn\nka-de\nkh-ta\nco-tc\nwh-qp\ntb-vc\ntd-yn";
+
+	/// Tests the triangles and password against the puzzle text
+	#[test]
+	fn test_network() {
+		let network = parse_network(EXAMPLE).unwrap();
+		assert_eq!(network.triangles().len(), 12);
+		assert_eq!(part1_solution(EXAMPLE), Ok(7));
+		assert_eq!(password(&network), "co,de,ka,ta");
+		assert_eq!(parse_network("kh-tc\nkhtc"), Err(NetworkParseError::MissingDash { line: 2 }));
+	}
+
+}
@@ -0,0 +1,301 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::error::Error;
+use std::fmt::{self, Display, Formatter, Write};
+
+use common::report::report;
+
+/// Possible errors when parsing the circuit
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CircuitParseError {
+	InvalidWire { line: usize },
+	InvalidGate { line: usize },
+	UnknownOperation { line: usize, operation: String },
+}
+
+impl Display for CircuitParseError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		match self {
+			CircuitParseError::InvalidWire { line } => write!(f, "Line {line}: expected a wire's initial value like 'x00: 1'"),
+			CircuitParseError::InvalidGate { line } => write!(f, "Line {line}: expected a gate like 'x00 AND y00 -> z00'"),
+			CircuitParseError::UnknownOperation { line, operation } => write!(f, "Line {line}: unknown operation '{operation}'"),
+		}
+	}
+}
+
+impl Error for CircuitParseError {}
+
+/// Possible errors when evaluating the circuit
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CircuitError {
+	/// Gates whose inputs never receive a value, either from an undriven wire or a loop
+	Unresolved { wires: Vec<String> },
+}
+
+impl Display for CircuitError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		match self {
+			CircuitError::Unresolved { wires } => write!(f, "Wires never receive a value: {}", wires.join(", ")),
+		}
+	}
+}
+
+impl Error for CircuitError {}
+
+/// Possible errors when solving part 1
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SolutionError {
+	Parse(CircuitParseError),
+	Circuit(CircuitError),
+}
+
+impl Display for SolutionError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		match self {
+			SolutionError::Parse(_) => write!(f, "failed to parse the circuit"),
+			SolutionError::Circuit(_) => write!(f, "failed to evaluate the circuit"),
+		}
+	}
+}
+
+impl Error for SolutionError {
+	fn source(&self) -> Option<&(dyn Error + 'static)> {
+		match self {
+			SolutionError::Parse(error) => Some(error),
+			SolutionError::Circuit(error) => Some(error),
+		}
+	}
+}
+
+impl From<CircuitParseError> for SolutionError {
+	fn from(error: CircuitParseError) -> Self { SolutionError::Parse(error) }
+}
+
+impl From<CircuitError> for SolutionError {
+	fn from(error: CircuitError) -> Self { SolutionError::Circuit(error) }
+}
+
+/// A logic gate's operation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Operation { And, Or, Xor }
+
+impl Operation {
+	/// Applies the operation to two values
+	pub fn apply(self, a: bool, b: bool) -> bool {
+		match self {
+			Operation::And => a && b,
+			Operation::Or => a || b,
+			Operation::Xor => a ^ b,
+		}
+	}
+
+	/// The operation's name in the puzzle input
+	pub fn name(self) -> &'static str {
+		match self {
+			Operation::And => "AND",
+			Operation::Or => "OR",
+			Operation::Xor => "XOR",
+		}
+	}
+}
+
+/// A gate combining two input wires into an output wire
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Gate {
+	pub inputs: [String; 2],
+	pub operation: Operation,
+	pub output: String,
+}
+
+/// The full circuit, the initial wire values and every gate
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Circuit {
+	pub initial: BTreeMap<String, bool>,
+	pub gates: Vec<Gate>,
+}
+
+impl TryFrom<&str> for Circuit {
+	type Error = CircuitParseError;
+
+	/// Converts from the initial values `x00: 1`, a blank line, then gates `x00 AND y00 -> z00`.
+	fn try_from(value: &str) -> Result<Self, CircuitParseError> {
+		let mut circuit = Circuit::default();
+		let mut lines = value.lines().enumerate().map(|(index, line)| (index + 1, line.trim()));
+		for (line, text) in lines.by_ref().take_while(|(_, text)| !text.is_empty()) {
+			let (wire, value) = text.split_once(": ").ok_or(CircuitParseError::InvalidWire { line })?;
+			let value = match value { "0" => false, "1" => true, _ => return Err(CircuitParseError::InvalidWire { line }) };
+			circuit.initial.insert(wire.to_string(), value);
+		}
+		for (line, text) in lines.filter(|(_, text)| !text.is_empty()) {
+			let [a, operation, b, "->", output] = text.split_whitespace().collect::<Vec<_>>()[..] else {
+				return Err(CircuitParseError::InvalidGate { line })
+			};
+			let operation = match operation {
+				"AND" => Operation::And,
+				"OR" => Operation::Or,
+				"XOR" => Operation::Xor,
+				operation => return Err(CircuitParseError::UnknownOperation { line, operation: operation.to_string() }),
+			};
+			circuit.gates.push(Gate { inputs: [a.to_string(), b.to_string()], operation, output: output.to_string() });
+		}
+		Ok(circuit)
+	}
+}
+
+impl Circuit {
+	/// The value of every wire once the circuit settles.
+	pub fn evaluate(&self) -> Result<HashMap<&str, bool>, CircuitError> {
+		let mut values = self.initial.iter().map(|(wire, &value)| (wire.as_str(), value)).collect::<HashMap<_, _>>();
+		let mut pending = self.gates.iter().collect::<Vec<_>>();
+		while !pending.is_empty() {
+			let before = pending.len();
+			pending.retain(|gate| {
+				let [a, b] = &gate.inputs;
+				let (Some(&a), Some(&b)) = (values.get(a.as_str()), values.get(b.as_str())) else { return true };
+				values.insert(&gate.output, gate.operation.apply(a, b));
+				false
+			});
+			if pending.len() == before {
+				return Err(CircuitError::Unresolved { wires: pending.iter().map(|gate| gate.output.clone()).collect() })
+			}
+		}
+		Ok(values)
+	}
+
+	/// The number formed by the wires starting with `prefix`, where the wire ending `00` is the least significant bit.
+	pub fn number(values: &HashMap<&str, bool>, prefix: char) -> u64 {
+		let mut bits = values.iter().filter(|(wire, _)| wire.starts_with(prefix)).collect::<Vec<_>>();
+		bits.sort_unstable();
+		bits.iter().rev().fold(0, |number, (_, &bit)| number << 1 | bit as u64)
+	}
+
+	/// The output wires of gates which break the ripple-carry adder adding `x` and `y` into `z`, sorted.
+	///
+	/// Each full adder computes `x ^ y` and `x & y`, XORs the former with the carry into `z`, and ORs the latter with
+	/// `(x ^ y) & carry` into the next carry. So every `z` but the last comes from an XOR, every other XOR reads `x` and
+	/// `y` and feeds another XOR, and every AND feeds an OR. The first bit is a half adder and is exempt from the latter.
+	pub fn miswired_outputs(&self) -> Vec<String> {
+		let last_z = self.gates.iter().map(|gate| &gate.output).filter(|wire| wire.starts_with('z')).max();
+		let is_input = |wire: &str| wire.starts_with('x') || wire.starts_with('y');
+		let is_first = |gate: &Gate| gate.inputs.iter().all(|wire| &wire[1..] == "00");
+		let feeds = |wire: &str, operation: Operation| self.gates.iter().any(|gate| gate.operation == operation && gate.inputs.iter().any(|input| input == wire));
+
+		let mut miswired = self.gates.iter().filter(|gate| {
+			let from_inputs = gate.inputs.iter().all(|wire| is_input(wire));
+			match gate.operation {
+				_ if gate.output.starts_with('z') && Some(&gate.output) != last_z => gate.operation != Operation::Xor,
+				Operation::Xor if !from_inputs => true,
+				Operation::Xor => !is_first(gate) && !feeds(&gate.output, Operation::Xor),
+				Operation::And => !is_first(gate) && !feeds(&gate.output, Operation::Or),
+				Operation::Or => false,
+			}
+		}).map(|gate| gate.output.clone()).collect::<HashSet<_>>().into_iter().collect::<Vec<_>>();
+		miswired.sort_unstable();
+		miswired
+	}
+
+	/// Renders the circuit in Graphviz's DOT language, with a node per wire and per gate, to inspect by hand with
+	/// `dot -Tsvg`. Highlighted wires are filled in red.
+	pub fn to_dot(&self, highlighted: &[String]) -> String {
+		let mut dot = String::from("digraph circuit {\n\trankdir=LR;\n");
+		for wire in self.initial.keys() {
+			writeln!(dot, "\t{wire} [shape=box];").unwrap();
+		}
+		for (index, gate) in self.gates.iter().enumerate() {
+			writeln!(dot, "\tgate{index} [label=\"{}\", shape=ellipse];", gate.operation.name()).unwrap();
+			for input in &gate.inputs {
+				writeln!(dot, "\t{input} -> gate{index};").unwrap();
+			}
+			writeln!(dot, "\tgate{index} -> {};", gate.output).unwrap();
+		}
+		for wire in highlighted {
+			writeln!(dot, "\t{wire} [style=filled, fillcolor=red];").unwrap();
+		}
+		dot.push_str("}\n");
+		dot
+	}
+}
+
+/// Part 1 solution - the number output on the `z` wires.
+fn part1_solution(input: &str) -> Result<u64, SolutionError> {
+	let circuit = Circuit::try_from(input)?;
+	Ok(Circuit::number(&circuit.evaluate()?, 'z'))
+}
+
+/// Part 2 solution - the swapped wires of the adder, sorted and joined by commas.
+fn part2_solution(input: &str) -> Result<String, CircuitParseError> {
+	Ok(Circuit::try_from(input)?.miswired_outputs().join(","))
+}
+
+/// Entry point
+fn main() {
+	let example = "x00: 1\nx01: 0\nx02: 1\nx03: 1\nx04: 0\ny00: 1\ny01: 1\ny02: 1\ny03: 1\ny04: 1
+
+ntg XOR fgs -> mjb\ny02 OR x01 -> tnw\nkwq OR kpj -> z05\nx00 OR x03 -> fst\ntgd XOR rvg -> z01\nvdt OR tnw -> bfw
+bfw AND frj -> z10\nffh OR nrd -> bqk\ny00 AND y03 -> djm\ny03 OR y00 -> psh\nbqk OR frj -> z08\ntnw OR fst -> frj
+gnj AND tgd -> z11\nbfw XOR mjb -> z00\nx03 OR x00 -> vdt\ngnj AND wpb -> z02\nx04 AND y00 -> kjc\ndjm OR pbm -> qhw
+nrd AND vdt -> hwm\nkjc AND fst -> rvg\ny04 OR y02 -> fgs\ny01 AND x02 -> pbm\nntg OR kjc -> kwq\npsh XOR fgs -> tgd
+qhw XOR tgd -> z09\npbm OR djm -> kpj\nx03 XOR y03 -> ffh\nx00 XOR y04 -> ntg\nbfw OR bqk -> z06\nnrd XOR fgs -> wpb
+frj XOR qhw -> z04\nbqk OR frj -> z07\ny03 OR x01 -> nrd\nhwm AND bqk -> z03\ntgd XOR rvg -> z12\ntnw OR pbm -> gnj";
+
+	// Part 2 only applies to the real input's adder, the example isn't one
+	println!("Part 1 Solution on Example: {}", report(part1_solution(example), "solving day 24 part 1"));
+
+	// Pass the input with `--file <path>`, and `--dot` to print the circuit for Graphviz with the swapped wires highlighted
+	let args = std::env::args().collect::<Vec<_>>();
+	if let Some(path) = args.iter().skip_while(|&arg| arg != "--file").nth(1) {
+		let input = std::fs::read_to_string(path).expect("Failed to read input file");
+		if args.iter().any(|arg| arg == "--dot") {
+			let circuit = Circuit::try_from(input.as_str()).expect("Failed to parse circuit");
+			print!("{}", circuit.to_dot(&circuit.miswired_outputs()));
+			return;
+		}
+		println!("Part 1 Solution on Input: {}", report(part1_solution(&input), "solving day 24 part 1"));
+		println!("Part 2 Solution on Input: {}", report(part2_solution(&input), "solving day 24 part 2"));
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Builds a correct ripple-carry adder over the given number of bits, with every input bit set.
+	fn adder(bits: usize) -> Circuit {
+		let mut input = (0..bits).flat_map(|bit| [format!("x{bit:02}: 1"), format!("y{bit:02}: 1")]).collect::<Vec<_>>().join("\n");
+		input.push_str("\n\nx00 XOR y00 -> z00\nx00 AND y00 -> c00\n");
+		for bit in 1..bits {
+			let carry = format!("c{:02}", bit - 1);
+			input.push_str(&format!("x{bit:02} XOR y{bit:02} -> s{bit:02}\nx{bit:02} AND y{bit:02} -> a{bit:02}\n"));
+			input.push_str(&format!("s{bit:02} XOR {carry} -> z{bit:02}\ns{bit:02} AND {carry} -> b{bit:02}\n"));
+			let out = if bit == bits - 1 { format!("z{bits:02}") } else { format!("c{bit:02}") };
+			input.push_str(&format!("a{bit:02} OR b{bit:02} -> {out}\n"));
+		}
+		Circuit::try_from(input.as_str()).unwrap()
+	}
+
+	/// Tests evaluating the smaller example from the puzzle text, and an adder
+	#[test]
+	fn test_evaluate() {
+		let circuit = Circuit::try_from("x00: 1\nx01: 1\nx02: 1\ny00: 0\ny01: 1\ny02: 0\n\nx00 AND y00 -> z00\nx01 XOR y01 -> z01\nx02 OR y02 -> z02").unwrap();
+		assert_eq!(circuit.evaluate().map(|values| Circuit::number(&values, 'z')), Ok(4));
+		let adder = adder(6);
+		assert_eq!(adder.evaluate().map(|values| Circuit::number(&values, 'z')), Ok(63 + 63));
+		assert!(adder.miswired_outputs().is_empty());
+
+		assert_eq!(part1_solution("x00: 1\n\nx00 AND y00 -> z00"), Err(SolutionError::Circuit(CircuitError::Unresolved { wires: vec![String::from("z00")] })));
+		assert_eq!(part1_solution("x00 1"), Err(SolutionError::Parse(CircuitParseError::InvalidWire { line: 1 })));
+	}
+
+	/// Tests that swapping outputs in an adder is detected
+	#[test]
+	fn test_miswired_outputs() {
+		let mut circuit = adder(8);
+		let swap = |circuit: &mut Circuit, a: &str, b: &str| for gate in &mut circuit.gates {
+			if gate.output == a { gate.output = b.to_string() } else if gate.output == b { gate.output = a.to_string() }
+		};
+		swap(&mut circuit, "z03", "b03");
+		swap(&mut circuit, "s05", "a05");
+		assert_eq!(circuit.miswired_outputs(), vec!["a05", "b03", "s05", "z03"]);
+		assert!(circuit.to_dot(&circuit.miswired_outputs()).contains("z03 [style=filled, fillcolor=red];"));
+	}
+
+}
@@ -0,0 +1,115 @@
+use std::{error::Error, fmt::{self, Display, Formatter}};
+
+use common::report::report;
+
+/// Possible errors when parsing the schematics
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SchematicParseError {
+	/// A schematic doesn't start with a full row of `#` (a lock) or end with one (a key), the index counts schematics from 1
+	UnknownKind { schematic: usize },
+	/// A schematic's rows aren't all as wide
+	RaggedRows { schematic: usize },
+}
+
+impl Display for SchematicParseError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		match self {
+			SchematicParseError::UnknownKind { schematic } => write!(f, "Schematic {schematic}: neither a lock nor a key"),
+			SchematicParseError::RaggedRows { schematic } => write!(f, "Schematic {schematic}: rows aren't all as wide"),
+		}
+	}
+}
+
+impl Error for SchematicParseError {}
+
+/// The heights of every column of a lock or key, not counting the full row
+pub type Heights = Vec<usize>;
+
+/// Every lock and key, and the number of rows the columns can fill between their full rows
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Schematics {
+	pub locks: Vec<Heights>,
+	pub keys: Vec<Heights>,
+	pub space: usize,
+}
+
+impl TryFrom<&str> for Schematics {
+	type Error = SchematicParseError;
+
+	/// Converts from schematics separated by blank lines, locks with their top row filled and keys with their bottom.
+	fn try_from(value: &str) -> Result<Self, SchematicParseError> {
+		let mut schematics = Schematics::default();
+		for (index, block) in value.trim().split("\n\n").enumerate() {
+			let rows = block.lines().map(|line| line.trim().as_bytes()).collect::<Vec<_>>();
+			let width = rows[0].len();
+			if rows.iter().any(|row| row.len() != width) { return Err(SchematicParseError::RaggedRows { schematic: index + 1 }) }
+			let is_full = |row: &[u8]| row.iter().all(|&c| c == b'#');
+			let is_lock = match (is_full(rows[0]), is_full(rows[rows.len() - 1])) {
+				(true, false) => true,
+				(false, true) => false,
+				_ => return Err(SchematicParseError::UnknownKind { schematic: index + 1 }),
+			};
+			let heights = (0..width).map(|column| rows.iter().filter(|row| row[column] == b'#').count() - 1).collect();
+			schematics.space = schematics.space.max(rows.len() - 2);
+			if is_lock { schematics.locks.push(heights) } else { schematics.keys.push(heights) }
+		}
+		Ok(schematics)
+	}
+}
+
+impl Schematics {
+	/// Whether a key fits a lock, without any column overlapping
+	pub fn fits(&self, lock: &[usize], key: &[usize]) -> bool {
+		lock.iter().zip(key).all(|(lock, key)| lock + key <= self.space)
+	}
+
+	/// The number of lock and key pairs which fit
+	pub fn fitting_pairs(&self) -> usize {
+		self.locks.iter().map(|lock| self.keys.iter().filter(|key| self.fits(lock, key)).count()).sum()
+	}
+}
+
+/// Part 1 solution - the number of lock and key pairs which fit. There is no part 2 on the last day.
+fn part1_solution(input: &str) -> Result<usize, SchematicParseError> {
+	Ok(Schematics::try_from(input)?.fitting_pairs())
+}
+
+/// Entry point
+fn main() {
+	let example = "#####\n.####\n.####\n.####\n.#.#.\n.#...\n.....
+
+#####\n##.##\n.#.##\n...##\n...#.\n...#.\n.....
+
+.....\n#....\n#....\n#...#\n#.#.#\n#.###\n#####
+
+.....\n.....\n#.#..\n###..\n###.#\n###.#\n#####
+
+.....\n.....\n.....\n#....\n#.#..\n#.#.#\n#####";
+
+	println!("Part 1 Solution on Example: {}", report(part1_solution(example), "solving day 25 part 1"));
+
+	// Pass the input with `--file <path>`
+	let args = std::env::args().collect::<Vec<_>>();
+	if let Some(path) = args.iter().skip_while(|&arg| arg != "--file").nth(1) {
+		let input = std::fs::read_to_string(path).expect("Failed to read input file");
+		println!("Part 1 Solution on Input: {}", report(part1_solution(&input), "solving day 25 part 1"));
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Tests the heights and fitting pairs against the puzzle text
+	#[test]
+	fn test_fitting_pairs() {
+		let example = "#####\n.####\n.####\n.####\n.#.#.\n.#...\n.....\n\n.....\n#....\n#....\n#...#\n#.#.#\n#.###\n#####
+\n.....\n.....\n.....\n#....\n#.#..\n#.#.#\n#####";
+		let schematics = Schematics::try_from(example).unwrap();
+		assert_eq!(schematics.locks, vec![vec![0, 5, 3, 4, 3]]);
+		assert_eq!(schematics.keys, vec![vec![5, 0, 2, 1, 3], vec![3, 0, 2, 0, 1]]);
+		assert_eq!(schematics.fitting_pairs(), 1);
+		assert_eq!(Schematics::try_from("#####\n.....\n#####"), Err(SchematicParseError::UnknownKind { schematic: 1 }));
+	}
+
+}
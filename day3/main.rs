@@ -0,0 +1,109 @@
+/// A single uncorrupted instruction in the computer's memory
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Instruction {
+	/// `mul(a,b)`, multiplies two numbers of 1 to 3 digits.
+	Mul(u64, u64),
+	/// `do()`, enables future `mul` instructions.
+	Do,
+	/// `don't()`, disables future `mul` instructions.
+	Dont,
+}
+
+/// Scans corrupted memory for instructions, skipping everything else.
+pub struct Instructions<'a> {
+	memory: &'a str,
+}
+
+impl<'a> Instructions<'a> {
+	/// Creates a scanner over corrupted memory
+	pub fn new(memory: &'a str) -> Self {
+		Self { memory }
+	}
+
+	/// Parses a number of 1 to 3 digits at the start of `text`, returning it and the text after it.
+	fn number(text: &str) -> Option<(u64, &str)> {
+		let digits = text.bytes().take(3).take_while(u8::is_ascii_digit).count();
+		if digits == 0 { return None }
+		Some((text[..digits].parse().ok()?, &text[digits..]))
+	}
+
+	/// Parses an instruction at the very start of `text`, returning it and the text after it.
+	fn instruction(text: &str) -> Option<(Instruction, &str)> {
+		if let Some(rest) = text.strip_prefix("do()") { return Some((Instruction::Do, rest)) }
+		if let Some(rest) = text.strip_prefix("don't()") { return Some((Instruction::Dont, rest)) }
+		let (a, rest) = Self::number(text.strip_prefix("mul(")?)?;
+		let (b, rest) = Self::number(rest.strip_prefix(',')?)?;
+		Some((Instruction::Mul(a, b), rest.strip_prefix(')')?))
+	}
+}
+
+impl Iterator for Instructions<'_> {
+	type Item = Instruction;
+
+	fn next(&mut self) -> Option<Instruction> {
+		// Every instruction starts with a `m` or `d`, so jump between those and try to parse one there
+		while let Some(start) = self.memory.find(['m', 'd']) {
+			match Self::instruction(&self.memory[start..]) {
+				Some((instruction, rest)) => { self.memory = rest; return Some(instruction) },
+				None => self.memory = &self.memory[start + 1..],
+			}
+		}
+		self.memory = "";
+		None
+	}
+}
+
+/// Part 1 solution - sum of the results of every `mul` instruction.
+fn part1_solution(input: &str) -> u64 {
+	Instructions::new(input).map(|instruction| match instruction {
+		Instruction::Mul(a, b) => a * b,
+		Instruction::Do | Instruction::Dont => 0,
+	}).sum()
+}
+
+/// Part 2 solution - sum of the results of `mul` instructions, which are disabled after a `don't()` until a `do()`.
+fn part2_solution(input: &str) -> u64 {
+	Instructions::new(input).scan(true, |enabled, instruction| Some(match instruction {
+		Instruction::Mul(a, b) => if *enabled { a * b } else { 0 },
+		Instruction::Do => { *enabled = true; 0 },
+		Instruction::Dont => { *enabled = false; 0 },
+	})).sum()
+}
+
+/// Entry point
+fn main() {
+	let example = "xmul(2,4)%&mul[3,7]!@^do_not_mul(5,5)+mul(32,64]then(mul(11,8)mul(8,5))";
+	let example2 = "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
+	// Solve another input instead of the bundled one with `--file <path>`
+	let args = std::env::args().collect::<Vec<_>>();
+	let file = args.iter().skip_while(|&arg| arg != "--file").nth(1)
+		.map(|path| std::fs::read_to_string(path).expect("Failed to read input file"));
+	let input = file.as_deref().unwrap_or(include_str!("day3.txt"));
+
+	println!("Part 1 Solution on Example: {:#?}", part1_solution(example));
+	println!("Part 1 Solution on Input: {:#?}", part1_solution(input));
+
+	println!("Part 2 Solution on Example: {:#?}", part2_solution(example2));
+	println!("Part 2 Solution on Input: {:#?}", part2_solution(input));
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Tests the scanner finds only the uncorrupted instructions from the puzzle text
+	#[test]
+	fn test_instructions() {
+		let instructions = Instructions::new("xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))");
+		assert_eq!(instructions.collect::<Vec<_>>(), [
+			Instruction::Mul(2, 4),
+			Instruction::Dont,
+			Instruction::Mul(5, 5),
+			Instruction::Mul(11, 8),
+			Instruction::Do,
+			Instruction::Mul(8, 5),
+		]);
+		assert_eq!(Instructions::new("mul(1234,5) mul(4*) mul ( 2 , 4 ) mul(12,345)").collect::<Vec<_>>(), [Instruction::Mul(12, 345)]);
+	}
+
+}
@@ -0,0 +1,119 @@
+/// One of the 8 directions a word can be read in, as a change in x and y
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Direction { pub d_x: isize, pub d_y: isize }
+
+impl Direction {
+	/// Every horizontal, vertical, and diagonal direction, clockwise from right.
+	pub const ALL: [Direction; 8] = [
+		Direction { d_x: 1, d_y: 0 }, Direction { d_x: 1, d_y: 1 }, Direction { d_x: 0, d_y: 1 }, Direction { d_x: -1, d_y: 1 },
+		Direction { d_x: -1, d_y: 0 }, Direction { d_x: -1, d_y: -1 }, Direction { d_x: 0, d_y: -1 }, Direction { d_x: 1, d_y: -1 },
+	];
+}
+
+/// A rectangular grid of cells, 0,0 is at the top-left, x increases right, y increases down
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+	cells: Vec<T>,
+	width: usize,
+	height: usize,
+}
+
+impl<T> Grid<T> {
+	/// Gets the cell at a position, or None if it's outside the grid.
+	pub fn get(&self, x: isize, y: isize) -> Option<&T> {
+		if x < 0 || y < 0 || x as usize >= self.width || y as usize >= self.height { return None }
+		self.cells.get(y as usize * self.width + x as usize)
+	}
+
+	/// Iterates over every position in the grid in reading order.
+	pub fn positions(&self) -> impl Iterator<Item = (isize, isize)> + '_ {
+		(0..self.height).flat_map(|y| (0..self.width).map(move |x| (x as isize, y as isize)))
+	}
+
+	/// Iterates over the cells starting at a position and going in a direction, until the edge of the grid.
+	pub fn ray(&self, x: isize, y: isize, direction: Direction) -> impl Iterator<Item = &T> + '_ {
+		(0..).map_while(move |step| self.get(x + direction.d_x * step, y + direction.d_y * step))
+	}
+}
+
+impl From<&str> for Grid<char> {
+	/// Converts from lines of characters, shorter lines are treated as if they stopped at the edge.
+	fn from(value: &str) -> Self {
+		let lines = value.lines().filter(|line| !line.is_empty()).collect::<Vec<_>>();
+		let width = lines.iter().map(|line| line.chars().count()).max().unwrap_or(0);
+		let cells = lines.iter()
+			.flat_map(|line| line.chars().chain(std::iter::repeat(' ')).take(width))
+			.collect();
+		Self { cells, width, height: lines.len() }
+	}
+}
+
+/// Counts every occurrence of `word` in the grid, reading in any of the 8 directions.
+pub fn count_word(grid: &Grid<char>, word: &str) -> usize {
+	let word = word.chars().collect::<Vec<_>>();
+	grid.positions().map(|(x, y)| {
+		Direction::ALL.iter().filter(|&&direction| grid.ray(x, y, direction).take(word.len()).eq(word.iter())).count()
+	}).sum()
+}
+
+/// Counts every X-MAS in the grid - two diagonal MAS crossing at their A, each may be read either way.
+pub fn count_crosses(grid: &Grid<char>) -> usize {
+	let is_mas = |(x, y): (isize, isize), direction: Direction| {
+		let diagonal = grid.ray(x - direction.d_x, y - direction.d_y, direction).take(3).collect::<String>();
+		diagonal == "MAS" || diagonal == "SAM"
+	};
+	grid.positions()
+		.filter(|&(x, y)| grid.get(x, y) == Some(&'A'))
+		.filter(|&pos| is_mas(pos, Direction { d_x: 1, d_y: 1 }) && is_mas(pos, Direction { d_x: 1, d_y: -1 }))
+		.count()
+}
+
+/// Part 1 solution - the number of times XMAS appears in any direction.
+fn part1_solution(input: &str) -> usize {
+	count_word(&Grid::from(input), "XMAS")
+}
+
+/// Part 2 solution - the number of X-MAS crosses.
+fn part2_solution(input: &str) -> usize {
+	count_crosses(&Grid::from(input))
+}
+
+/// Entry point
+fn main() {
+	let example = "MMMSXXMASM
+MSAMXMSMSA
+AMXSXMAAMM
+MSAMASMSMX
+XMASAMXAMM
+XXAMMXXAMA
+SMSMSASXSS
+SAXAMASAAA
+MAMMMXMMMM
+MXMXAXMASX";
+	// Solve another input instead of the bundled one with `--file <path>`
+	let args = std::env::args().collect::<Vec<_>>();
+	let file = args.iter().skip_while(|&arg| arg != "--file").nth(1)
+		.map(|path| std::fs::read_to_string(path).expect("Failed to read input file"));
+	let input = file.as_deref().unwrap_or(include_str!("day4.txt"));
+
+	println!("Part 1 Solution on Example: {:#?}", part1_solution(example));
+	println!("Part 1 Solution on Input: {:#?}", part1_solution(input));
+
+	println!("Part 2 Solution on Example: {:#?}", part2_solution(example));
+	println!("Part 2 Solution on Input: {:#?}", part2_solution(input));
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Tests words are found in every direction, and crosses only on diagonals
+	#[test]
+	fn test_directions() {
+		let grid = Grid::from("S..S..S\n.A.A.A.\n..MMM..\nSAMXMAS\n..MMM..\n.A.A.A.\nS..S..S");
+		assert_eq!(count_word(&grid, "XMAS"), 8);
+		assert_eq!(count_crosses(&Grid::from("M.S\n.A.\nM.S")), 1);
+		assert_eq!(count_crosses(&Grid::from(".M.\nMAS\n.S.")), 0);
+	}
+
+}
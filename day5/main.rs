@@ -0,0 +1,189 @@
+use std::{cmp::Ordering, collections::{BTreeMap, BTreeSet}, error::Error, fmt::{self, Display, Formatter}, num::ParseIntError};
+
+use common::report::report;
+
+/// Possible errors when parsing the rules and updates, lines are 1-indexed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PrintQueueParseError {
+	/// There was no blank line between the rules and updates.
+	MissingUpdates,
+	/// A rule wasn't in the form `a|b`.
+	InvalidRule { line: usize, text: String },
+	/// A page number wasn't an integer.
+	IntegerParseError { line: usize, text: String, error: ParseIntError },
+}
+
+impl Display for PrintQueueParseError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		match self {
+			PrintQueueParseError::MissingUpdates => write!(f, "Expected a blank line between the rules and updates"),
+			PrintQueueParseError::InvalidRule { line, text } => write!(f, "Line {line}: invalid rule '{text}', expected a|b"),
+			PrintQueueParseError::IntegerParseError { line, text, error } => write!(f, "Line {line}: invalid page '{text}', {error}"),
+		}
+	}
+}
+
+impl Error for PrintQueueParseError {}
+
+/// The page ordering rules as a directed graph, with an edge from each page to every page which must come after it.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RuleGraph {
+	after: BTreeMap<u32, BTreeSet<u32>>,
+}
+
+impl RuleGraph {
+	/// Adds a rule that `before` must be printed before `after`.
+	pub fn add_rule(&mut self, before: u32, after: u32) {
+		self.after.entry(before).or_default().insert(after);
+	}
+
+	/// Checks whether a rule says `before` must be printed before `after`.
+	pub fn must_precede(&self, before: u32, after: u32) -> bool {
+		self.after.get(&before).is_some_and(|pages| pages.contains(&after))
+	}
+
+	/// Gets the pages which must be printed after a page, in ascending order.
+	pub fn successors(&self, page: u32) -> impl Iterator<Item = u32> + '_ {
+		self.after.get(&page).into_iter().flatten().copied()
+	}
+
+	/// Gets every page mentioned by a rule, in ascending order.
+	pub fn pages(&self) -> BTreeSet<u32> {
+		self.after.iter().flat_map(|(&page, after)| std::iter::once(page).chain(after.iter().copied())).collect()
+	}
+
+	/// Compares two pages by the rules, pages without a rule between them are equal.
+	pub fn compare(&self, a: u32, b: u32) -> Ordering {
+		if self.must_precede(a, b) { Ordering::Less } else if self.must_precede(b, a) { Ordering::Greater } else { Ordering::Equal }
+	}
+
+	/// Checks whether an update is printed in an order following every rule between its pages.
+	pub fn is_ordered(&self, update: &[u32]) -> bool {
+		update.iter().enumerate().all(|(idx, &page)| update[idx + 1..].iter().all(|&later| !self.must_precede(later, page)))
+	}
+
+	/// Orders pages so every rule between them is followed, using only the rules between the given pages. Ties are
+	/// broken by the smallest page first. Returns None if the rules between the pages form a cycle.
+	pub fn topological_order(&self, pages: &[u32]) -> Option<Vec<u32>> {
+		let pages = pages.iter().copied().collect::<BTreeSet<_>>();
+		let mut incoming = pages.iter().map(|&page| (page, 0)).collect::<BTreeMap<_, _>>();
+		for &page in &pages {
+			for after in self.successors(page).filter(|after| pages.contains(after)) { *incoming.entry(after).or_default() += 1; }
+		}
+
+		// Kahn's algorithm, always printing the smallest page with nothing left before it
+		let mut ready = incoming.iter().filter(|(_, &count)| count == 0).map(|(&page, _)| page).collect::<BTreeSet<_>>();
+		let mut order = Vec::with_capacity(pages.len());
+		while let Some(page) = ready.pop_first() {
+			order.push(page);
+			for after in self.successors(page).filter(|after| pages.contains(after)) {
+				let count = incoming.get_mut(&after).expect("Every page has a count");
+				*count -= 1;
+				if *count == 0 { ready.insert(after); }
+			}
+		}
+		(order.len() == pages.len()).then_some(order)
+	}
+}
+
+/// Parses the rules, then a blank line, then the updates as comma separated page numbers.
+pub fn parse_print_queue(input: &str) -> Result<(RuleGraph, Vec<Vec<u32>>), PrintQueueParseError> {
+	let lines = input.lines().enumerate().map(|(idx, line)| (idx + 1, line.trim())).collect::<Vec<_>>();
+	let split = lines.iter().position(|(_, line)| line.is_empty()).ok_or(PrintQueueParseError::MissingUpdates)?;
+	let parse = |line: usize, text: &str| text.parse::<u32>()
+		.map_err(|error| PrintQueueParseError::IntegerParseError { line, text: text.into(), error });
+
+	let mut rules = RuleGraph::default();
+	for &(line, text) in &lines[..split] {
+		let (before, after) = text.split_once('|').ok_or_else(|| PrintQueueParseError::InvalidRule { line, text: text.into() })?;
+		rules.add_rule(parse(line, before)?, parse(line, after)?);
+	}
+	let updates = lines[split + 1..].iter()
+		.filter(|(_, text)| !text.is_empty())
+		.map(|&(line, text)| text.split(',').map(|page| parse(line, page)).collect())
+		.collect::<Result<_, _>>()?;
+	Ok((rules, updates))
+}
+
+/// Part 1 solution - sum of the middle pages of every update already in order.
+fn part1_solution(input: &str) -> Result<u32, PrintQueueParseError> {
+	let (rules, updates) = parse_print_queue(input)?;
+	Ok(updates.iter().filter(|update| rules.is_ordered(update)).map(|update| update[update.len() / 2]).sum())
+}
+
+/// Part 2 solution - sum of the middle pages of every update which wasn't in order, after ordering it.
+fn part2_solution(input: &str) -> Result<u32, PrintQueueParseError> {
+	let (rules, mut updates) = parse_print_queue(input)?;
+	Ok(updates.iter_mut()
+		.filter(|update| !rules.is_ordered(update))
+		.map(|update| {
+			// Every pair of pages in an update has a rule between them, so the rules can be used directly as a comparator
+			update.sort_by(|&a, &b| rules.compare(a, b));
+			update[update.len() / 2]
+		})
+		.sum())
+}
+
+/// Entry point
+fn main() {
+	let example = "47|53
+97|13
+97|61
+97|47
+75|29
+61|13
+75|53
+29|13
+97|29
+53|29
+61|53
+97|53
+61|29
+47|13
+75|47
+97|75
+47|61
+75|61
+47|29
+75|13
+53|13
+
+75,47,61,53,29
+97,61,53,29,13
+75,29,13
+75,97,47,61,53
+61,13,29
+97,13,75,29,47";
+	// Solve another input instead of the bundled one with `--file <path>`
+	let args = std::env::args().collect::<Vec<_>>();
+	let file = args.iter().skip_while(|&arg| arg != "--file").nth(1)
+		.map(|path| std::fs::read_to_string(path).expect("Failed to read input file"));
+	let input = file.as_deref().unwrap_or(include_str!("day5.txt"));
+
+	println!("Part 1 Solution on Example: {}", report(part1_solution(example), "solving day 5 part 1"));
+	println!("Part 1 Solution on Input: {}", report(part1_solution(input), "solving day 5 part 1"));
+
+	println!("Part 2 Solution on Example: {}", report(part2_solution(example), "solving day 5 part 2"));
+	println!("Part 2 Solution on Input: {}", report(part2_solution(input), "solving day 5 part 2"));
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Tests the rule graph orders pages, and detects cycles
+	#[test]
+	fn test_topological_order() {
+		let mut rules = RuleGraph::default();
+		for (before, after) in [(97, 75), (75, 47), (47, 61), (97, 61), (61, 29)] { rules.add_rule(before, after); }
+		assert_eq!(rules.topological_order(&[61, 29, 75, 97, 47]), Some(vec![97, 75, 47, 61, 29]));
+		assert!(rules.is_ordered(&[97, 75, 47]));
+		assert!(!rules.is_ordered(&[75, 97, 47]));
+		assert_eq!(rules.pages().into_iter().collect::<Vec<_>>(), [29, 47, 61, 75, 97]);
+		rules.add_rule(29, 97);
+		assert_eq!(rules.topological_order(&[61, 29, 75, 97, 47]), None);
+		// The cycle only involves pages not given, so only the rule 75|47 applies
+		assert_eq!(rules.topological_order(&[47, 75]), Some(vec![75, 47]));
+	}
+
+}
@@ -1,10 +1,16 @@
-use std::fmt::{Display, Write};
+use std::{cmp, collections::{BTreeSet, HashSet}, error::Error, fmt::{self, Debug, Display, Formatter, Write}, sync::{atomic::{AtomicU64, AtomicUsize, Ordering}, Arc}, time::{Duration, Instant}};
 
-use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+use common::{cancel::{print_progress, CancelToken, Progress}, compare::{agree, compare, comparison_json, print_comparison}, debug::debug, random::Xorshift, report::report, validate::validate_file};
+use rayon::iter::{IndexedParallelIterator, IntoParallelIterator, ParallelBridge, ParallelIterator};
+#[cfg(feature = "serde")]
+use std::{fs::File, io::{self, BufReader, BufWriter, Write as _}, path::Path};
+#[cfg(feature = "serde")]
+use common::report::error_chain;
 
 /// Traversal directions
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum Direction {
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Direction {
 	North, East, South, West,
 }
 
@@ -19,9 +25,14 @@ impl Direction {
 		}
 	}
 
-	/// Turns this direction right.
-	fn go_right(&mut self) {
-		*self = self.get_right_direction();
+	/// Gets the direction by rotating left from the current direction
+	fn get_left_direction(&self) -> Self {
+		match self {
+			Direction::North => Direction::West,
+			Direction::East => Direction::North,
+			Direction::South => Direction::East,
+			Direction::West => Direction::South,
+		}
 	}
 
 	/// Gets the index in the tile visited array.
@@ -33,10 +44,74 @@ impl Direction {
 			Direction::West => 3,
 		}
 	}
+
+	/// Gets the position one step in this direction, None if it would leave a map of the given size.
+	fn step(&self, Position { y, x }: Position, height: usize, width: usize) -> Option<Position> {
+		match self {
+			Direction::North => Some(Position { y: y.checked_sub(1)?, x }),
+			Direction::East => (x + 1 < width).then_some(Position { y, x: x + 1 }),
+			Direction::South => (y + 1 < height).then_some(Position { y: y + 1, x }),
+			Direction::West => Some(Position { y, x: x.checked_sub(1)? }),
+		}
+	}
+
+	/// Gets the direction a guard glyph is facing, None if the character isn't a guard.
+	fn from_guard_char(value: char) -> Option<Self> {
+		match value {
+			'^' => Some(Direction::North),
+			'>' => Some(Direction::East),
+			'v' => Some(Direction::South),
+			'<' => Some(Direction::West),
+			_ => None
+		}
+	}
+}
+
+/// How a guard turns upon encountering an obsticle.
+#[derive(Clone, Default)]
+pub enum TurnPolicy {
+	/// Turn right, as in the puzzle.
+	#[default]
+	Right,
+	/// Turn left.
+	Left,
+	/// Turn around.
+	Reverse,
+	/// Turn to whichever direction the function gives for the direction the guard was facing.
+	Custom(Arc<dyn Fn(Direction) -> Direction + Send + Sync>),
+}
+
+impl Debug for TurnPolicy {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			TurnPolicy::Right => f.write_str("Right"),
+			TurnPolicy::Left => f.write_str("Left"),
+			TurnPolicy::Reverse => f.write_str("Reverse"),
+			TurnPolicy::Custom(_) => f.write_str("Custom"),
+		}
+	}
 }
 
+impl TurnPolicy {
+	/// Gets the direction a guard facing `direction` turns to when blocked.
+	fn turn(&self, direction: Direction) -> Direction {
+		match self {
+			TurnPolicy::Right => direction.get_right_direction(),
+			TurnPolicy::Left => direction.get_left_direction(),
+			TurnPolicy::Reverse => direction.get_right_direction().get_right_direction(),
+			TurnPolicy::Custom(turn) => turn(direction),
+		}
+	}
+}
+
+/// A position on the map in the orientation of the puzzle input, where 0,0 is the top left.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Position { pub y: usize, pub x: usize }
+
 /// Represents a tile on the map.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 enum Tile {
 	Obsticle,
 	Freespace { visited: [bool; 4] },
@@ -51,7 +126,7 @@ impl Tile {
 		match value {
 			'#' => Some(Self::Obsticle),
 			'.' => Some(Self::Freespace { visited: [false; 4] }),
-			'^' => Some(Self::Guard { visited: [false; 4] }),
+			'^' | '>' | 'v' | '<' => Some(Self::Guard { visited: [false; 4] }),
 			_ => None
 		}
 	}
@@ -65,12 +140,12 @@ impl Tile {
 		}
 	}
 
-	/// Checks whether the tile has been traversed in a certain direction.
+	/// Checks whether the tile has been traversed in a certain direction. The guard's own tile counts too, otherwise a
+	/// guard which only turns in place would never be seen repeating itself.
 	fn is_traversed(&self, direction: Direction) -> bool {
 		match self {
 			Tile::Obsticle => false,
-			Tile::Freespace { visited } => visited[direction.get_visited_index()],
-			Tile::Guard { visited: _ } => false,
+			Tile::Freespace { visited } | Tile::Guard { visited } => visited[direction.get_visited_index()],
 		}
 	}
 
@@ -118,15 +193,67 @@ impl Display for Tile {
 	}
 }
 
+/// Where the guard was when a traversal step failed.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct TraversalContext {
+	/// The guard's last known position in the orientation of the puzzle input, None if it was never found.
+	pub position: Option<Position>,
+	/// The direction the guard was facing.
+	pub direction: Direction,
+	/// How many steps (walks up to the next obsticle) the guard had completed.
+	pub step: usize,
+}
+
+impl Display for TraversalContext {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		match self.position {
+			Some(Position { y, x }) => write!(f, "at ({y}, {x}) facing {:?} on step {}", self.direction, self.step),
+			None => write!(f, "facing {:?} on step {}", self.direction, self.step),
+		}
+	}
+}
+
 /// Possible errors during a single map traversal step
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum TraversalStepError {
-	GuardNotFound,
-	InvalidObsticleEncountered,
-	TraversalUpdateError,
-	InfiniteLoopEncountered,
+	GuardNotFound(TraversalContext),
+	InvalidObsticleEncountered(TraversalContext),
+	TraversalUpdateError(TraversalContext),
+	InfiniteLoopEncountered(TraversalContext),
+}
+
+impl TraversalStepError {
+	/// Where the guard was when the step failed.
+	pub fn context(&self) -> &TraversalContext {
+		match self {
+			TraversalStepError::GuardNotFound(context) | TraversalStepError::InvalidObsticleEncountered(context) |
+			TraversalStepError::TraversalUpdateError(context) | TraversalStepError::InfiniteLoopEncountered(context) => context,
+		}
+	}
+
+	/// Fills in the step the error happened on, and the guard's last known position if the step couldn't find it.
+	fn during(mut self, step: usize, last_position: Option<Position>) -> Self {
+		let (TraversalStepError::GuardNotFound(context) | TraversalStepError::InvalidObsticleEncountered(context) |
+			TraversalStepError::TraversalUpdateError(context) | TraversalStepError::InfiniteLoopEncountered(context)) = &mut self;
+		context.step = step;
+		context.position = context.position.or(last_position);
+		self
+	}
 }
 
+impl Display for TraversalStepError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		match self {
+			TraversalStepError::GuardNotFound(context) => write!(f, "guard not found {context}"),
+			TraversalStepError::InvalidObsticleEncountered(context) => write!(f, "invalid obsticle encountered {context}"),
+			TraversalStepError::TraversalUpdateError(context) => write!(f, "failed to update traversed tiles {context}"),
+			TraversalStepError::InfiniteLoopEncountered(context) => write!(f, "infinite loop encountered {context}"),
+		}
+	}
+}
+
+impl Error for TraversalStepError {}
+
 /// Possible errors during map traversal
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum TraversalError {
@@ -134,14 +261,50 @@ pub enum TraversalError {
 	MaxIterationsReached,
 }
 
+impl Display for TraversalError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		match self {
+			TraversalError::TraversalStepError(_) => write!(f, "traversal step failed"),
+			TraversalError::MaxIterationsReached => write!(f, "max iterations reached"),
+		}
+	}
+}
+
+impl Error for TraversalError {
+	fn source(&self) -> Option<&(dyn Error + 'static)> {
+		match self {
+			TraversalError::TraversalStepError(error) => Some(error),
+			TraversalError::MaxIterationsReached => None,
+		}
+	}
+}
+
+/// Locations traversed in a single step in the orientation of the puzzle input, the direction they were traversed in,
+/// and whether we can traverse further.
+type OrientedTraversal = (Vec<Position>, Direction, bool);
+
+/// Possible errors when saving or loading a snapshot of the map.
+#[cfg(feature = "serde")]
+#[derive(Debug)]
+pub enum SnapshotError {
+	/// The snapshot file couldn't be read or written.
+	Io(io::Error),
+	/// The snapshot couldn't be encoded, or the file didn't contain a valid snapshot.
+	Json(serde_json::Error),
+}
+
 /// Represents the full map in the puzzle. There is a grid of a Guard, Free spaces which can be moved on, and obsticles.
 /// Upon encountering any obsticle, the guard turns right and continues.
 #[derive(Clone)]
-struct Map {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Map {
 	/// 2d array containing the map.
 	map: Vec<Vec<Tile>>,
 	/// The direction we're currently travelling.
 	direction: Direction,
+	/// How the guard turns when it reaches an obsticle. This isn't saved in snapshots, and is Right when loaded.
+	#[cfg_attr(feature = "serde", serde(skip))]
+	turn_policy: TurnPolicy,
 }
 
 impl Display for Map {
@@ -157,17 +320,40 @@ impl Display for Map {
 impl Map {
 
 	/// Creates a map from a string.
-	fn from_string(input: &str) -> Option<Self> {
+	pub fn from_string(input: &str) -> Option<Self> {
 		let mut map = Self {
 			map: input.lines()
 				.map(|line| line.chars().map(Tile::from_char).collect::<Option<Vec<Tile>>>())
 				.collect::<Option<Vec<Vec<Tile>>>>()?,
-			direction: Direction::North,
+			direction: input.chars().find_map(Direction::from_guard_char).unwrap_or(Direction::North),
+			turn_policy: TurnPolicy::Right,
 		};
-		map.rotate_right();
+		// Rotate so the guard travels right along its row, this is undone one step at a time as the guard turns right.
+		for _ in 0..(5 - map.direction.get_visited_index()) % 4 { map.rotate_right(); }
 		Some(map)
 	}
 
+	/// Sets how the guard turns when it reaches an obsticle.
+	pub fn with_turn_policy(mut self, turn_policy: TurnPolicy) -> Self {
+		self.turn_policy = turn_policy;
+		self
+	}
+
+	/// Saves the current state of the map, including all traversal so far, to a JSON file.
+	#[cfg(feature = "serde")]
+	pub fn save_snapshot(&self, path: impl AsRef<Path>) -> Result<(), SnapshotError> {
+		let mut out = BufWriter::new(File::create(path).map_err(SnapshotError::Io)?);
+		serde_json::to_writer(&mut out, self).map_err(SnapshotError::Json)?;
+		out.flush().map_err(SnapshotError::Io)
+	}
+
+	/// Loads a map saved by `save_snapshot`, it can be traversed from exactly where it was saved.
+	#[cfg(feature = "serde")]
+	pub fn load_snapshot(path: impl AsRef<Path>) -> Result<Self, SnapshotError> {
+		let file = File::open(path).map_err(SnapshotError::Io)?;
+		serde_json::from_reader(BufReader::new(file)).map_err(SnapshotError::Json)
+	}
+
 	/// Rotates a 2d array rightt
 	fn rotate_right(&mut self) {
 		self.map = (0..self.map[0].len())
@@ -183,16 +369,38 @@ impl Map {
 			.collect()
 	}
 
+	/// Gets a copy of the map rotated back so north faces up, matching the orientation of the puzzle input.
+	fn oriented(&self) -> Self {
+		let mut map = self.clone();
+		// The map is rotated right once on creation, then left once for every right turn of the guard.
+		for _ in 0..(self.direction.get_visited_index() + 3) % 4 { map.rotate_right(); }
+		map
+	}
+
+	/// Gets the (y, x) position of the guard, or None if the guard has left the map.
+	fn guard_position(&self) -> Option<(usize, usize)> {
+		self.map.iter()
+			.enumerate()
+			.find_map(|(y, row)| Some((y, row.iter().position(|tile| tile.is_guard())?)))
+	}
+
 	/// Traverses the map by one step.
 	/// Returns a tuple of:
 	/// - Vec(y, x) of all locations traversed in this step
 	/// - whether or not we can traverse further (true when we can still traverse)
 	fn traverse(&mut self) -> Result<(Vec<(usize, usize)>, bool), TraversalStepError> {
+		let (direction, height, width) = (self.direction, self.map.len(), self.map[0].len());
+		let context = |position: Option<(usize, usize)>| TraversalContext {
+			position: position.map(|pos| Self::orient_position(pos, direction, height, width)),
+			direction,
+			step: 0,
+		};
+
 		// Row the guard is in, and the x position of the guard.
 		let (y, x, row) = self.map.iter_mut()
 			.enumerate()
 			.find_map(|(y, row)| Some((y, row.iter().position(|c| c.is_guard())?, row)))
-			.ok_or(TraversalStepError::GuardNotFound)?;
+			.ok_or_else(|| TraversalStepError::GuardNotFound(context(None)))?;
 
 		let mut traversed = Vec::new();
 
@@ -200,8 +408,8 @@ impl Map {
 		let obsticle_index = {
 			let mut pos = None;
 			for (x, tile) in row.iter_mut().enumerate().skip(x) {
-				if tile.is_traversed(self.direction) { return Err(TraversalStepError::InfiniteLoopEncountered); }
-				if tile.set_traversed(self.direction).is_none() { pos = Some(x); break; }
+				if tile.is_traversed(direction) { return Err(TraversalStepError::InfiniteLoopEncountered(context(Some((y, x))))); }
+				if tile.set_traversed(direction).is_none() { pos = Some(x); break; }
 				else { traversed.push((y, x)); }
 			}
 			pos
@@ -209,23 +417,65 @@ impl Map {
 
 		if let Some(obsticle) = obsticle_index { // Obsticle found, go to it
 			row[obsticle-1].set_guard();
-			self.direction.go_right();
-			self.rotate_left();
+			// Rotate the map against the turn so the guard keeps travelling right along its row.
+			let direction = self.turn_policy.turn(self.direction);
+			for _ in 0..(direction.get_visited_index() + 4 - self.direction.get_visited_index()) % 4 { self.rotate_left(); }
+			self.direction = direction;
 			Ok((traversed, true))
 		} else { // There is no obsticle; We've exited the map.
 			Ok((traversed, false))
 		}
 	}
 
+	/// Converts a (y, x) position on a map of the given size, rotated for a guard travelling in `direction`, into the
+	/// orientation of the puzzle input.
+	fn orient_position((mut y, mut x): (usize, usize), direction: Direction, height: usize, width: usize) -> Position {
+		let (mut height, mut width) = (height, width);
+		// Undo each right rotation, the map has been rotated right once more than the guard has turned right.
+		for _ in 0..(5 - direction.get_visited_index()) % 4 {
+			(y, x) = (width - 1 - x, y);
+			(height, width) = (width, height);
+		}
+		Position { y, x }
+	}
+
+	/// Converts a position in the orientation of the puzzle input into a (y, x) index into the map's current rotation.
+	fn rotated_index(&self, Position { mut y, mut x }: Position) -> (usize, usize) {
+		let rotations = (5 - self.direction.get_visited_index()) % 4;
+		let (mut height, mut width) = (self.map.len(), self.map[0].len());
+		if rotations % 2 == 1 { (height, width) = (width, height); }
+		for _ in 0..rotations {
+			(y, x) = (x, height - 1 - y);
+			(height, width) = (width, height);
+		}
+		(y, x)
+	}
+
+	/// Traverses the map by one step like `traverse`, but reports the traversed (y, x) locations in the orientation of
+	/// the puzzle input alongside the direction they were traversed in.
+	fn traverse_oriented(&mut self) -> Result<OrientedTraversal, TraversalStepError> {
+		let (direction, height, width) = (self.direction, self.map.len(), self.map[0].len());
+		let (traversed, traversing) = self.traverse()?;
+		let traversed = traversed.into_iter().map(|pos| Self::orient_position(pos, direction, height, width)).collect();
+		Ok((traversed, direction, traversing))
+	}
+
 	/// Counts the number of tiles that have been traversed thus far
 	fn count_traversed(&self) -> usize {
 		self.map.iter().flatten().filter(|&&tile| tile.is_visited()).count()
 	}
 
+	/// The most times the guard can turn before it must be in a loop. Each turn leaves the guard on a new tile and
+	/// direction until it starts repeating them, and loops are found as soon as that happens, so this is only a safety
+	/// valve against the guard never stopping.
+	fn iteration_bound(&self) -> usize {
+		4 * self.map.len() * self.map[0].len()
+	}
+
 	/// Traverses until either an error occurs, or we can no longer traverse.
 	fn traverse_steps(&mut self, max_iters: usize) -> Result<(), TraversalError> {
 		let mut counter = 0;
-		while self.traverse().map_err(TraversalError::TraversalStepError)?.1 {
+		while self.traverse().map_err(|error| TraversalError::TraversalStepError(error.during(counter, None)))?.1 {
 			// Ensure we don't exceed max iterations
 			counter += 1;
 			if counter > max_iters { return Err(TraversalError::MaxIterationsReached); }
@@ -233,6 +483,244 @@ impl Map {
 		Ok(())
 	}
 
+	/// Walks the guard's patrol on a copy of the map, recording every (position, direction) state the guard is in, in order.
+	/// Turning on a tile is its own state. Returns the states along with how the patrol ended, which is Ok when the guard
+	/// left the map, and an error when a loop was found or max iterations were exceeded.
+	fn patrol(&self, max_iters: usize) -> (Vec<(Position, Direction)>, Result<(), TraversalError>) {
+		let mut map = self.clone();
+		let mut path: Vec<(Position, Direction)> = Vec::new();
+		let mut counter = 0;
+		loop {
+			let (traversed, direction, traversing) = match map.traverse_oriented() {
+				Ok(traversal) => traversal,
+				Err(error) => {
+					let error = error.during(counter, path.last().map(|&(pos, _)| pos));
+					return (path, Err(TraversalError::TraversalStepError(error)))
+				},
+			};
+
+			// Each traversal starts on the tile the previous one ended on, only the direction has changed.
+			let mut traversed = traversed.into_iter();
+			if let Some(start) = traversed.next() {
+				if path.last() != Some(&(start, direction)) { path.push((start, direction)); }
+			}
+			path.extend(traversed.map(|pos| (pos, direction)));
+			if !traversing { return (path, Ok(())) }
+
+			// Ensure we don't exceed max iterations
+			counter += 1;
+			if counter > max_iters { return (path, Err(TraversalError::MaxIterationsReached)); }
+		}
+	}
+
+	/// Gets every step of the guard's patrol in order, until the guard either leaves the map or starts repeating a loop.
+	/// Turning on a tile counts as a step.
+	pub fn patrol_path(&self) -> Vec<(Position, Direction)> {
+		self.patrol(self.iteration_bound()).0
+	}
+
+	/// Whether adding an obsticle at the given position (in the orientation of the input) forces the guard into an
+	/// infinite loop.
+	fn loops_with_obsticle(&self, pos: Position) -> bool {
+		// Clone the map and add the obsticle, see if it is infinite.
+		let mut map = self.clone();
+		let (y, x) = map.rotated_index(pos);
+		map.map[y][x] = Tile::Obsticle;
+		let response = map.traverse_steps(self.iteration_bound());
+		if let Err(err) = response {
+			match err {
+				TraversalError::TraversalStepError(traversal_step_error) => {
+					matches!(traversal_step_error, TraversalStepError::InfiniteLoopEncountered(_))
+				},
+				TraversalError::MaxIterationsReached => {
+					println!("Max iterations reached.");
+					false
+				},
+			}
+		} else { false }
+	}
+
+	/// Traverses until either an error occurs, or we can no longer traverse, counting how many times each tile is entered.
+	/// Returns the counts as a grid in the orientation of the puzzle input, where the guard's starting tile counts once.
+	fn traverse_counting(&self) -> Result<Vec<Vec<usize>>, TraversalError> {
+		let (path, result) = self.patrol(self.iteration_bound());
+		result?;
+		let oriented = self.oriented();
+		let mut counts = vec![vec![0; oriented.map[0].len()]; oriented.map.len()];
+		let mut previous = None;
+		for (pos, _) in path {
+			if previous != Some(pos) { counts[pos.y][pos.x] += 1; }
+			previous = Some(pos);
+		}
+		Ok(counts)
+	}
+
+	/// Renders the map in the orientation of the puzzle input with the given positions marked as `O`.
+	fn render_marked(&self, marked: &[Position]) -> String {
+		let mut lines = self.oriented().map.iter()
+			.map(|row| row.iter().map(|tile| tile.to_string()).collect::<String>())
+			.collect::<Vec<_>>();
+		for &Position { y, x } in marked { lines[y].replace_range(x..x + 1, "O"); }
+		lines.join("\n")
+	}
+
+	/// Renders entry counts from `traverse_counting` over the map in the orientation of the puzzle input.
+	/// Obsticles are drawn as `#`, tiles which were never entered as `.`, and all others as their count (capped at 9).
+	fn render_heatmap(&self, counts: &[Vec<usize>]) -> String {
+		self.oriented().map.iter().zip(counts).map(|(row, row_counts)| {
+			row.iter().zip(row_counts).map(|(tile, &count)| match (tile, count) {
+				(Tile::Obsticle, _) => '#',
+				(_, 0) => '.',
+				(_, count) => char::from_digit(cmp::min(count, 9) as u32, 10).unwrap(),
+			}).collect::<String>()
+		}).collect::<Vec<_>>().join("\n")
+	}
+
+}
+
+/// Where a guard is in its patrol.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GuardStatus {
+	/// The guard is still on the map and hasn't repeated itself.
+	Patrolling,
+	/// The guard has walked off the edge of the map.
+	Exited,
+	/// The guard has returned to a position and direction it was already in, and will loop forever.
+	Looped,
+}
+
+/// A single guard patrolling the map, moving one tile or turning once per step.
+#[derive(Debug, Clone)]
+pub struct Guard {
+	/// The guard's current position, or its last position on the map once it has exited.
+	pub position: Position,
+	/// The direction the guard is facing.
+	pub direction: Direction,
+	/// Where the guard is in its patrol.
+	pub status: GuardStatus,
+	/// Every (position, direction) state the guard has been in.
+	visited: HashSet<(Position, Direction)>,
+}
+
+impl Guard {
+	/// Creates a guard standing at a position.
+	fn new(position: Position, direction: Direction) -> Self {
+		Self { position, direction, status: GuardStatus::Patrolling, visited: HashSet::from([(position, direction)]) }
+	}
+}
+
+/// A map patrolled by any number of guards. Guards move in lockstep and don't block each other, each guard keeps its
+/// own history so exits and loops are detected per guard.
+#[derive(Debug, Clone)]
+pub struct Patrol {
+	/// Whether each tile is an obsticle, indexed by `[y][x]` in the orientation of the puzzle input.
+	obsticles: Vec<Vec<bool>>,
+	/// Every guard on the map, in the order they appear in the input.
+	pub guards: Vec<Guard>,
+	/// How guards turn when they reach an obsticle.
+	turn_policy: TurnPolicy,
+}
+
+impl Patrol {
+	/// Creates a patrol from a string, with a guard for every guard glyph in the map.
+	pub fn from_string(input: &str) -> Option<Self> {
+		let mut guards = Vec::new();
+		let obsticles = input.lines().enumerate().map(|(y, line)| {
+			line.chars().enumerate().map(|(x, c)| {
+				if let Some(direction) = Direction::from_guard_char(c) { guards.push(Guard::new(Position { y, x }, direction)); }
+				Some(Tile::from_char(c)? == Tile::Obsticle)
+			}).collect::<Option<Vec<bool>>>()
+		}).collect::<Option<Vec<Vec<bool>>>>()?;
+		Some(Self { obsticles, guards, turn_policy: TurnPolicy::Right })
+	}
+
+	/// Sets how guards turn when they reach an obsticle.
+	pub fn with_turn_policy(mut self, turn_policy: TurnPolicy) -> Self {
+		self.turn_policy = turn_policy;
+		self
+	}
+
+	/// Steps every guard which is still patrolling once, either turning right if an obsticle is in front of it, or moving
+	/// forward. Returns true while any guard is still patrolling.
+	pub fn step(&mut self) -> bool {
+		let (height, width) = (self.obsticles.len(), self.obsticles.first().map_or(0, Vec::len));
+		for guard in self.guards.iter_mut().filter(|guard| guard.status == GuardStatus::Patrolling) {
+			let Some(next) = guard.direction.step(guard.position, height, width) else {
+				guard.status = GuardStatus::Exited;
+				continue;
+			};
+			if self.obsticles[next.y][next.x] { guard.direction = self.turn_policy.turn(guard.direction); }
+			else { guard.position = next; }
+			if !guard.visited.insert((guard.position, guard.direction)) { guard.status = GuardStatus::Looped; }
+		}
+		self.guards.iter().any(|guard| guard.status == GuardStatus::Patrolling)
+	}
+
+	/// The most steps any guard can take before it must have exited or looped, as every step but the last leaves it on a
+	/// new tile and direction.
+	fn iteration_bound(&self) -> usize {
+		4 * self.obsticles.len() * self.obsticles.first().map_or(0, Vec::len)
+	}
+
+	/// Steps until every guard has either exited or looped, or max steps is exceeded.
+	pub fn run(&mut self, max_steps: usize) -> Result<(), TraversalError> {
+		for _ in 0..max_steps {
+			if !self.step() { return Ok(()) }
+		}
+		Err(TraversalError::MaxIterationsReached)
+	}
+
+	/// Gets every position visited by any guard.
+	pub fn visited(&self) -> HashSet<Position> {
+		self.guards.iter().flat_map(|guard| guard.visited.iter().map(|&(pos, _)| pos)).collect()
+	}
+}
+
+/// A structural problem with a map, found by `validate`. Lines and columns count from 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputProblem {
+	/// The input has no lines.
+	Empty,
+	/// A line isn't as long as the first, so the map isn't rectangular.
+	RaggedLine { line: usize, length: usize, expected: usize },
+	/// A character which isn't a tile, `.`, `#`, or a guard facing `^`, `>`, `v`, or `<`.
+	UnknownCharacter { line: usize, column: usize, character: char },
+	/// There is no guard on the map.
+	MissingGuard,
+}
+
+impl Display for InputProblem {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		match self {
+			InputProblem::Empty => write!(f, "The map is empty"),
+			InputProblem::RaggedLine { line, length, expected } => {
+				write!(f, "Line {line} is {length} long, expected {expected} like the first line")
+			},
+			InputProblem::UnknownCharacter { line, column, character } => {
+				write!(f, "Unknown character {character:?} at line {line}, column {column}")
+			},
+			InputProblem::MissingGuard => write!(f, "There is no guard on the map"),
+		}
+	}
+}
+
+/// Checks a map is a rectangular grid of known characters without solving it, reporting every problem rather than
+/// stopping at the first.
+pub fn validate(input: &str) -> Vec<InputProblem> {
+	let lines = input.lines().collect::<Vec<_>>();
+	let Some(first) = lines.first() else { return vec![InputProblem::Empty] };
+	let expected = first.chars().count();
+	let mut problems = Vec::new();
+	for (idx, text) in lines.iter().enumerate() {
+		let length = text.chars().count();
+		if length != expected { problems.push(InputProblem::RaggedLine { line: idx + 1, length, expected }); }
+		for (column, character) in text.chars().enumerate() {
+			if Tile::from_char(character).is_some() { continue }
+			problems.push(InputProblem::UnknownCharacter { line: idx + 1, column: column + 1, character });
+		}
+	}
+	if !input.chars().any(|c| Direction::from_guard_char(c).is_some()) { problems.push(InputProblem::MissingGuard); }
+	problems
 }
 
 /// Possible errors in the part 1 solution.
@@ -242,46 +730,218 @@ pub enum Part1Error {
 	MapParsingError,
 }
 
+impl Display for Part1Error {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		match self {
+			Part1Error::TraversalError(_) => write!(f, "failed to patrol the map"),
+			Part1Error::MapParsingError => write!(f, "failed to parse the map"),
+		}
+	}
+}
+
+impl Error for Part1Error {
+	fn source(&self) -> Option<&(dyn Error + 'static)> {
+		match self {
+			Part1Error::TraversalError(error) => Some(error),
+			Part1Error::MapParsingError => None,
+		}
+	}
+}
+
+/// Generates a square practice map of the given size, with roughly one obsticle in `sparsity` tiles, whose guard leaves
+/// the map so both parts have answers.
+pub fn generate_practice(seed: u64, size: usize, sparsity: u64) -> String {
+	let mut rng = Xorshift::new(seed);
+	loop {
+		let mut tiles = (0..size)
+			.map(|_| (0..size).map(|_| if rng.below(sparsity.max(1)) == 0 { '#' } else { '.' }).collect::<Vec<_>>())
+			.collect::<Vec<_>>();
+		let (y, x) = (rng.below(size as u64) as usize, rng.below(size as u64) as usize);
+		tiles[y][x] = ['^', '>', 'v', '<'][rng.below(4) as usize];
+		let map = tiles.iter().map(|row| row.iter().collect::<String>()).collect::<Vec<_>>().join("\n");
+		if part1_solution(&map).is_ok() { return map }
+	}
+}
+
 /// Part 1 solution to the advent of code day 6.
 /// Puzzle: traverse until the end, and find the number of traversed tiles.
-pub fn part1_solution(input: &str, max_iters: usize) -> Result<usize, Part1Error> {
-	let mut map = Map::from_string(input).ok_or(Part1Error::MapParsingError)?;
-	map.traverse_steps(max_iters).map_err(Part1Error::TraversalError)?;
-	Ok(map.count_traversed())
+pub fn part1_solution(input: &str) -> Result<usize, Part1Error> {
+	let map = Map::from_string(input).ok_or(Part1Error::MapParsingError)?;
+	let (path, result) = map.patrol(map.iteration_bound());
+	result.map_err(Part1Error::TraversalError)?;
+	Ok(path.into_iter().map(|(pos, _)| pos).collect::<BTreeSet<_>>().len())
+}
+
+/// Counts how many times the guard enters each tile while traversing until the end.
+/// Returns a grid of counts indexed by `[y][x]` in the orientation of the input.
+pub fn visit_counts(input: &str) -> Result<Vec<Vec<usize>>, Part1Error> {
+	let map = Map::from_string(input).ok_or(Part1Error::MapParsingError)?;
+	map.traverse_counting().map_err(Part1Error::TraversalError)
 }
 
 /// Possible errors in the part 2 solution.
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum Part2Error {
 	MapParsingError,
+	/// The search was cancelled before every candidate was checked.
+	Cancelled,
+}
+
+impl Display for Part2Error {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		match self {
+			Part2Error::MapParsingError => write!(f, "failed to parse the map"),
+			Part2Error::Cancelled => write!(f, "the search was cancelled"),
+		}
+	}
+}
+
+impl Error for Part2Error {}
+
+/// Finds every position (in the orientation of the input) where adding an obsticle would force the guard into an
+/// infinite loop, ordered by position.
+pub fn find_loop_obstacles(input: &str) -> Result<Vec<Position>, Part2Error> {
+	find_loop_obstacles_monitored(input, &CancelToken::default(), |_| {})
+}
+
+/// Like `find_loop_obstacles`, but stops checking candidates once the token is cancelled, and calls `progress` after
+/// checking each candidate. Candidates are checked in parallel, so `progress` is called from many threads and not in
+/// order of position.
+pub fn find_loop_obstacles_monitored(
+	input: &str, cancel: &CancelToken, progress: impl Fn(Progress<Position>) + Sync,
+) -> Result<Vec<Position>, Part2Error> {
+	let completed = AtomicUsize::new(0);
+	search_loop_obstacles(input, Chunking::default(), cancel, |item, total, _| {
+		progress(Progress { completed: completed.fetch_add(1, Ordering::Relaxed) + 1, total, item });
+	})
+}
+
+/// How the loop obstacle search splits its candidates between threads. Candidates which cause a loop take much longer
+/// to check than those where the guard quickly leaves, so the split decides how evenly the threads are kept busy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Chunking {
+	/// Let rayon split the candidates in halves as threads become idle.
+	#[default]
+	Adaptive,
+	/// Let rayon split the candidates, but never into runs shorter than this.
+	MinLength(usize),
+	/// Hand out candidates one at a time to whichever thread asks next.
+	Dynamic,
+}
+
+impl TryFrom<&str> for Chunking {
+	type Error = ();
+
+	fn try_from(value: &str) -> Result<Self, ()> {
+		match value {
+			"adaptive" => Ok(Chunking::Adaptive),
+			"dynamic" => Ok(Chunking::Dynamic),
+			length => length.parse().map(Chunking::MinLength).map_err(|_| ()),
+		}
+	}
+}
+
+/// How busy each thread was during a search.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Utilization {
+	/// How long the whole search took.
+	pub wall: Duration,
+	/// How long each of rayon's threads spent checking candidates, indexed by thread.
+	pub busy: Vec<Duration>,
+}
+
+impl Utilization {
+	/// The fraction of the search's time the threads spent checking candidates, from 0 to 1.
+	pub fn fraction(&self) -> f64 {
+		let available = self.wall.as_secs_f64() * self.busy.len() as f64;
+		if available == 0.0 { 1.0 } else { self.busy.iter().map(Duration::as_secs_f64).sum::<f64>() / available }
+	}
+}
+
+/// Like `find_loop_obstacles`, but splits the candidates between threads with the given chunking, and measures how
+/// busy each thread was.
+pub fn find_loop_obstacles_chunked(
+	input: &str, chunking: Chunking,
+) -> Result<(Vec<Position>, Utilization), Part2Error> {
+	let busy = (0..rayon::current_num_threads()).map(|_| AtomicU64::new(0)).collect::<Vec<_>>();
+	let start = Instant::now();
+	let obstacles = search_loop_obstacles(input, chunking, &CancelToken::default(), |_, _, time| {
+		let thread = rayon::current_thread_index().unwrap_or(0);
+		busy[thread].fetch_add(time.as_nanos() as u64, Ordering::Relaxed);
+	})?;
+	let wall = start.elapsed();
+	let busy = busy.into_iter().map(|nanos| Duration::from_nanos(nanos.into_inner())).collect();
+	Ok((obstacles, Utilization { wall, busy }))
+}
+
+/// Searches for loop obstacles, splitting candidates between threads with the given chunking. `checked` is called
+/// after each candidate with the candidate, the total number of candidates, and how long it took to check.
+fn search_loop_obstacles(
+	input: &str, chunking: Chunking, cancel: &CancelToken,
+	checked: impl Fn(Position, usize, Duration) + Sync,
+) -> Result<Vec<Position>, Part2Error> {
+	let map = Map::from_string(input).ok_or(Part2Error::MapParsingError)?;
+
+	// An obsticle can only change the guard's patrol if it is placed on the guard's path, and not on the guard itself.
+	let path = map.patrol_path();
+	let start = path.first().map(|&(pos, _)| pos);
+	let candidates: Vec<Position> = path.iter()
+		.filter_map(|&(pos, _)| (Some(pos) != start).then_some(pos))
+		.collect::<BTreeSet<_>>()
+		.into_iter()
+		.collect();
+
+	let total = candidates.len();
+	let check = |&pos: &Position| {
+		if cancel.is_cancelled() { return false }
+		let started = Instant::now();
+		let is_loop = map.loops_with_obsticle(pos);
+		checked(pos, total, started.elapsed());
+		is_loop
+	};
+	let obstacles = match chunking {
+		Chunking::Adaptive => candidates.into_par_iter().filter(check).collect(),
+		Chunking::MinLength(length) => candidates.into_par_iter().with_min_len(length.max(1)).filter(check).collect(),
+		Chunking::Dynamic => {
+			let mut obstacles = candidates.into_iter().par_bridge().filter(check).collect::<Vec<_>>();
+			obstacles.sort();
+			obstacles
+		},
+	};
+	if cancel.is_cancelled() { return Err(Part2Error::Cancelled) }
+	Ok(obstacles)
+}
+
+/// Like `find_loop_obstacles`, but tries an obsticle on every free tile rather than only those on the guard's path.
+/// This is much slower, and is kept to check the faster search against.
+pub fn find_loop_obstacles_exhaustive(input: &str) -> Result<Vec<Position>, Part2Error> {
+	let map = Map::from_string(input).ok_or(Part2Error::MapParsingError)?;
+	let candidates = input.lines().enumerate()
+		.flat_map(|(y, line)| line.chars().enumerate().filter(|&(_, c)| c == '.').map(move |(x, _)| Position { y, x }))
+		.collect::<Vec<_>>();
+	Ok(candidates.into_par_iter().filter(|&pos| map.loops_with_obsticle(pos)).collect())
 }
 
 /// Part 2 solution to the advent of code day 6.
 /// Puzzle: Count the number of places we could add an obsticle to force the guard into an infinite loop.
-pub fn part2_solution(input: &str, max_iters: usize) -> Result<usize, Part2Error> {
-	let map = Map::from_string(input).ok_or(Part2Error::MapParsingError)?;
-	let indices: Vec<(usize, usize)> = (0..map.map.len()).flat_map(|y| (0..map.map[0].len()).map(move |x| (y, x))).collect();
-	
-	Ok(indices.par_iter().filter(|(y, x)| {
-		// Exclude anything which already had a barrier
-		if map.map[*y][*x] == Tile::Obsticle { return false; }
+pub fn part2_solution(input: &str) -> Result<usize, Part2Error> {
+	Ok(find_loop_obstacles(input)?.len())
+}
 
-		// Clone the map and add the obsticle, see if it is infinite.
-		let mut map = map.clone();
-		map.map[*y][*x] = Tile::Obsticle;
-		let response = map.traverse_steps(max_iters);
-		if let Err(err) = response {
-			match err {
-				TraversalError::TraversalStepError(traversal_step_error) => {
-					traversal_step_error == TraversalStepError::InfiniteLoopEncountered
-				},
-				TraversalError::MaxIterationsReached => {
-					println!("Max iterations reached.");
-					false
-				},
-			}
-		} else { false }
-	}).count())
+/// Writes a JSON line for every step of the guard's patrol with its (y, x) position in the orientation of the puzzle
+/// input, its direction, and whether the step was a turn. A patrol which fails ends with a line holding the error and
+/// every error which caused it.
+#[cfg(feature = "serde")]
+fn write_trace(map: &Map, out: &mut impl io::Write) -> io::Result<()> {
+	let (path, result) = map.patrol(map.iteration_bound());
+	let mut previous = None;
+	for (step, &(Position { y, x }, direction)) in path.iter().enumerate() {
+		let turn = previous == Some(Position { y, x });
+		writeln!(out, "{}", serde_json::json!({ "step": step, "y": y, "x": x, "direction": format!("{direction:?}"), "turn": turn }))?;
+		previous = Some(Position { y, x });
+	}
+	if let Err(error) = result { writeln!(out, "{}", serde_json::json!({ "step": path.len(), "error": error_chain(&error) }))?; }
+	Ok(())
 }
 
 pub fn main() {
@@ -295,11 +955,419 @@ pub fn main() {
 ........#.
 #.........
 ......#...";
-	let input = include_str!("day6.txt");
+	// Solve another input instead of the bundled one with `--file <path>`
+	let args = std::env::args().collect::<Vec<_>>();
+	let file = args.iter().skip_while(|&arg| arg != "--file").nth(1)
+		.map(|path| std::fs::read_to_string(path).expect("Failed to read input file"));
+	let input = file.as_deref().unwrap_or(include_str!("day6.txt"));
+
+	// Generate a practice input with `--practice <seed>`, sized with `--size <n>` (30 by default). The answers are only shown with `--reveal`, the
+	// same seed always generates the same input so they can be checked later
+	if let Some(seed) = args.iter().skip_while(|&arg| arg != "--practice").nth(1) {
+		let seed = seed.parse().expect("Invalid seed");
+		let size = args.iter().skip_while(|&arg| arg != "--size").nth(1).map_or(30, |size| size.parse().expect("Invalid size"));
+		let practice = generate_practice(seed, size, 10);
+		println!("{practice}");
+		if args.iter().any(|arg| arg == "--reveal") {
+			println!("Part 1: {:?}", part1_solution(&practice));
+			println!("Part 2: {:?}", part2_solution(&practice));
+		} else {
+			eprintln!("Run again with --reveal to see the answers");
+		}
+		return;
+	}
+
+	// Check a file for problems without solving it with `--validate <path>`
+	if let Some(path) = args.iter().skip_while(|&arg| arg != "--validate").nth(1) {
+		validate_file(path, validate);
+		return;
+	}
+	let source = if args.iter().any(|arg| arg == "--example") { example } else { input };
+
+	// Run every implementation of a part on the same input with `--compare <part>`, checking their answers agree. Time
+	// each over `--samples <n>` runs (1 by default), printing the timings as JSON for benchmark tools with `--json`
+	if let Some(part) = args.iter().skip_while(|&arg| arg != "--compare").nth(1) {
+		let samples = args.iter().skip_while(|&arg| arg != "--samples").nth(1).map_or(1, |samples| samples.parse().expect("Invalid samples"));
+		let results = match part.as_str() {
+			"1" => compare(source, &[
+				("map", &|input| part1_solution(input).ok()),
+				("guards", &|input| {
+					let mut patrol = Patrol::from_string(input)?;
+					patrol.run(patrol.iteration_bound()).ok()?;
+					Some(patrol.visited().len())
+				}),
+			], samples),
+			"2" => compare(source, &[
+				("path", &|input| find_loop_obstacles(input).map(|obstacles| obstacles.len()).ok()),
+				("every tile", &|input| find_loop_obstacles_exhaustive(input).map(|obstacles| obstacles.len()).ok()),
+			], samples),
+			_ => panic!("Unknown part {part}, expected 1 or 2"),
+		};
+		if args.iter().any(|arg| arg == "--json") { println!("{}", comparison_json(&results)) } else { print_comparison(&results) }
+		if !agree(&results) {
+			eprintln!("Implementations disagree");
+			std::process::exit(1)
+		}
+		return;
+	}
+
+	// Give up on the loop obstacle search after a number of seconds with `--timeout <seconds>`, and show its progress
+	// with `--progress`
+	let cancel = CancelToken::default();
+	if let Some(seconds) = args.iter().skip_while(|&arg| arg != "--timeout").nth(1) {
+		cancel.cancel_after(Duration::from_secs_f64(seconds.parse().expect("Invalid timeout")));
+	}
+	let show_progress = args.iter().any(|arg| arg == "--progress");
+	let report_progress = |progress: Progress<Position>| if show_progress { print_progress(progress) };
+	#[cfg(feature = "serde")]
+	if let Some(path) = args.iter().skip_while(|&arg| arg != "--trace").nth(1) {
+		let map = Map::from_string(source).expect("Failed to parse map");
+		let mut out = BufWriter::new(File::create(path).expect("Failed to create trace file"));
+		write_trace(&map, &mut out).and_then(|_| out.flush()).expect("Failed to write trace");
+		return;
+	}
+	if args.iter().any(|arg| arg == "--heatmap") {
+		let map = Map::from_string(source).expect("Failed to parse map");
+		let counts = map.traverse_counting().expect("Failed to traverse map");
+		println!("{}", map.render_heatmap(&counts));
+		return;
+	}
+	if args.iter().any(|arg| arg == "--loop-obstacles") {
+		let map = Map::from_string(source).expect("Failed to parse map");
+		let obstacles = find_loop_obstacles_monitored(source, &cancel, report_progress).expect("Failed to find loop obstacles");
+		println!("{}\n{} positions cause a loop", map.render_marked(&obstacles), obstacles.len());
+		return;
+	}
+	// Search for loop obstacles splitting the candidates between threads with `--chunking <adaptive|dynamic|length>`,
+	// printing how busy each thread was
+	if let Some(chunking) = args.iter().skip_while(|&arg| arg != "--chunking").nth(1) {
+		let chunking = Chunking::try_from(chunking.as_str()).unwrap_or_else(|_| panic!("Unknown chunking {chunking}"));
+		let (obstacles, utilization) = find_loop_obstacles_chunked(source, chunking).expect("Failed to find loop obstacles");
+		for (thread, busy) in utilization.busy.iter().enumerate() {
+			println!("Thread {thread}: busy {busy:.3?} of {:.3?}", utilization.wall);
+		}
+		println!("{} positions cause a loop, {:.1}% utilization", obstacles.len(), utilization.fraction() * 100.0);
+		return;
+	}
+	if args.iter().any(|arg| arg == "--guards") {
+		let mut patrol = Patrol::from_string(source).expect("Failed to parse map");
+		patrol.run(patrol.iteration_bound()).expect("Failed to finish patrol");
+		for (idx, guard) in patrol.guards.iter().enumerate() {
+			println!("Guard {idx}: {:?} at {:?} facing {:?}", guard.status, guard.position, guard.direction);
+		}
+		println!("{} tiles visited by all guards", patrol.visited().len());
+		return;
+	}
+	if args.iter().any(|arg| arg == "--debug") {
+		let map = Map::from_string(source).expect("Failed to parse map");
+		debug(map, |map| map.traverse().is_ok(), |map| {
+			let oriented = map.oriented();
+			let guard = match oriented.guard_position() {
+				Some((y, x)) => format!("Guard at ({y}, {x}) facing {:?}", map.direction),
+				None => String::from("Guard has left the map"),
+			};
+			format!("{oriented}\n{guard}, {} tiles traversed", map.count_traversed())
+		});
+		return;
+	}
+
+	println!("Part 1 solution for Example {}", report(part1_solution(example), "solving day 6 part 1"));
+	println!("Part 1 solution for Input {}", report(part1_solution(input), "solving day 6 part 1"));
+
+	println!("Part 2 solution for Example {}", report(part2_solution(example), "solving day 6 part 2"));
+	let obstacles = find_loop_obstacles_monitored(input, &cancel, report_progress).map(|obstacles| obstacles.len());
+	println!("Part 2 solution for Input {}", report(obstacles, "solving day 6 part 2"));
+}
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+
+	/// The example map from the puzzle
+	const EXAMPLE: &str = "....#.....
+.........#
+..........
+..#.......
+.......#..
+..........
+.#..^.....
+........#.
+#.........
+......#...";
+
+	/// Rotates a map string clockwise, turning the guard along with it.
+	fn rotate_clockwise(input: &str) -> String {
+		let lines = input.lines().map(|line| line.chars().collect::<Vec<_>>()).collect::<Vec<_>>();
+		(0..lines[0].len()).map(|x| {
+			lines.iter().rev().map(|line| match line[x] {
+				'^' => '>', '>' => 'v', 'v' => '<', '<' => '^',
+				c => c,
+			}).collect::<String>()
+		}).collect::<Vec<_>>().join("\n")
+	}
+
+	/// Tests the guard starts off facing the direction of its glyph
+	#[test]
+	fn test_guard_glyph_directions() {
+		let cases = [
+			('^', Direction::North, Position { y: 0, x: 2 }),
+			('>', Direction::East, Position { y: 1, x: 4 }),
+			('v', Direction::South, Position { y: 2, x: 2 }),
+			('<', Direction::West, Position { y: 1, x: 0 }),
+		];
+		for (glyph, direction, exit) in cases {
+			let map = Map::from_string(&format!(".....\n..{glyph}..\n.....")).unwrap();
+			assert_eq!(map.direction, direction);
+			let path = map.patrol_path();
+			assert_eq!(path.first(), Some(&(Position { y: 1, x: 2 }, direction)));
+			assert_eq!(path.last(), Some(&(exit, direction)));
+		}
+	}
+
+	/// Tests multiple guards are simulated independently within the step bound, and a single guard matches part 1
+	#[test]
+	fn test_multiple_guards() {
+		let mut patrol = Patrol::from_string(EXAMPLE).unwrap();
+		assert_eq!(patrol.run(patrol.iteration_bound()), Ok(()));
+		assert_eq!(patrol.visited().len(), 41);
+
+		// The second guard is boxed in and loops, the first walks off the map.
+		let mut patrol = Patrol::from_string("..^..\n.#...\n#>..#\n...#.").unwrap();
+		assert_eq!(patrol.run(patrol.iteration_bound()), Ok(()));
+		assert_eq!(patrol.guards[0].status, GuardStatus::Exited);
+		assert_eq!(patrol.guards[0].position, Position { y: 0, x: 2 });
+		assert_eq!(patrol.guards[1].status, GuardStatus::Looped);
+		assert_eq!(patrol.visited().len(), 1 + 3);
+	}
+
+	/// Tests the bound derived from the map size finds a real loop, and lets a long patrol walk all the way off the map
+	#[test]
+	fn test_iteration_bound() {
+		// A printing press next to the guard's start loops it, which is found rather than running out of steps
+		let looping = EXAMPLE.replace(".#..^", ".#.#^");
+		let map = Map::from_string(&looping).unwrap();
+		let (_, result) = map.patrol(map.iteration_bound());
+		assert!(matches!(result, Err(TraversalError::TraversalStepError(TraversalStepError::InfiniteLoopEncountered(_)))));
+		let mut patrol = Patrol::from_string(&looping).unwrap();
+		assert_eq!(patrol.run(patrol.iteration_bound()), Ok(()));
+		assert_eq!(patrol.guards[0].status, GuardStatus::Looped);
+
+		// A winding patrol, taking nearly as many steps as the map has tiles, isn't cut off before it leaves
+		let winding = ".###..\n...#..\n#....#\n....##\n^.#...\n...#..";
+		let map = Map::from_string(winding).unwrap();
+		let (path, result) = map.patrol(map.iteration_bound());
+		assert_eq!(result, Ok(()));
+		assert_eq!(path.len(), 29);
+		let mut patrol = Patrol::from_string(winding).unwrap();
+		assert_eq!(patrol.clone().run(28), Err(TraversalError::MaxIterationsReached));
+		assert_eq!(patrol.run(patrol.iteration_bound()), Ok(()));
+		assert_eq!(patrol.guards[0].status, GuardStatus::Exited);
+	}
+
+	/// Tests turn policies by mirroring the example, where turning left should retrace the mirrored path.
+	#[test]
+	fn test_turn_policies() {
+		let mirrored = EXAMPLE.lines().map(|line| line.chars().rev().collect::<String>()).collect::<Vec<_>>().join("\n");
+		let map = Map::from_string(&mirrored).unwrap().with_turn_policy(TurnPolicy::Left);
+		assert_eq!(map.patrol_path().iter().map(|(pos, _)| pos).collect::<HashSet<_>>().len(), 41);
+		let mut patrol = Patrol::from_string(&mirrored).unwrap().with_turn_policy(TurnPolicy::Left);
+		assert_eq!(patrol.run(1000), Ok(()));
+		assert_eq!(patrol.visited().len(), 41);
+
+		// Custom policies behave the same as the built in ones
+		let map = Map::from_string(EXAMPLE).unwrap().with_turn_policy(TurnPolicy::Custom(Arc::new(|direction| match direction {
+			Direction::North => Direction::East,
+			Direction::East => Direction::South,
+			Direction::South => Direction::West,
+			Direction::West => Direction::North,
+		})));
+		assert_eq!(map.patrol_path(), Map::from_string(EXAMPLE).unwrap().patrol_path());
 
-	println!("Part 1 solution for Example {:#?}", part1_solution(example, 20));
-	println!("Part 1 solution for Input {:#?}", part1_solution(input, 10000));
+		// Reversing walks back along the column the guard came from, leaving the map at the bottom
+		let map = Map::from_string("..#..\n.....\n..^..\n.....").unwrap().with_turn_policy(TurnPolicy::Reverse);
+		let path = map.patrol_path();
+		assert_eq!(path.last(), Some(&(Position { y: 3, x: 2 }, Direction::South)));
+		assert_eq!(path.len(), 2 + 1 + 2);
+	}
+
+	/// Tests a guard boxed in so that it only turns in place is found to loop rather than patrolling forever
+	#[test]
+	fn test_turning_in_place() {
+		let boxed = ".#.\n#^#\n.#.";
+		let context = TraversalContext { position: Some(Position { y: 1, x: 1 }), direction: Direction::North, step: 4 };
+		let error = TraversalError::TraversalStepError(TraversalStepError::InfiniteLoopEncountered(context));
+		assert_eq!(part1_solution(boxed), Err(Part1Error::TraversalError(error)));
+		assert_eq!(part2_solution(boxed), Ok(0));
+		let directions = [Direction::North, Direction::East, Direction::South, Direction::West];
+		let path = directions.map(|direction| (Position { y: 1, x: 1 }, direction));
+		assert_eq!(Map::from_string(boxed).unwrap().patrol_path(), path);
+	}
+
+	/// Tests every turn policy finds the loop when the guard can only turn on its own tile
+	#[test]
+	fn test_turning_in_place_policies() {
+		let start = (Position { y: 1, x: 1 }, Direction::North);
+		let boxed = Map::from_string(".#.\n#^#\n.#.").unwrap();
+		assert_eq!(boxed.clone().with_turn_policy(TurnPolicy::Right).patrol_path().len(), 4);
+		assert_eq!(boxed.clone().with_turn_policy(TurnPolicy::Left).patrol_path().len(), 4);
+
+		// Reversing at the end of a dead end corridor only ever faces the two ends
+		let corridor = Map::from_string("###\n#^#\n###").unwrap().with_turn_policy(TurnPolicy::Reverse);
+		assert_eq!(corridor.patrol_path(), [start, (Position { y: 1, x: 1 }, Direction::South)]);
+
+		// Turning to the same direction leaves the guard facing the obsticle forever
+		let map = Map::from_string(".#.\n.^.\n...").unwrap().with_turn_policy(TurnPolicy::Custom(Arc::new(|direction| direction)));
+		assert_eq!(map.patrol_path(), [start]);
+	}
+
+	/// Tests a snapshot taken part way through traversal resumes where it left off
+	#[cfg(feature = "serde")]
+	#[test]
+	fn test_snapshots() {
+		let mut map = Map::from_string(EXAMPLE).unwrap();
+		for _ in 0..3 { map.traverse().unwrap(); }
+		let path = std::env::temp_dir().join("day6_test_snapshot.json");
+		map.save_snapshot(&path).unwrap();
+		let loaded = Map::load_snapshot(&path).unwrap();
+		std::fs::remove_file(&path).unwrap();
+
+		assert_eq!(loaded.map, map.map);
+		assert_eq!(loaded.direction, map.direction);
+		assert_eq!(loaded.patrol_path(), map.patrol_path());
+		assert!(matches!(Map::load_snapshot(&path), Err(SnapshotError::Io(_))));
+	}
+
+	/// Tests every orientation of the example gives the same answers
+	#[test]
+	fn test_rotated_examples() {
+		let mut example = String::from(EXAMPLE);
+		for glyph in ['^', '>', 'v', '<'] {
+			assert!(example.contains(glyph));
+			assert_eq!(part1_solution(&example), Ok(41));
+			assert_eq!(part2_solution(&example), Ok(6));
+			example = rotate_clockwise(&example);
+		}
+	}
+
+	/// Tests loop obstacles come back in position order however the candidates are split between threads
+	#[test]
+	fn test_loop_obstacles_order() {
+		let obstacles = find_loop_obstacles(EXAMPLE).unwrap();
+		assert_eq!(obstacles.len(), 6);
+		assert!(obstacles.is_sorted());
+		for _ in 0..10 {
+			assert_eq!(find_loop_obstacles(EXAMPLE).unwrap(), obstacles);
+		}
+	}
+
+	/// Tests a cancelled search stops with an error rather than returning partial results
+	#[test]
+	fn test_cancel_loop_obstacles() {
+		let cancel = CancelToken::default();
+		assert_eq!(find_loop_obstacles_monitored(EXAMPLE, &cancel, |_| {}).map(|obstacles| obstacles.len()), Ok(6));
+		cancel.cancel();
+		assert_eq!(find_loop_obstacles_monitored(EXAMPLE, &cancel, |_| {}), Err(Part2Error::Cancelled));
+	}
+
+	/// Tests progress is reported once for every candidate, ending complete
+	#[test]
+	fn test_loop_obstacles_progress() {
+		let reports = std::sync::Mutex::new(Vec::new());
+		find_loop_obstacles_monitored(EXAMPLE, &CancelToken::default(), |progress| reports.lock().unwrap().push(progress)).unwrap();
+		let mut reports = reports.into_inner().unwrap();
+		reports.sort_by_key(|progress| progress.completed);
+		assert!(reports.iter().enumerate().all(|(idx, progress)| progress.completed == idx + 1 && progress.total == reports.len()));
+		assert_eq!(reports.last().map(Progress::fraction), Some(1.0));
+		let mut items = reports.iter().map(|progress| progress.item).collect::<Vec<_>>();
+		items.sort();
+		items.dedup();
+		assert_eq!(items.len(), reports.len());
+	}
+
+	/// Tests validation reports every problem with a map at once
+	#[test]
+	fn test_validate() {
+		assert!(validate(EXAMPLE).is_empty());
+		assert_eq!(validate(""), [InputProblem::Empty]);
+		assert_eq!(validate("..#\n.x\n..."), [
+			InputProblem::RaggedLine { line: 2, length: 2, expected: 3 },
+			InputProblem::UnknownCharacter { line: 2, column: 2, character: 'x' },
+			InputProblem::MissingGuard,
+		]);
+	}
+
+	/// Tests practice maps are valid, reproducible, and have answers
+	#[test]
+	fn test_generate_practice() {
+		for seed in 0..5 {
+			let practice = generate_practice(seed, 12, 8);
+			assert_eq!(practice, generate_practice(seed, 12, 8));
+			assert!(validate(&practice).is_empty());
+			assert!(part1_solution(&practice).is_ok());
+			assert!(part2_solution(&practice).is_ok());
+		}
+		assert_ne!(generate_practice(0, 12, 8), generate_practice(1, 12, 8));
+	}
+
+	/// Tests trying every free tile finds the same obstacles as only trying the guard's path
+	#[test]
+	fn test_exhaustive_loop_obstacles() {
+		assert_eq!(find_loop_obstacles_exhaustive(EXAMPLE), find_loop_obstacles(EXAMPLE));
+	}
+
+	/// Tests every chunking finds the same obstacles, in order, and accounts for each thread
+	#[test]
+	fn test_chunking() {
+		let expected = find_loop_obstacles(EXAMPLE);
+		for chunking in [Chunking::Adaptive, Chunking::MinLength(8), Chunking::Dynamic] {
+			let (obstacles, utilization) = find_loop_obstacles_chunked(EXAMPLE, chunking).unwrap();
+			assert_eq!(Ok(obstacles), expected);
+			assert_eq!(utilization.busy.len(), rayon::current_num_threads());
+		}
+		assert_eq!(Chunking::try_from("dynamic"), Ok(Chunking::Dynamic));
+		assert_eq!(Chunking::try_from("64"), Ok(Chunking::MinLength(64)));
+		assert_eq!(Chunking::try_from("static"), Err(()));
+	}
+
+	/// Tests traversal errors say where the guard was and on which step
+	#[test]
+	fn test_traversal_error_context() {
+		let error = part1_solution(".#..\n...#\n#^..\n..#.").unwrap_err();
+		let context = TraversalContext { position: Some(Position { y: 2, x: 1 }), direction: Direction::North, step: 4 };
+		let step_error = TraversalStepError::InfiniteLoopEncountered(context);
+		assert_eq!(error, Part1Error::TraversalError(TraversalError::TraversalStepError(step_error.clone())));
+		assert_eq!(step_error.to_string(), "infinite loop encountered at (2, 1) facing North on step 4");
+	}
+
+	/// Tests every trace line is valid JSON, including the error ending a looping patrol
+	#[cfg(feature = "serde")]
+	#[test]
+	fn test_write_trace() {
+		let mut out = Vec::new();
+		write_trace(&Map::from_string(".#..\n...#\n#^..\n..#.").unwrap(), &mut out).unwrap();
+		let lines = String::from_utf8(out).unwrap().lines()
+			.map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+			.collect::<Vec<_>>();
+		assert_eq!(lines[0], serde_json::json!({ "step": 0, "y": 2, "x": 1, "direction": "North", "turn": false }));
+		let error = "traversal step failed: infinite loop encountered at (2, 1) facing North on step 4";
+		assert_eq!(lines.last().unwrap()["error"], error);
+
+		// A guard turning in place stops too
+		let mut out = Vec::new();
+		write_trace(&Map::from_string(".#.\n#^#\n.#.").unwrap(), &mut out).unwrap();
+		assert_eq!(String::from_utf8(out).unwrap().lines().count(), 4 + 1);
+	}
+
+	/// Tests error reports include every error in the chain
+	#[test]
+	fn test_report() {
+		assert_eq!(report(part1_solution(EXAMPLE), "solving part 1"), "41");
+		assert_eq!(
+			report(part1_solution(".#..\n...#\n#^..\n..#."), "solving part 1"),
+			"error while solving part 1: failed to patrol the map: traversal step failed: infinite loop encountered at (2, 1) \
+			facing North on step 4",
+		);
+	}
 
-	println!("Part 2 solution for Example {:#?}", part2_solution(example, 50));
-	println!("Part 2 solution for Input {:#?}", part2_solution(input, 10000));
 }
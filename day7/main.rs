@@ -1,33 +1,162 @@
-use std::{borrow::Borrow, fmt::{self, Display, Formatter}};
+mod value;
 
+use std::{cmp, error::Error, fmt::{self, Display, Formatter}, fs::File, io::{BufRead, BufReader}, num::IntErrorKind, sync::atomic::{AtomicUsize, Ordering}, time::Duration};
+
+use common::{cancel::{print_progress, CancelToken, Progress}, compare::{agree, compare, comparison_json, print_comparison}, random::Xorshift, report::report, validate::validate_file};
+use value::Value;
 use itertools::Itertools;
-use rayon::iter::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator};
+use rayon::iter::{IndexedParallelIterator, IntoParallelRefIterator, ParallelBridge, ParallelIterator};
+
+/// An operator which can be placed between the values of an equation. Implement this to search equations with operators
+/// beyond the built in `Operand`s.
+pub trait Operator<N: Value = u64>: Send + Sync {
+	/// Applies the operator to two values. Returns an error if the result is undefined or overflows.
+	fn apply(&self, a: N, b: N) -> Result<N, ApplyError>;
+
+	/// Gets every left hand value `a` for which `apply(a, b) == Ok(result)`, used to search equations right to left
+	/// from their target. Returns None when the values can't be listed (the default), in which case equations are
+	/// searched left to right instead.
+	fn inverse(&self, _result: N, _b: N) -> Option<Vec<N>> {
+		None
+	}
+
+	/// How tightly the operator binds when evaluating with [`EvalOrder::Precedence`], higher values are applied
+	/// first. Operators of equal precedence are applied left to right.
+	fn precedence(&self) -> u8 {
+		0
+	}
+}
+
+/// Why an operator couldn't be applied to two values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApplyError {
+	/// The result isn't defined for the values, e.g. a negative difference or an inexact division.
+	Undefined,
+	/// The result is too large for the type being solved in.
+	Overflow,
+}
+
+impl Display for ApplyError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		match self {
+			ApplyError::Undefined => write!(f, "the result is undefined"),
+			ApplyError::Overflow => write!(f, "the result is too large"),
+		}
+	}
+}
+
+impl Error for ApplyError {}
+
+impl<N: Value, T: Operator<N> + ?Sized> Operator<N> for &T {
+	fn apply(&self, a: N, b: N) -> Result<N, ApplyError> { (**self).apply(a, b) }
+	fn inverse(&self, result: N, b: N) -> Option<Vec<N>> { (**self).inverse(result, b) }
+	fn precedence(&self) -> u8 { (**self).precedence() }
+}
+
+impl<N: Value, T: Operator<N> + ?Sized> Operator<N> for Box<T> {
+	fn apply(&self, a: N, b: N) -> Result<N, ApplyError> { (**self).apply(a, b) }
+	fn inverse(&self, result: N, b: N) -> Option<Vec<N>> { (**self).inverse(result, b) }
+	fn precedence(&self) -> u8 { (**self).precedence() }
+}
 
 /// Operands used for evaluating equations.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum Operand {
+pub enum Operand {
 	Add, Mul, Concat,
+	/// Subtraction, only defined when the result isn't negative.
+	Sub,
+	/// Division, only defined when it divides exactly.
+	Div,
+	/// Exponentiation, `a` to the power of `b`.
+	Pow,
 }
 
-impl Operand {
-	/// Evaluates the operator on two items.
-	fn evaluate(&self, a: usize, b: usize) -> usize {
+impl<N: Value> Operator<N> for Operand {
+	fn apply(&self, a: N, b: N) -> Result<N, ApplyError> {
+		match self {
+			Operand::Add => a.checked_add(b).ok_or(ApplyError::Overflow),
+			Operand::Mul => a.checked_mul(b).ok_or(ApplyError::Overflow),
+			// Both halves are digits, so the only way parsing fails is the result being too large
+			Operand::Concat => format!("{a}{b}").parse().map_err(|_| ApplyError::Overflow),
+			Operand::Sub => a.checked_sub(b).ok_or(ApplyError::Undefined),
+			Operand::Div => (b != N::ZERO && a.is_multiple_of(b)).then(|| a / b).ok_or(ApplyError::Undefined),
+			Operand::Pow => b.to_u32().and_then(|exp| a.checked_pow(exp)).ok_or(ApplyError::Overflow),
+		}
+	}
+
+	fn inverse(&self, result: N, b: N) -> Option<Vec<N>> {
+		Some(match self {
+			Operand::Add => result.checked_sub(b).into_iter().collect(),
+			// Anything multiplied by 0 is 0, so there is no single value to undo
+			Operand::Mul if b == N::ZERO => return (result != N::ZERO).then(Vec::new),
+			Operand::Mul => result.is_multiple_of(b).then(|| result / b).into_iter().collect(),
+			Operand::Concat => {
+				let shift = N::TEN.checked_pow(b.checked_ilog10().unwrap_or(0) + 1);
+				shift.filter(|&shift| result % shift == b).map(|shift| result / shift).into_iter().collect()
+			},
+			Operand::Sub => result.checked_add(b).into_iter().collect(),
+			Operand::Div => result.checked_mul(b).filter(|_| b != N::ZERO).into_iter().collect(),
+			// Anything to the power of 0 is 1, so there is no single value to undo
+			Operand::Pow if b == N::ZERO => return (result != N::ONE).then(Vec::new),
+			Operand::Pow if b == N::ONE => vec![result],
+			Operand::Pow => {
+				// Binary search for the largest integer root, floating point estimates lose precision past 2^53
+				let Some(exp) = b.to_u32() else { return Some(Vec::new()) };
+				let two = N::ONE.checked_add(N::ONE)?;
+				let (mut low, mut high) = (N::ZERO, result);
+				while low < high {
+					let mid = high.checked_sub(high.checked_sub(low)? / two)?;
+					if mid.checked_pow(exp).is_some_and(|power| power <= result) { low = mid } else { high = mid.checked_sub(N::ONE)? }
+				}
+				(low.checked_pow(exp) == Some(result)).then_some(low).into_iter().collect()
+			},
+		})
+	}
+
+	fn precedence(&self) -> u8 {
 		match self {
-			Operand::Add => a + b,
-			Operand::Mul => a * b,
-			Operand::Concat => format!("{a}{b}").parse().expect("Operand concatenation failed."),
+			Operand::Add | Operand::Sub => 0,
+			Operand::Mul | Operand::Div | Operand::Concat => 1,
+			Operand::Pow => 2,
+		}
+	}
+}
+
+/// The order in which the operators of an equation are applied.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EvalOrder {
+	/// Strictly left to right, ignoring precedence, as the puzzle describes.
+	#[default]
+	LeftToRight,
+	/// Higher [`Operator::precedence`] operators first, then left to right, e.g. `2 + 3 * 4 = 14`.
+	Precedence,
+}
+
+impl TryFrom<&str> for Operand {
+	type Error = ();
+
+	/// Converts from the operator's symbol, `+`, `*`, `||`, `-`, `/`, or `^`.
+	fn try_from(value: &str) -> Result<Self, ()> {
+		match value {
+			"+" => Ok(Operand::Add),
+			"*" => Ok(Operand::Mul),
+			"||" => Ok(Operand::Concat),
+			"-" => Ok(Operand::Sub),
+			"/" => Ok(Operand::Div),
+			"^" => Ok(Operand::Pow),
+			_ => Err(()),
 		}
 	}
 }
 
 /// Represents a single equation from day 7 of advent of code.
 #[derive(Debug)]
-struct Equation {
-	target: usize,
-	values: Vec<usize>,
+struct Equation<N = u64> {
+	target: N,
+	values: Vec<N>,
 }
 
-impl Display for Equation {
+impl<N: Value> Display for Equation<N> {
 	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
 		write!(f, "{}:", self.target)?;
 		for val in &self.values { write!(f, " {val}")?; }
@@ -35,46 +164,128 @@ impl Display for Equation {
 	}
 }
 
-impl Equation {
-	/// Creates an equation from a string, returns None on failure.
-	fn from_string(value: &str) -> Option<Self> {
+impl<N: Value> Equation<N> {
+	/// Creates an equation from the given line of the input, with an overflow error when a number is too large for
+	/// `N` and a parse error on any other failure.
+	fn parse(value: &str, line: usize) -> Result<Self, SolutionError> {
 		let strs = value.split(": ").collect::<Vec<&str>>();
 		let (target_str, values_str) = if let [target_str, values_str] = strs.as_slice() {
 			(target_str, values_str)
-		} else { return None };
+		} else { return Err(SolutionError::ParseError { line }) };
 
-		let values = values_str.split(' ').map(|val| { val.parse::<usize>().ok() }).collect::<Option<Vec<usize>>>()?;
-		if values.is_empty() { return None }
+		let parse = |text: &str| text.parse::<N>().map_err(|error| match error.kind() {
+			IntErrorKind::PosOverflow => SolutionError::Overflow { line: Some(line) },
+			_ => SolutionError::ParseError { line },
+		});
+		let values = values_str.split(' ').map(parse).collect::<Result<Vec<N>, _>>()?;
+		if values.is_empty() { return Err(SolutionError::ParseError { line }) }
 
-		Some(Self { target: target_str.parse().ok()?, values })
+		Ok(Self { target: parse(target_str)?, values })
 	}
 
-	/// Evaluates the equation by using some operands, will return None if the operands are of incorrect length.
-	fn evaluate<Op: Borrow<Operand>, It: IntoIterator<Item = Op>>(&self, operands: It) -> Option<usize> {
+	/// Evaluates the equation by using some operands, will return the error of the first operation along the way which
+	/// is undefined or overflows. The operands being of incorrect length is undefined.
+	fn evaluate<Op: Operator<N>, It: IntoIterator<Item = Op>>(&self, operands: It, order: EvalOrder) -> Result<N, ApplyError> {
 		let ops = operands.into_iter().collect_vec();
-		if ops.len() != self.values.len() - 1 { return None; }
-		Some(self.values[1..].iter()
-			.zip(ops.iter())
-			.fold(self.values[0], |a, (&b, op)| op.borrow().evaluate(a, b)))
+		if ops.len() != self.values.len() - 1 { return Err(ApplyError::Undefined); }
+		if order == EvalOrder::LeftToRight {
+			return self.values[1..].iter()
+				.zip(ops.iter())
+				.try_fold(self.values[0], |a, (&b, op)| op.apply(a, b));
+		}
+
+		// Apply any pending operators which bind at least as tightly before pushing the next one
+		let mut values = vec![self.values[0]];
+		let mut pending: Vec<&Op> = Vec::new();
+		let reduce = |values: &mut Vec<N>, op: &Op| {
+			let b = values.pop().expect("Every pending operator has two values");
+			let a = values.pop().expect("Every pending operator has two values");
+			values.push(op.apply(a, b)?);
+			Ok(())
+		};
+		for (&b, op) in self.values[1..].iter().zip(ops.iter()) {
+			while let Some(top) = pending.pop_if(|top| top.precedence() >= op.precedence()) {
+				reduce(&mut values, top)?;
+			}
+			pending.push(op);
+			values.push(b);
+		}
+		while let Some(top) = pending.pop() { reduce(&mut values, top)?; }
+		Ok(values.pop().expect("A single value is left once every operator is applied"))
 	}
 
-	/// Whether or not the target is achievable by some left to right permutation of the given operands.
-	/// Returns true when the target is achievable. Returns None if there was an error encountered.
-	fn target_achievable(&self, operators: &[Operand]) -> Option<bool> {
-		let results = (0..self.values.len() - 1)
+	/// Whether or not the target is achievable by some permutation of the given operands, applied in the given order.
+	/// Returns true when the target is achievable. When it isn't, but some permutation overflowed, that permutation
+	/// might have reached the target in a wider type, so an overflow error is returned rather than false. Trying every
+	/// permutation stops early, returning false, once the token is cancelled.
+	fn target_achievable<Op: Operator<N>>(&self, operators: &[Op], order: EvalOrder, cancel: &CancelToken) -> Result<bool, ApplyError> {
+		if order == EvalOrder::LeftToRight {
+			if let Some(achievable) = self.achievable_from(self.target, self.values.len() - 1, operators) {
+				return Ok(achievable)
+			}
+		}
+		let mut overflowed = false;
+		let permutations = (0..self.values.len() - 1)
 			.map(|_| operators.iter())
 			.multi_cartesian_product()
-			.map(|operands| self.evaluate(operands))
-			.collect::<Option<Vec<usize>>>()?;
-		Some(results.iter().any(|&result| result == self.target))
+			.take_while(|_| !cancel.is_cancelled());
+		for operands in permutations {
+			match self.evaluate(operands, order) {
+				Ok(result) if result == self.target => return Ok(true),
+				Err(ApplyError::Overflow) => overflowed = true,
+				Ok(_) | Err(ApplyError::Undefined) => {},
+			}
+		}
+		if overflowed && !cancel.is_cancelled() { Err(ApplyError::Overflow) } else { Ok(false) }
+	}
+
+	/// Searches right to left, whether `result` is achievable from the first `len + 1` values by undoing operators on the
+	/// last value until only the first is left. Returns None if an operator couldn't be undone.
+	fn achievable_from<Op: Operator<N>>(&self, result: N, len: usize, operators: &[Op]) -> Option<bool> {
+		if len == 0 { return Some(result == self.values[0]) }
+		for op in operators {
+			for a in op.inverse(result, self.values[len])? {
+				if self.achievable_from(a, len - 1, operators)? { return Some(true) }
+			}
+		}
+		Some(false)
+	}
+
+	/// Counts the assignments of +, *, and || which reach the target, indexed by how many || operators they use.
+	fn solution_counts(&self) -> Vec<usize> {
+		let mut counts = vec![0; self.values.len()];
+		if self.count_from(self.target, self.values.len() - 1, 0, &mut counts).is_some() { return counts }
+
+		// Some operator couldn't be undone, so count every assignment left to right instead
+		counts.fill(0);
+		let operators = [Operand::Add, Operand::Mul, Operand::Concat];
+		for operands in (0..self.values.len() - 1).map(|_| operators.iter()).multi_cartesian_product() {
+			let concats = operands.iter().filter(|&&&op| op == Operand::Concat).count();
+			if self.evaluate(operands, EvalOrder::LeftToRight) == Ok(self.target) { counts[concats] += 1; }
+		}
+		counts
+	}
+
+	/// Searches right to left like `achievable_from`, adding every solving assignment to `counts` rather than stopping
+	/// at the first. Returns None if an operator couldn't be undone.
+	fn count_from(&self, result: N, len: usize, concats: usize, counts: &mut [usize]) -> Option<()> {
+		if len == 0 {
+			if result == self.values[0] { counts[concats] += 1; }
+			return Some(())
+		}
+		for op in [Operand::Add, Operand::Mul, Operand::Concat] {
+			let concats = concats + usize::from(op == Operand::Concat);
+			for a in op.inverse(result, self.values[len])? { self.count_from(a, len - 1, concats, counts)?; }
+		}
+		Some(())
 	}
 }
 
-/// Parses an input string into a list of equations, or provides the line number where parsing failed.
-fn parse_input(input: &str) -> Result<Vec<Equation>, usize> {
+/// Parses an input string into a list of equations, or provides the error for the first line which failed.
+fn parse_input<N: Value>(input: &str) -> Result<Vec<Equation<N>>, SolutionError> {
 	input.split('\n')
         .enumerate()
-        .map(|(line, eq)| Equation::from_string(eq).ok_or(line))
+        .map(|(line, eq)| Equation::parse(eq, line))
         .collect()
 }
 
@@ -83,38 +294,194 @@ fn parse_input(input: &str) -> Result<Vec<Equation>, usize> {
 pub enum SolutionError {
 	/// An error parsing the input
 	ParseError { line: usize },
-	/// An error evaluating an equation
-	EvaluationError,
+	/// An error reading the input, from `solve_reader`
+	ReadError { line: usize },
+	/// Solving was cancelled before every equation was checked, from `solve_with_operators_monitored`
+	Cancelled,
+	/// A number on the line, or a value while evaluating its equation, is too large for the type being solved in, or
+	/// without a line, the sum of the achievable targets is
+	Overflow { line: Option<usize> },
+}
+
+impl SolutionError {
+	/// The line the error happened on, None when solving was cancelled or the sum overflowed.
+	pub fn line(&self) -> Option<usize> {
+		match self {
+			SolutionError::ParseError { line } | SolutionError::ReadError { line } => Some(*line),
+			SolutionError::Overflow { line } => *line,
+			SolutionError::Cancelled => None,
+		}
+	}
+}
+
+impl Display for SolutionError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		// Lines are counted from 0 in the error, but from 1 like `validate` when shown
+		match self {
+			SolutionError::ParseError { line } => write!(f, "failed to parse line {}", line + 1),
+			SolutionError::ReadError { line } => write!(f, "failed to read line {}", line + 1),
+			SolutionError::Cancelled => write!(f, "solving was cancelled"),
+			SolutionError::Overflow { line: Some(line) } => write!(f, "a value on line {} is too large", line + 1),
+			SolutionError::Overflow { line: None } => write!(f, "the sum of the achievable targets is too large"),
+		}
+	}
+}
+
+impl Error for SolutionError {}
+
+/// Returns the sum of all equation targets which are achievable with some permutation of the given operands, applied
+/// in the given order.
+pub fn solve_with_operators<Op: Operator>(input: &str, operators: &[Op], order: EvalOrder) -> Result<u64, SolutionError> {
+	solve_with_operators_monitored(input, operators, order, &CancelToken::default(), |_| {})
+}
+
+/// Like `solve_with_operators`, but solves in any integer type `N`, such as `u128` for inputs too large for `u64`.
+/// Stops searching once the token is cancelled, and calls `progress` with the line of each equation after checking it.
+/// Equations are checked in parallel, so `progress` is called from many threads and not in line order.
+pub fn solve_with_operators_monitored<N: Value, Op: Operator<N>>(
+	input: &str, operators: &[Op], order: EvalOrder, cancel: &CancelToken, progress: impl Fn(Progress<usize>) + Sync,
+) -> Result<N, SolutionError> {
+	let equations = parse_input::<N>(input)?;
+	let (total, completed) = (equations.len(), AtomicUsize::new(0));
+	let sum = equations.par_iter()
+		.enumerate()
+		.map(|(line, eq)| {
+			let achievable = eq.target_achievable(operators, order, cancel);
+			progress(Progress { completed: completed.fetch_add(1, Ordering::Relaxed) + 1, total, item: line });
+			let achievable = achievable.map_err(|_| SolutionError::Overflow { line: Some(line) })?;
+			Ok(if achievable { eq.target } else { N::ZERO })
+		})
+		.try_reduce(|| N::ZERO, |a, b| a.checked_add(b).ok_or(SolutionError::Overflow { line: None }));
+	if cancel.is_cancelled() { return Err(SolutionError::Cancelled) }
+	sum
+}
+
+/// Like `solve_with_operators`, but parses and solves the equations line by line as they're read, so the input never
+/// has to fit in memory. Empty lines are skipped. When several lines fail, the error for the earliest is returned no
+/// matter which thread finds it first, and any line's error wins over the sum overflowing.
+pub fn solve_reader<R: BufRead + Send, Op: Operator>(reader: R, operators: &[Op], order: EvalOrder) -> Result<u64, SolutionError> {
+	reader.lines()
+		.enumerate()
+		.par_bridge()
+		.map(|(line, eq)| {
+			let eq = eq.map_err(|_| SolutionError::ReadError { line })?;
+			if eq.is_empty() { return Ok(0) }
+			let eq = Equation::parse(&eq, line)?;
+			let achievable = eq.target_achievable(operators, order, &CancelToken::default())
+				.map_err(|_| SolutionError::Overflow { line: Some(line) })?;
+			Ok(if achievable { eq.target } else { 0 })
+		})
+		.reduce(|| Ok(0), |a, b| match (a, b) {
+			(Ok(a), Ok(b)) => a.checked_add(b).ok_or(SolutionError::Overflow { line: None }),
+			// Overflowing sums have no line, so they rank after every line's error
+			(Err(a), Err(b)) => Err(cmp::min_by_key(a, b, |err| err.line().unwrap_or(usize::MAX))),
+			(Err(err), Ok(_)) | (Ok(_), Err(err)) => Err(err),
+		})
+}
+
+/// Statistics on how an equation can be solved with the +, *, and || operators.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EquationStats {
+	pub target: u64,
+	/// Whether the equation is solvable with only + and *.
+	pub solvable_without_concat: bool,
+	/// The fewest || operators any solution uses, None when the equation isn't solvable.
+	pub min_concats: Option<usize>,
+	/// The number of distinct operator assignments which solve the equation.
+	pub solutions: usize,
+}
+
+/// Gets the solvability statistics of every equation in the input, in input order.
+pub fn equation_stats(input: &str) -> Result<Vec<EquationStats>, SolutionError> {
+	let equations = parse_input::<u64>(input)?;
+	Ok(equations.par_iter()
+		.map(|eq| {
+			let counts = eq.solution_counts();
+			EquationStats {
+				target: eq.target,
+				solvable_without_concat: counts[0] > 0,
+				min_concats: counts.iter().position(|&count| count > 0),
+				solutions: counts.iter().sum(),
+			}
+		})
+		.collect())
+}
+
+/// A structural problem with an equation, found by `validate`. Lines count from 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputProblem {
+	/// A line is blank, including a trailing newline at the end of the input.
+	EmptyLine { line: usize },
+	/// A line doesn't have a single `: ` between the target and values.
+	MissingColon { line: usize },
+	InvalidTarget { line: usize, text: String },
+	InvalidValue { line: usize, text: String },
+	/// An equation has a target but no values.
+	NoValues { line: usize },
+}
+
+impl Display for InputProblem {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		match self {
+			InputProblem::EmptyLine { line } => write!(f, "Line {line} is empty"),
+			InputProblem::MissingColon { line } => write!(f, "Line {line} should have a single ': ' after the target"),
+			InputProblem::InvalidTarget { line, text } => write!(f, "Line {line}: invalid target '{text}'"),
+			InputProblem::InvalidValue { line, text } => write!(f, "Line {line}: invalid value '{text}'"),
+			InputProblem::NoValues { line } => write!(f, "Line {line} has no values"),
+		}
+	}
+}
+
+/// Checks every equation is well formed without solving any, reporting every problem rather than stopping at the first.
+pub fn validate(input: &str) -> Vec<InputProblem> {
+	let mut problems = Vec::new();
+	for (idx, text) in input.split('\n').enumerate() {
+		let line = idx + 1;
+		if text.trim().is_empty() { problems.push(InputProblem::EmptyLine { line }); continue }
+		let [target, values] = text.split(": ").collect::<Vec<_>>()[..] else { problems.push(InputProblem::MissingColon { line }); continue };
+		if target.parse::<u64>().is_err() { problems.push(InputProblem::InvalidTarget { line, text: target.to_string() }); }
+		if values.is_empty() { problems.push(InputProblem::NoValues { line }); continue }
+		problems.extend(values.split(' ')
+			.filter(|value| value.parse::<u64>().is_err())
+			.map(|value| InputProblem::InvalidValue { line, text: value.to_string() }));
+	}
+	problems
+}
+
+/// Generates practice equations, each with 2 to 6 values below 100. Most targets are made by applying random +, *, and
+/// || operators left to right so they're solvable, the rest are nudged off by one so they might not be.
+pub fn generate_practice(seed: u64, count: usize) -> String {
+	let mut rng = Xorshift::new(seed);
+	(0..count).map(|_| {
+		let values = (0..2 + rng.below(5)).map(|_| 1 + rng.below(99)).collect::<Vec<_>>();
+		let operators = [Operand::Add, Operand::Mul, Operand::Concat];
+		let target = values[1..].iter().fold(values[0], |a, &b| {
+			operators[rng.below(3) as usize].apply(a, b).expect("Values are small enough to never overflow")
+		});
+		let target = if rng.below(3) == 0 { target + 1 } else { target };
+		format!("{target}: {}", values.iter().join(" "))
+	}).join("\n")
 }
 
 /// Solves part1 - returns the sum of all equation targets which are achievable left to right with
 /// some permutation of the + and * operands.
-pub fn part1_solution(input: &str) -> Result<usize, SolutionError> {
-	let equations = parse_input(input).map_err(|line| SolutionError::ParseError { line })?;
-	let achievable = equations.par_iter()
-		.map(|eq| eq.target_achievable(&[Operand::Add, Operand::Mul]))
-		.collect::<Option<Vec<bool>>>()
-		.ok_or(SolutionError::EvaluationError)?;
-	Ok(achievable.par_iter()
-		.zip(equations)
-		.filter_map(|(achievable, eq)| achievable.then_some(eq.target))
-		.sum())
+pub fn part1_solution(input: &str) -> Result<u64, SolutionError> {
+	solve_with_operators(input, &[Operand::Add, Operand::Mul], EvalOrder::LeftToRight)
 }
 
 /// Solves part2 - returns the sum of all equation targets which are achievable left to right with
 /// some permutation of the +, *, and || (concatenation) operands.
-pub fn part2_solution(input: &str) -> Result<usize, SolutionError> {
-	let equations = parse_input(input).map_err(|line| SolutionError::ParseError { line })?;
-	let achievable = equations.par_iter()
-		.map(|eq| eq.target_achievable(&[Operand::Add, Operand::Mul, Operand::Concat]))
-		.collect::<Option<Vec<bool>>>()
-		.ok_or(SolutionError::EvaluationError)?;
-	Ok(achievable.par_iter()
-		.zip(equations)
-		.filter_map(|(achievable, eq)| achievable.then_some(eq.target))
-		.sum())
+pub fn part2_solution(input: &str) -> Result<u64, SolutionError> {
+	solve_with_operators(input, &[Operand::Add, Operand::Mul, Operand::Concat], EvalOrder::LeftToRight)
 }
 
+/// Wraps an operator without its inverse, so equations using it are searched by trying every assignment of operators.
+struct Forward<Op>(Op);
+
+impl<N: Value, Op: Operator<N>> Operator<N> for Forward<Op> {
+	fn apply(&self, a: N, b: N) -> Result<N, ApplyError> { self.0.apply(a, b) }
+	fn precedence(&self) -> u8 { self.0.precedence() }
+}
 
 /// Entry point to the day 7 task.
 pub fn main() {
@@ -129,9 +496,346 @@ pub fn main() {
 292: 11 6 16 20";
 	let input = include_str!("day7.txt");
 
-	println!("Part 1 Solution on Example: {:#?}", part1_solution(example));
-	println!("Part 1 Solution on Input: {:#?}", part1_solution(input));
+	let args = std::env::args().collect::<Vec<_>>();
+
+	// Generate a practice input with `--practice <seed>` with `--count <n>` equations (20 by default). The answers are only shown with `--reveal`, the
+	// same seed always generates the same input so they can be checked later
+	if let Some(seed) = args.iter().skip_while(|&arg| arg != "--practice").nth(1) {
+		let seed = seed.parse().expect("Invalid seed");
+		let count = args.iter().skip_while(|&arg| arg != "--count").nth(1).map_or(20, |count| count.parse().expect("Invalid count"));
+		let practice = generate_practice(seed, count);
+		println!("{practice}");
+		if args.iter().any(|arg| arg == "--reveal") {
+			println!("Part 1: {:?}", part1_solution(&practice));
+			println!("Part 2: {:?}", part2_solution(&practice));
+		} else {
+			eprintln!("Run again with --reveal to see the answers");
+		}
+		return;
+	}
+
+	// Check a file for problems without solving it with `--validate <path>`
+	if let Some(path) = args.iter().skip_while(|&arg| arg != "--validate").nth(1) {
+		validate_file(path, validate);
+		return;
+	}
+	// Run every implementation of a part on the input with `--compare <part>`, checking their answers agree. Time each
+	// over `--samples <n>` runs (1 by default), printing the timings as JSON for benchmark tools with `--json`
+	if let Some(part) = args.iter().skip_while(|&arg| arg != "--compare").nth(1) {
+		let operators = match part.as_str() {
+			"1" => vec![Operand::Add, Operand::Mul],
+			"2" => vec![Operand::Add, Operand::Mul, Operand::Concat],
+			_ => panic!("Unknown part {part}, expected 1 or 2"),
+		};
+		let forward = operators.iter().map(Forward).collect::<Vec<_>>();
+		let samples = args.iter().skip_while(|&arg| arg != "--samples").nth(1).map_or(1, |samples| samples.parse().expect("Invalid samples"));
+		let results = compare(input, &[
+			("right to left", &|input| solve_with_operators(input, &operators, EvalOrder::LeftToRight)),
+			("every assignment", &|input| solve_with_operators(input, &forward, EvalOrder::LeftToRight)),
+			("streamed", &|input| solve_reader(input.as_bytes(), &operators, EvalOrder::LeftToRight)),
+		], samples);
+		if args.iter().any(|arg| arg == "--json") { println!("{}", comparison_json(&results)) } else { print_comparison(&results) }
+		if !agree(&results) {
+			eprintln!("Implementations disagree");
+			std::process::exit(1)
+		}
+		return;
+	}
+
+	// Solve with any set of operators given by their symbols, e.g. `--operators "+ * - /"`, add `--precedence` to apply
+	// them with normal operator precedence rather than left to right
+	let order = if args.iter().any(|arg| arg == "--precedence") { EvalOrder::Precedence } else { EvalOrder::LeftToRight };
+
+	// Give up on searching with custom operators after a number of seconds with `--timeout <seconds>`, some sets of
+	// operators can't be undone and fall back to trying every permutation. Show the search's progress with `--progress`
+	let cancel = CancelToken::default();
+	if let Some(seconds) = args.iter().skip_while(|&arg| arg != "--timeout").nth(1) {
+		cancel.cancel_after(Duration::from_secs_f64(seconds.parse().expect("Invalid timeout")));
+	}
+	let show_progress = args.iter().any(|arg| arg == "--progress");
+	let report_progress = |progress: Progress<usize>| if show_progress { print_progress(progress) };
+	if let Some(symbols) = args.iter().skip_while(|&arg| arg != "--operators").nth(1) {
+		let operators = symbols.split_whitespace()
+			.map(|symbol| Operand::try_from(symbol).unwrap_or_else(|_| panic!("Unknown operator {symbol}")))
+			.collect::<Vec<_>>();
+		// Solve in u128 rather than u64 with `--wide`, for inputs whose numbers or sums are too large
+		let solve = |input| if args.iter().any(|arg| arg == "--wide") {
+			report(solve_with_operators_monitored::<u128, _>(input, &operators, order, &cancel, report_progress), "solving with custom operators")
+		} else {
+			report(solve_with_operators_monitored::<u64, _>(input, &operators, order, &cancel, report_progress), "solving with custom operators")
+		};
+		println!("Solution on Example: {}", solve(example));
+		println!("Solution on Input: {}", solve(input));
+		return;
+	}
+
+	// Solve both parts on a file which is streamed rather than loaded, e.g. a generated stress input
+	if let Some(path) = args.iter().skip_while(|&arg| arg != "--file").nth(1) {
+		let open = || BufReader::new(File::open(path).expect("Failed to open input file"));
+		println!("Part 1 Solution on File: {}", report(solve_reader(open(), &[Operand::Add, Operand::Mul], order), "solving day 7 part 1"));
+		println!("Part 2 Solution on File: {}", report(solve_reader(open(), &[Operand::Add, Operand::Mul, Operand::Concat], order), "solving day 7 part 2"));
+		return;
+	}
+
+	// Print how each equation can be solved, and a summary of how many || operators the input needs
+	if args.iter().any(|arg| arg == "--stats") {
+		for stats in equation_stats(example).expect("Example should parse") { println!("{stats:?}"); }
+		let stats = equation_stats(input).expect("Input should parse");
+		for (min_concats, group) in &stats.iter().map(|stats| stats.min_concats).sorted().chunk_by(|&min| min) {
+			println!("Minimum || operators {min_concats:?}: {} equations", group.count());
+		}
+		return;
+	}
+
+	// Compare the answers of both parts when evaluated with precedence
+	if order == EvalOrder::Precedence {
+		for (part, operators) in [(1, &[Operand::Add, Operand::Mul][..]), (2, &[Operand::Add, Operand::Mul, Operand::Concat][..])] {
+			let left_to_right = solve_with_operators(input, operators, EvalOrder::LeftToRight);
+			let precedence = solve_with_operators(input, operators, EvalOrder::Precedence);
+			println!("Part {part} on Input: left to right {left_to_right:?}, with precedence {precedence:?}");
+		}
+		return;
+	}
+
+	println!("Part 1 Solution on Example: {}", report(part1_solution(example), "solving day 7 part 1"));
+	println!("Part 1 Solution on Input: {}", report(part1_solution(input), "solving day 7 part 1"));
+
+	println!("Part 2 Solution on Example: {}", report(part2_solution(example), "solving day 7 part 2"));
+	println!("Part 2 Solution on Input: {}", report(part2_solution(input), "solving day 7 part 2"));
+}
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+
+	/// Tests the extended operators are only used where they're defined
+	#[test]
+	fn test_extended_operators() {
+		assert_eq!(Operand::Sub.apply(5u64, 3), Ok(2));
+		assert_eq!(Operand::Sub.apply(3u64, 5), Err(ApplyError::Undefined));
+		assert_eq!(Operand::Div.apply(12u64, 4), Ok(3));
+		assert_eq!(Operand::Div.apply(12u64, 5), Err(ApplyError::Undefined));
+		assert_eq!(Operand::Div.apply(12u64, 0), Err(ApplyError::Undefined));
+		assert_eq!(Operand::Pow.apply(2u64, 10), Ok(1024));
+		assert_eq!(Operand::Pow.apply(2u64, 64), Err(ApplyError::Overflow));
+
+		// Evaluated left to right (10 - 6) / 2 = 2, so 7 isn't reachable
+		assert_eq!(solve_with_operators("2: 10 6 2", &[Operand::Sub, Operand::Div], EvalOrder::LeftToRight), Ok(2));
+		assert_eq!(solve_with_operators("7: 10 6 2", &[Operand::Sub, Operand::Div], EvalOrder::LeftToRight), Ok(0));
+		assert_eq!(solve_with_operators("8: 2 3\n9: 3 2", &[Operand::Pow], EvalOrder::LeftToRight), Ok(17));
+		assert_eq!(solve_with_operators("8: 3 2", &[Operand::Add, Operand::Mul], EvalOrder::LeftToRight), Ok(0));
+	}
+
+	/// Tests every built in operator's inverse undoes exactly the values which apply to the result
+	#[test]
+	fn test_inverses() {
+		let operators = [Operand::Add, Operand::Mul, Operand::Concat, Operand::Sub, Operand::Div, Operand::Pow];
+		for op in operators {
+			for b in 0u64..12 {
+				for result in 0..120 {
+					let Some(inverse) = op.inverse(result, b) else { continue };
+					let expected = (0..1500).filter(|&a| op.apply(a, b) == Ok(result)).collect::<Vec<_>>();
+					assert_eq!(inverse, expected, "{op:?} inverse of {result} with {b}");
+				}
+			}
+		}
+
+		// Roots of results past 2^53, where floats can't hold every integer
+		let large = (1u64 << 60) + 100;
+		assert_eq!(Operand::Pow.inverse(large, 1), Some(vec![large]));
+		assert_eq!(Operand::Pow.inverse(large, 2), Some(vec![]));
+		assert_eq!(Operand::Pow.inverse(3_037_000_499u64 * 3_037_000_499, 2), Some(vec![3_037_000_499]));
+		assert_eq!(Operand::Pow.inverse(3_037_000_499u64 * 3_037_000_499 - 1, 2), Some(vec![]));
+		assert_eq!(Operand::Pow.inverse(u64::MAX, 64), Some(vec![]));
+		assert_eq!(Operand::Pow.inverse((1u128 << 120) * 3, 1), Some(vec![(1u128 << 120) * 3]));
+		assert_eq!(Operand::Pow.inverse(3u128.pow(80), 5), Some(vec![3u128.pow(16)]));
+		assert_eq!(solve_with_operators(&format!("{large}: {large} 1"), &[Operand::Pow], EvalOrder::LeftToRight), Ok(large));
+	}
+
+	/// An operator without an inverse, which keeps the larger value
+	struct Max;
+
+	impl Operator for Max {
+		fn apply(&self, a: u64, b: u64) -> Result<u64, ApplyError> { Ok(a.max(b)) }
+	}
+
+	/// Tests custom operators can be mixed with the built in ones
+	#[test]
+	fn test_custom_operators() {
+		let operators: [&dyn Operator; 2] = [&Max, &Operand::Add];
+		assert_eq!(solve_with_operators("9: 3 9\n12: 3 9\n10: 3 9", &operators, EvalOrder::LeftToRight), Ok(21));
+		assert_eq!(solve_with_operators("9: 3 9", &[Max], EvalOrder::LeftToRight), Ok(9));
+	}
+
+	/// Tests evaluating with precedence applies multiplication and concatenation before addition
+	#[test]
+	fn test_precedence() {
+		let equation = Equation::<u64>::parse("14: 2 3 4", 0).unwrap();
+		assert_eq!(equation.evaluate([Operand::Add, Operand::Mul], EvalOrder::LeftToRight), Ok(20));
+		assert_eq!(equation.evaluate([Operand::Add, Operand::Mul], EvalOrder::Precedence), Ok(14));
+		assert_eq!(equation.evaluate([Operand::Add, Operand::Concat], EvalOrder::Precedence), Ok(36));
+		assert_eq!(equation.evaluate([Operand::Sub, Operand::Add], EvalOrder::Precedence), Err(ApplyError::Undefined));
+		assert_eq!(equation.evaluate([Operand::Pow, Operand::Mul], EvalOrder::Precedence), Ok(32));
+		assert_eq!(equation.evaluate([Operand::Mul, Operand::Pow], EvalOrder::Precedence), Ok(162));
+
+		let operators = [Operand::Add, Operand::Mul];
+		assert_eq!(solve_with_operators("14: 2 3 4\n20: 2 3 4", &operators, EvalOrder::LeftToRight), Ok(20));
+		assert_eq!(solve_with_operators("14: 2 3 4\n20: 2 3 4", &operators, EvalOrder::Precedence), Ok(14));
+	}
+
+	/// Tests the solvability statistics of the example equations
+	#[test]
+	fn test_equation_stats() {
+		let input = "190: 10 19\n3267: 81 40 27\n83: 17 5\n156: 15 6\n7290: 6 8 6 15\n0: 0 0 0";
+		let stats = equation_stats(input).unwrap();
+		let summary = stats.iter()
+			.map(|stats| (stats.target, stats.solvable_without_concat, stats.min_concats, stats.solutions))
+			.collect::<Vec<_>>();
+		assert_eq!(summary, [
+			(190, true, Some(0), 1),
+			(3267, true, Some(0), 2),
+			(83, false, None, 0),
+			(156, false, Some(1), 1),
+			(7290, false, Some(1), 1),
+			// Multiplying by 0 can't be undone, 0 + 0 and 0 * 0 and 00 in each place all solve it
+			(0, true, Some(0), 9),
+		]);
+	}
+
+	/// Tests streaming the input gives the same answer as loading it
+	#[test]
+	fn test_solve_reader() {
+		let input = "190: 10 19\n3267: 81 40 27\n83: 17 5\n156: 15 6\n\n292: 11 6 16 20\n";
+		let operators = [Operand::Add, Operand::Mul, Operand::Concat];
+		assert_eq!(solve_reader(input.as_bytes(), &operators, EvalOrder::LeftToRight), Ok(190 + 3267 + 156 + 292));
+		assert_eq!(solve_reader("1: 1\n2 3".as_bytes(), &operators, EvalOrder::LeftToRight), Err(SolutionError::ParseError { line: 1 }));
+
+		// Whichever thread reads a bad line first, the earliest bad line is reported
+		let input = (0..2000).map(|line| if line == 700 || line == 1500 { String::from("bad") } else { format!("{line}: {line}") }).join("\n");
+		for _ in 0..10 {
+			assert_eq!(solve_reader(input.as_bytes(), &operators, EvalOrder::LeftToRight), Err(SolutionError::ParseError { line: 700 }));
+		}
+
+		// A bad line is reported even when the sum of the lines before it overflows
+		let large = u64::MAX / 2;
+		let input = (0..2000).map(|line| if line == 1500 { String::from("bad") } else { format!("{large}: {large}") }).join("\n");
+		for _ in 0..10 {
+			assert_eq!(solve_reader(input.as_bytes(), &operators, EvalOrder::LeftToRight), Err(SolutionError::ParseError { line: 1500 }));
+		}
+	}
+
+	/// Tests a cancelled search stops with an error
+	#[test]
+	fn test_cancel_search() {
+		let input = "190: 10 19\n3267: 81 40 27\n83: 17 5";
+		let cancel = CancelToken::default();
+		assert_eq!(solve_with_operators_monitored::<u64, _>(input, &[Operand::Add, Operand::Mul], EvalOrder::Precedence, &cancel, |_| {}), Ok(190 + 3267));
+		cancel.cancel();
+		assert_eq!(solve_with_operators_monitored::<u64, _>(input, &[Operand::Add, Operand::Mul], EvalOrder::Precedence, &cancel, |_| {}), Err(SolutionError::Cancelled));
+	}
+
+	/// Tests progress is reported once for every equation, ending complete
+	#[test]
+	fn test_progress() {
+		let reports = std::sync::Mutex::new(Vec::new());
+		let operators = [Operand::Add, Operand::Mul];
+		let report = |progress| reports.lock().unwrap().push(progress);
+		let solution = solve_with_operators_monitored::<u64, _>("190: 10 19\n3267: 81 40 27\n83: 17 5", &operators, EvalOrder::LeftToRight, &CancelToken::default(), report);
+		assert_eq!(solution, Ok(190 + 3267));
+		let mut reports = reports.into_inner().unwrap();
+		reports.sort_by_key(|progress| progress.completed);
+		assert_eq!(reports.iter().map(|progress| (progress.completed, progress.total)).collect::<Vec<_>>(), [(1, 3), (2, 3), (3, 3)]);
+		let mut lines = reports.iter().map(|progress| progress.item).collect::<Vec<_>>();
+		lines.sort();
+		assert_eq!(lines, [0, 1, 2]);
+	}
+
+	/// Tests validation reports every problem with the equations at once
+	#[test]
+	fn test_validate() {
+		assert!(validate("190: 10 19\n3267: 81 40 27").is_empty());
+		assert_eq!(validate("190: 10 x9\n\n3267 81\nabc: \n1: 2  3"), [
+			InputProblem::InvalidValue { line: 1, text: String::from("x9") },
+			InputProblem::EmptyLine { line: 2 },
+			InputProblem::MissingColon { line: 3 },
+			InputProblem::InvalidTarget { line: 4, text: String::from("abc") },
+			InputProblem::NoValues { line: 4 },
+			InputProblem::InvalidValue { line: 5, text: String::new() },
+		]);
+		assert_eq!(validate("190: 10 19\n"), [InputProblem::EmptyLine { line: 2 }]);
+	}
+
+	/// Tests practice equations are valid, reproducible, and mostly solvable
+	#[test]
+	fn test_generate_practice() {
+		let practice = generate_practice(7, 50);
+		assert_eq!(practice, generate_practice(7, 50));
+		assert!(validate(&practice).is_empty());
+		let stats = equation_stats(&practice).unwrap();
+		assert_eq!(stats.len(), 50);
+		assert!(stats.iter().filter(|stats| stats.solutions > 0).count() >= 25);
+	}
+
+	/// Tests searching without inverses finds the same answers as searching right to left
+	#[test]
+	fn test_forward_search() {
+		let input = "190: 10 19\n3267: 81 40 27\n83: 17 5\n156: 15 6\n7290: 6 8 6 15\n192: 17 8 14";
+		let forward = [Forward(Operand::Add), Forward(Operand::Mul), Forward(Operand::Concat)];
+		assert_eq!(solve_with_operators(input, &forward[..2], EvalOrder::LeftToRight), part1_solution(input));
+		assert_eq!(solve_with_operators(input, &forward, EvalOrder::LeftToRight), part2_solution(input));
+	}
+
+	/// Tests error reports say which line failed
+	#[test]
+	fn test_report() {
+		assert_eq!(report(part1_solution("190: 10 19"), "solving part 1"), "190");
+		assert_eq!(report(part1_solution("190: 10 19\n3267 81"), "solving part 1"), "error while solving part 1: failed to parse line 2");
+	}
+
+	/// Tests compared implementations are timed for every sample and reported as JSON
+	#[test]
+	fn test_compare_json() {
+		let results = compare("190: 10 19", &[("search", &|input| part1_solution(input))], 3);
+		assert!(agree(&results));
+		assert_eq!(results[0].samples.len(), 3);
+		let json = comparison_json(&results);
+		assert!(json.starts_with(r#"[{"name":"search","unit":"s","value":"#));
+		assert_eq!(json.matches(',').count(), 8);
+	}
+
+	/// Tests numbers and sums too large for u64 are overflow errors, and solve in u128
+	#[test]
+	fn test_wide_values() {
+		let operators = [Operand::Add, Operand::Mul, Operand::Concat];
+		let solve = |input| solve_with_operators(input, &operators, EvalOrder::LeftToRight);
+		let wide = |input| solve_with_operators_monitored::<u128, _>(input, &operators, EvalOrder::LeftToRight, &CancelToken::default(), |_| {});
+
+		let large = "1: 1\n20000000000000000000: 10000000000000000000 10000000000000000000";
+		assert_eq!(solve(large), Err(SolutionError::Overflow { line: Some(1) }));
+		assert_eq!(wide(large), Ok(20000000000000000001));
+
+		let sum = "10000000000000000000: 10000000000000000000\n10000000000000000000: 10000000000 1000000000";
+		assert_eq!(solve(sum), Err(SolutionError::Overflow { line: None }));
+		assert_eq!(wide(sum), Ok(20000000000000000000));
+		assert_eq!(wide("1000000000000000000000000: 1000000000000 1000000000000"), Ok(1000000000000000000000000));
+	}
+
+	/// Tests values overflowing while evaluating are errors unless the target is reached some other way, and solve in u128
+	#[test]
+	fn test_evaluation_overflow() {
+		assert_eq!(Operand::Mul.apply(u64::MAX, 2), Err(ApplyError::Overflow));
+		assert_eq!(Operand::Concat.apply(u64::MAX, 1), Err(ApplyError::Overflow));
+
+		// Searched by trying every assignment, which overflows on * before finding 10^11 - 10^10 with -
+		let operators = [Forward(Operand::Mul), Forward(Operand::Sub)];
+		let solve = |input| solve_with_operators(input, &operators, EvalOrder::LeftToRight);
+		let wide = |input| solve_with_operators_monitored::<u128, _>(input, &operators, EvalOrder::LeftToRight, &CancelToken::default(), |_| {});
+		assert_eq!(solve("90000000000: 100000000000 10000000000"), Ok(90000000000));
+		assert_eq!(solve("1: 2 3\n7: 100000000000 10000000000"), Err(SolutionError::Overflow { line: Some(1) }));
+		assert_eq!(wide("1: 2 3\n7: 100000000000 10000000000"), Ok(0));
+		assert_eq!(solve_reader("7: 100000000000 10000000000".as_bytes(), &operators, EvalOrder::LeftToRight), Err(SolutionError::Overflow { line: Some(0) }));
+		assert_eq!(report(solve("7: 100000000000 10000000000"), "solving"), "error while solving: a value on line 1 is too large");
+	}
 
-	println!("Part 2 Solution on Example: {:#?}", part2_solution(example));
-	println!("Part 2 Solution on Input: {:#?}", part2_solution(input));
 }
@@ -0,0 +1,42 @@
+//! The unsigned integer types equations can be solved in.
+
+use std::{fmt::{Debug, Display}, num::ParseIntError, ops::{Div, Rem}, str::FromStr};
+
+/// An unsigned integer type which equation values, targets, and sums are held in. Equations are solved in `u64` by
+/// default, `u128` fits inputs whose values or sums are too large for it.
+pub trait Value:
+	Copy + Ord + Debug + Display + FromStr<Err = ParseIntError> + Div<Output = Self> + Rem<Output = Self> + Send + Sync
+{
+	const ZERO: Self;
+	const ONE: Self;
+	const TEN: Self;
+
+	fn checked_add(self, other: Self) -> Option<Self>;
+	fn checked_sub(self, other: Self) -> Option<Self>;
+	fn checked_mul(self, other: Self) -> Option<Self>;
+	fn checked_pow(self, exp: u32) -> Option<Self>;
+	fn checked_ilog10(self) -> Option<u32>;
+	fn is_multiple_of(self, other: Self) -> bool;
+	/// Converts to a `u32` exponent, None if it's too large.
+	fn to_u32(self) -> Option<u32>;
+}
+
+macro_rules! impl_value {
+	($($type:ty),*) => {$(
+		impl Value for $type {
+			const ZERO: Self = 0;
+			const ONE: Self = 1;
+			const TEN: Self = 10;
+
+			fn checked_add(self, other: Self) -> Option<Self> { self.checked_add(other) }
+			fn checked_sub(self, other: Self) -> Option<Self> { self.checked_sub(other) }
+			fn checked_mul(self, other: Self) -> Option<Self> { self.checked_mul(other) }
+			fn checked_pow(self, exp: u32) -> Option<Self> { self.checked_pow(exp) }
+			fn checked_ilog10(self) -> Option<u32> { self.checked_ilog10() }
+			fn is_multiple_of(self, other: Self) -> bool { self.is_multiple_of(other) }
+			fn to_u32(self) -> Option<u32> { u32::try_from(self).ok() }
+		}
+	)*};
+}
+
+impl_value!(u64, u128);
@@ -1,10 +1,12 @@
-use std::{cmp, collections::HashMap, fmt::{self, Display, Formatter, Write}, ops::Range};
+use std::{collections::{BTreeMap, BTreeSet, HashMap, HashSet}, error::Error, fmt::{self, Display, Formatter, Write}, ops::Range};
 
+use common::{random::Xorshift, report::report, validate::validate_file};
 use itertools::Itertools;
 use nalgebra::Vector2;
 
 /// Describes a bounding box on the map
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 struct BoundingBox {
 	/// Everything within the bounding box must be >= top_left
 	top_left: Vector2<i32>,
@@ -21,7 +23,8 @@ impl BoundingBox {
 
 /// Represents the variant of a single antenna on the map
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
-enum AntennaVariant {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AntennaVariant {
 	// Lowercase variants
 	VariantLowerA, VariantLowerB, VariantLowerC, VariantLowerD, VariantLowerE, VariantLowerF, VariantLowerG, VariantLowerH, VariantLowerI, VariantLowerJ,
 	VariantLowerK, VariantLowerL, VariantLowerM, VariantLowerN, VariantLowerO, VariantLowerP, VariantLowerQ, VariantLowerR, VariantLowerS, VariantLowerT,
@@ -83,9 +86,146 @@ impl Display for AntennaVariant {
 	}
 }
 
+/// Possible errors when strictly parsing a map.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MapParseError {
+	/// The map has no lines.
+	Empty,
+	/// A character which isn't a frequency, `.`, or whitespace was found at the given line and column, counted from 1.
+	UnknownCharacter { line: usize, column: usize, character: char },
+}
+
+impl Display for MapParseError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		match self {
+			MapParseError::Empty => write!(f, "The map is empty"),
+			MapParseError::UnknownCharacter { line, column, character } => {
+				write!(f, "Unknown character {character:?} at line {line}, column {column}")
+			},
+		}
+	}
+}
+
+impl Error for MapParseError {}
+
+/// A multiple of the distance between two antennas, `numerator / denominator`, at which an antinode is placed past the
+/// second antenna. 1 is the antinode from part 1, 0 is the antenna itself, and -1/2 is the midpoint between the pair.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StepMultiplier {
+	pub numerator: i32,
+	pub denominator: i32,
+}
+
+impl StepMultiplier {
+	/// Creates a whole number multiplier.
+	pub fn whole(multiplier: i32) -> Self {
+		Self { numerator: multiplier, denominator: 1 }
+	}
+
+	/// Applies the multiplier to the step between two antennas, None if the result isn't a whole position.
+	fn apply(&self, step: Vector2<i32>) -> Option<Vector2<i32>> {
+		let scaled = step * self.numerator;
+		(self.denominator != 0 && scaled.iter().all(|component| component % self.denominator == 0))
+			.then(|| scaled / self.denominator)
+	}
+}
+
+impl TryFrom<&str> for StepMultiplier {
+	type Error = ();
+
+	/// Converts from a whole number or a fraction, e.g. `2` or `-1/2`.
+	fn try_from(value: &str) -> Result<Self, ()> {
+		let (numerator, denominator) = value.split_once('/').unwrap_or((value, "1"));
+		let denominator = denominator.parse().map_err(|_| ())?;
+		if denominator == 0 { return Err(()) }
+		Ok(Self { numerator: numerator.parse().map_err(|_| ())?, denominator })
+	}
+}
+
+/// Where the antinodes of a pair of antennas are placed, as multiples of the distance between them past the second
+/// antenna.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AntinodeMode {
+	/// A single antinode one distance past the second antenna, as in part 1.
+	Single,
+	/// An antinode for each multiple in the range, where 0 is the second antenna itself.
+	Harmonics(Range<usize>),
+	/// An antinode at every multiple from 0 until leaving the map, as in part 2.
+	Unbounded,
+	/// An antinode at each multiplier which lands on a whole position.
+	Steps(Vec<StepMultiplier>),
+}
+
+/// Statistics on the antinodes of a single frequency.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FrequencyReport {
+	pub frequency: AntennaVariant,
+	/// The number of antennas of this frequency.
+	pub antennas: usize,
+	/// The number of in bounds antinodes, including antinodes at the same position from different pairs.
+	pub antinodes: usize,
+	/// Every position with at least one antinode of this frequency, sorted by row then column.
+	pub unique_antinodes: Vec<Vector2<i32>>,
+	/// The number of this frequency's antinode positions shared with each other frequency which shares any.
+	pub overlaps: BTreeMap<AntennaVariant, usize>,
+}
+
+/// The differences between the antennas of two maps, see `Map::diff`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MapDiff {
+	/// Antennas only in the new map, sorted by frequency then row then column.
+	pub added: Vec<(AntennaVariant, Vector2<i32>)>,
+	/// Antennas only in the old map, sorted by frequency then row then column.
+	pub removed: Vec<(AntennaVariant, Vector2<i32>)>,
+	/// Antennas of a frequency which was both removed from one position and added at another, as (frequency, from, to).
+	/// The closest removed and added antennas are paired first. Sorted like `added`, by where they moved from.
+	pub moved: Vec<(AntennaVariant, Vector2<i32>, Vector2<i32>)>,
+	/// Antennas in the same position in both maps.
+	pub unchanged: Vec<(AntennaVariant, Vector2<i32>)>,
+	/// The (width, height) of the old and new maps.
+	pub sizes: (Vector2<i32>, Vector2<i32>),
+}
+
+impl MapDiff {
+	/// Whether the maps have the same antennas and size.
+	pub fn is_empty(&self) -> bool {
+		self.added.is_empty() && self.removed.is_empty() && self.moved.is_empty() && self.sizes.0 == self.sizes.1
+	}
+
+	/// Renders both maps overlaid, large enough to fit either. Unchanged antennas are drawn as their frequency, added
+	/// antennas as `+`, removed as `-`, and moved antennas as `o` where they were and `*` where they are now.
+	pub fn overlay(&self) -> String {
+		let (width, height) = (self.sizes.0.x.max(self.sizes.1.x), self.sizes.0.y.max(self.sizes.1.y));
+		let mut lines = vec![vec!['.'; width as usize]; height as usize];
+		let mut draw = |pos: Vector2<i32>, c: char| lines[pos.y as usize][pos.x as usize] = c;
+		for &(variant, pos) in &self.unchanged { draw(pos, variant.into()); }
+		for &(_, pos) in &self.added { draw(pos, '+'); }
+		for &(_, pos) in &self.removed { draw(pos, '-'); }
+		for &(_, from, to) in &self.moved { draw(from, 'o'); draw(to, '*'); }
+		lines.iter().map(|line| line.iter().collect::<String>()).join("\n")
+	}
+}
+
+impl Display for MapDiff {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		if self.sizes.0 != self.sizes.1 {
+			let (old, new) = self.sizes;
+			writeln!(f, "Resized from {}x{} to {}x{}", old.x, old.y, new.x, new.y)?;
+		}
+		for (variant, pos) in &self.added { writeln!(f, "Added {variant} at ({}, {})", pos.x, pos.y)?; }
+		for (variant, pos) in &self.removed { writeln!(f, "Removed {variant} at ({}, {})", pos.x, pos.y)?; }
+		for (variant, from, to) in &self.moved {
+			writeln!(f, "Moved {variant} from ({}, {}) to ({}, {})", from.x, from.y, to.x, to.y)?;
+		}
+		Ok(())
+	}
+}
+
 /// Represents the full map from the puzzle, containing the bounds of the city and all antenna locations.
 #[derive(Debug)]
-struct Map {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Map {
 	/// The bounds of the map
 	bounds: BoundingBox,
 	/// All antennas on the map.
@@ -126,6 +266,81 @@ impl Display for Map {
 
 impl Map {
 
+	/// Parses a map like `Map::from`, but fails on the first character that isn't a known frequency, `.`, or whitespace
+	/// rather than skipping it.
+	pub fn parse_strict(value: &str) -> Result<Self, MapParseError> {
+		if value.lines().next().is_none() { return Err(MapParseError::Empty) }
+		for (line, text) in value.lines().enumerate() {
+			for (column, character) in text.chars().enumerate() {
+				if character == '.' || character.is_whitespace() || AntennaVariant::try_from(character).is_ok() { continue }
+				return Err(MapParseError::UnknownCharacter { line: line + 1, column: column + 1, character });
+			}
+		}
+		Ok(Map::from(value))
+	}
+
+	/// An equivalent map to share in place of this one. Frequencies are shuffled between each other, and the map is
+	/// mirrored and rotated, none of which change where antinodes are relative to their antennas, so both parts keep
+	/// their answers. The `seed` picks the shuffle and orientation.
+	pub fn anonymized(&self, seed: u64) -> Map {
+		let mut rng = Xorshift::new(seed);
+
+		let variants = AntennaVariant::get_char_map().into_values().sorted().collect_vec();
+		let mut shuffled = variants.clone();
+		for idx in (1..shuffled.len()).rev() { shuffled.swap(idx, rng.below(idx as u64 + 1) as usize); }
+		let frequencies: HashMap<_, _> = variants.into_iter().zip(shuffled).collect();
+
+		let (width, height) = (self.bounds.bottom_right.x + 1, self.bounds.bottom_right.y + 1);
+		let orientation = rng.next_u64();
+		let (transpose, flip_x, flip_y) = (orientation & 1 != 0, orientation & 2 != 0, orientation & 4 != 0);
+		let (width, height) = if transpose { (height, width) } else { (width, height) };
+		let orient = |pos: &Vector2<i32>| {
+			let pos = if transpose { Vector2::new(pos.y, pos.x) } else { *pos };
+			Vector2::new(if flip_x { width - 1 - pos.x } else { pos.x }, if flip_y { height - 1 - pos.y } else { pos.y })
+		};
+		Map {
+			bounds: BoundingBox { top_left: Vector2::new(0, 0), bottom_right: Vector2::new(width - 1, height - 1) },
+			antennas: self.antennas.iter().map(|(variant, positions)| (frequencies[variant], positions.iter().map(orient).collect())).collect(),
+		}
+	}
+
+	/// Finds which antennas were added, removed, or moved between this map and another.
+	pub fn diff(&self, other: &Map) -> MapDiff {
+		let size = |map: &Map| map.bounds.bottom_right - map.bounds.top_left + Vector2::new(1, 1);
+		let mut diff = MapDiff {
+			added: Vec::new(), removed: Vec::new(), moved: Vec::new(), unchanged: Vec::new(), sizes: (size(self), size(other)),
+		};
+		let frequencies = self.antennas.keys().chain(other.antennas.keys()).copied().collect::<BTreeSet<_>>();
+		for frequency in frequencies {
+			let positions = |map: &Map| -> BTreeSet<(i32, i32)> {
+				map.antennas.get(&frequency).into_iter().flatten().map(|pos| (pos.y, pos.x)).collect()
+			};
+			let (old, new) = (positions(self), positions(other));
+			diff.unchanged.extend(old.intersection(&new).map(|&(y, x)| (frequency, Vector2::new(x, y))));
+			let mut removed = old.difference(&new).copied().collect_vec();
+			let mut added = new.difference(&old).copied().collect_vec();
+
+			// Pair the closest removed and added antennas as moves until either runs out
+			let pairs = removed.iter().cartesian_product(&added)
+				.map(|(&from, &to)| ((from.0 - to.0).abs() + (from.1 - to.1).abs(), from, to))
+				.sorted();
+			let mut moved = Vec::new();
+			for (_, from, to) in pairs {
+				if !removed.contains(&from) || !added.contains(&to) { continue }
+				removed.retain(|&pos| pos != from);
+				added.retain(|&pos| pos != to);
+				moved.push((from, to));
+			}
+			moved.sort();
+
+			let antenna = |(y, x): (i32, i32)| (frequency, Vector2::new(x, y));
+			diff.added.extend(added.into_iter().map(antenna));
+			diff.removed.extend(removed.into_iter().map(antenna));
+			diff.moved.extend(moved.into_iter().map(|((fy, fx), (ty, tx))| (frequency, Vector2::new(fx, fy), Vector2::new(tx, ty))));
+		}
+		diff
+	}
+
 	/// Converts the map to a displayable string
 	fn to_string(&self, antinodes: Option<&HashMap<AntennaVariant, Vec<Vector2<i32>>>>) -> String {
 		let mut lines: Vec<Vec<char>> = vec![vec!['.'; self.bounds.bottom_right.x as usize + 1]; self.bounds.bottom_right.y as usize + 1];
@@ -141,44 +356,135 @@ impl Map {
 			.join("\n")
 	}
 
+	/// Gets statistics on the antinodes of every frequency placed with the given mode, sorted by frequency.
+	pub fn antinode_report(&self, mode: &AntinodeMode) -> Vec<FrequencyReport> {
+		let antinodes = self.get_antinodes(mode);
+		let unique: BTreeMap<_, HashSet<_>> = antinodes.iter()
+			.map(|(variant, positions)| (*variant, positions.iter().copied().collect()))
+			.collect();
+		unique.iter().map(|(&frequency, positions)| {
+			let overlaps = unique.iter()
+				.filter(|&(&other, _)| other != frequency)
+				.map(|(&other, other_positions)| (other, positions.intersection(other_positions).count()))
+				.filter(|&(_, count)| count > 0)
+				.collect();
+			FrequencyReport {
+				frequency,
+				antennas: self.antennas[&frequency].len(),
+				antinodes: antinodes[&frequency].len(),
+				unique_antinodes: positions.iter().copied().sorted_by_key(|pos| (pos.y, pos.x)).collect(),
+				overlaps,
+			}
+		}).collect()
+	}
+
 	/// Gets all antinodes created by the antennas in the map. For each line from two antennas of the same frequency,
-	/// Each item in the range rep will be given its own antinode.
-	fn get_antinodes(&self, reps: Option<Range<usize>>) -> HashMap<AntennaVariant, Vec<Vector2<i32>>> {
-		let reps = if let Some(reps) = reps { reps } else {
-			0..cmp::max(self.bounds.bottom_right.x as usize, self.bounds.bottom_right.y as usize)
-		};
+	/// antinodes are placed according to the mode.
+	pub fn get_antinodes(&self, mode: &AntinodeMode) -> HashMap<AntennaVariant, Vec<Vector2<i32>>> {
 		self.antennas.iter().map(|(variant, positions)| {
 			let antinodes = positions.iter().permutations(2).flat_map(|antennas| {
 				let (&&from, &&to) = antennas.iter().collect_tuple().expect("Expected permutations of 2 antennas");
-				let step = to - from;
-				reps.clone().filter_map(move |idx| {
-					let antinode = to + step * idx as i32;
-					self.bounds.includes(antinode).then_some(antinode)
-				})
+				self.pair_antinodes(from, to, mode)
 			}).collect_vec();
 			(*variant, antinodes)
 		}).collect()
 	}
+
+	/// Gets all antinodes created by pairs of antennas for which `pairs(from, to)` is true, rather than only antennas
+	/// of the same frequency. For each pair, antinodes are placed past `to` according to the mode.
+	pub fn get_antinodes_paired<F>(&self, mode: &AntinodeMode, pairs: F) -> Vec<Vector2<i32>>
+	where F: Fn(AntennaVariant, AntennaVariant) -> bool {
+		let antennas = self.antennas.iter()
+			.flat_map(|(&variant, positions)| positions.iter().map(move |&pos| (variant, pos)))
+			.collect_vec();
+		antennas.iter().permutations(2).flat_map(|antennas| {
+			let (&&(from_variant, from), &&(to_variant, to)) = antennas.iter().collect_tuple().expect("Expected permutations of 2 antennas");
+			if pairs(from_variant, to_variant) { self.pair_antinodes(from, to, mode) } else { Vec::new() }
+		}).collect()
+	}
+
+	/// Gets the in bounds antinodes created by the antennas at `from` and `to`.
+	fn pair_antinodes(&self, from: Vector2<i32>, to: Vector2<i32>, mode: &AntinodeMode) -> Vec<Vector2<i32>> {
+		let step = to - from;
+		let in_bounds = |antinode: &Vector2<i32>| self.bounds.includes(*antinode);
+		match mode {
+			AntinodeMode::Single => Some(to + step).filter(in_bounds).into_iter().collect(),
+			AntinodeMode::Harmonics(reps) => reps.clone().map(|idx| to + step * idx as i32).filter(in_bounds).collect(),
+			AntinodeMode::Unbounded => (0..).map(|idx| to + step * idx).take_while(in_bounds).collect(),
+			AntinodeMode::Steps(steps) => steps.iter()
+				.filter_map(|multiplier| Some(to + multiplier.apply(step)?))
+				.filter(in_bounds)
+				.collect(),
+		}
+	}
+}
+
+/// A structural problem with a map, found by `validate`. Lines and columns count from 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputProblem {
+	/// The input has no lines.
+	Empty,
+	/// A line isn't as long as the first, so the map isn't rectangular.
+	RaggedLine { line: usize, length: usize, expected: usize },
+	/// A character which isn't a frequency, `.`, or whitespace.
+	UnknownCharacter { line: usize, column: usize, character: char },
+}
+
+impl Display for InputProblem {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		match self {
+			InputProblem::Empty => write!(f, "The map is empty"),
+			InputProblem::RaggedLine { line, length, expected } => {
+				write!(f, "Line {line} is {length} long, expected {expected} like the first line")
+			},
+			InputProblem::UnknownCharacter { line, column, character } => {
+				write!(f, "Unknown character {character:?} at line {line}, column {column}")
+			},
+		}
+	}
+}
+
+/// Checks a map is a rectangular grid of known characters without solving it, reporting every problem rather than
+/// stopping at the first.
+pub fn validate(input: &str) -> Vec<InputProblem> {
+	let lines = input.lines().collect::<Vec<_>>();
+	let Some(first) = lines.first() else { return vec![InputProblem::Empty] };
+	let expected = first.chars().count();
+	let mut problems = Vec::new();
+	for (idx, text) in lines.iter().enumerate() {
+		let length = text.chars().count();
+		if length != expected { problems.push(InputProblem::RaggedLine { line: idx + 1, length, expected }); }
+		for (column, character) in text.chars().enumerate() {
+			if character == '.' || character.is_whitespace() || AntennaVariant::try_from(character).is_ok() { continue }
+			problems.push(InputProblem::UnknownCharacter { line: idx + 1, column: column + 1, character });
+		}
+	}
+	problems
 }
 
 /// Finds the number of unique positions antinodes are present in when only 1 antinode is created per pair of antennas.
-pub fn part1_solution(input: &str) -> usize {
-	Map::from(input)
-		.get_antinodes(Some(1..2))
+pub fn part1_solution(input: &str) -> Result<usize, MapParseError> {
+	Ok(Map::parse_strict(input)?
+		.get_antinodes(&AntinodeMode::Single)
 		.drain()
 		.flat_map(|(_variant, positions)| positions)
 		.unique()
-		.count()
+		.count())
 }
 
 /// Finds the number of unique positions antinodes are present in when any amount of antinodes are created per pair of antennas.
-pub fn part2_solution(input: &str) -> usize {
-	Map::from(input)
-		.get_antinodes(None)
+pub fn part2_solution(input: &str) -> Result<usize, MapParseError> {
+	Ok(Map::parse_strict(input)?
+		.get_antinodes(&AntinodeMode::Unbounded)
 		.drain()
 		.flat_map(|(_variant, positions)| positions)
 		.unique()
-		.count()
+		.count())
+}
+
+/// Rewrites a map into an equivalent one to share in place of the real input, see `Map::anonymized`.
+pub fn anonymize(input: &str, seed: u64) -> Result<String, MapParseError> {
+	Ok(String::from(&Map::parse_strict(input)?.anonymized(seed)))
 }
 
 /// Entry point
@@ -195,11 +501,210 @@ pub fn main() {
 .........A..
 ............
 ............";
-	let input = include_str!("day8.txt");
+	// Solve another input instead of the bundled one with `--file <path>`
+	let args = std::env::args().collect::<Vec<_>>();
+	let file = args.iter().skip_while(|&arg| arg != "--file").nth(1)
+		.map(|path| std::fs::read_to_string(path).expect("Failed to read input file"));
+	let input = file.as_deref().unwrap_or(include_str!("day8.txt"));
+
+	// Check a file for problems without solving it with `--validate <path>`
+	if let Some(path) = args.iter().skip_while(|&arg| arg != "--validate").nth(1) {
+		validate_file(path, validate);
+		return;
+	}
+
+	// Print a shareable version of a map file with `--anonymize <path>`, shuffled by `--seed <number>`, after checking
+	// both parts still have the same answers
+	if let Some(path) = args.iter().skip_while(|&arg| arg != "--anonymize").nth(1) {
+		let seed = args.iter().skip_while(|&arg| arg != "--seed").nth(1).map_or(0, |seed| seed.parse().expect("Invalid seed"));
+		let original = std::fs::read_to_string(path).expect("Failed to read map file");
+		let anonymized = match anonymize(&original, seed) {
+			Ok(anonymized) => anonymized,
+			Err(error) => {
+				eprintln!("Failed to parse {path}: {error}");
+				std::process::exit(1)
+			},
+		};
+		let mut preserved = true;
+		for (part, solution) in [(1, part1_solution as fn(&str) -> _), (2, part2_solution)] {
+			let (before, after) = (solution(&original), solution(&anonymized));
+			if before == after {
+				eprintln!("Part {part} answer preserved: {before:?}");
+			} else {
+				eprintln!("Part {part} changed answer from {before:?} to {after:?}");
+				preserved = false;
+			}
+		}
+		if !preserved { std::process::exit(1) }
+		println!("{anonymized}");
+		return;
+	}
+
+	// Show which antennas changed between two map files with `--diff <old> <new>`
+	if let Some(paths) = args.iter().skip_while(|&arg| arg != "--diff").nth(1).zip(args.iter().skip_while(|&arg| arg != "--diff").nth(2)) {
+		let read = |path| Map::parse_strict(&std::fs::read_to_string(path).expect("Failed to read map file")).expect("Failed to parse map");
+		let diff = read(paths.0).diff(&read(paths.1));
+		println!("{}\n{diff}", diff.overlay());
+		if diff.is_empty() { println!("The maps are the same"); }
+		return;
+	}
+
+	// Count the unique antinodes from any step multipliers, e.g. `--steps "1 -1/2"` for part 1 plus midpoints
+	if let Some(steps) = args.iter().skip_while(|&arg| arg != "--steps").nth(1) {
+		let steps = steps.split_whitespace()
+			.map(|step| StepMultiplier::try_from(step).unwrap_or_else(|_| panic!("Invalid step multiplier {step}")))
+			.collect_vec();
+		let map = Map::parse_strict(if args.iter().any(|arg| arg == "--example") { example } else { input }).expect("Failed to parse map");
+		println!("Unique antinodes: {}", map.get_antinodes(&AntinodeMode::Steps(steps)).into_values().flatten().unique().count());
+		return;
+	}
 
-	println!("Part 1 Solution on Example: {:#?}", part1_solution(example));
-	println!("Part 1 Solution on Input: {:#?}", part1_solution(input));
+	// Solve both parts pairing antennas of the same letter regardless of case
+	if args.iter().any(|arg| arg == "--ignore-case") {
+		let map = Map::parse_strict(if args.iter().any(|arg| arg == "--example") { example } else { input }).expect("Failed to parse map");
+		let same_letter = |a: AntennaVariant, b: AntennaVariant| char::from(a).eq_ignore_ascii_case(&char::from(b));
+		for (part, mode) in [(1, AntinodeMode::Single), (2, AntinodeMode::Unbounded)] {
+			println!("Part {part} ignoring case: {}", map.get_antinodes_paired(&mode, same_letter).into_iter().unique().count());
+		}
+		return;
+	}
+
+	// Print statistics on every frequency's antinodes, for both parts
+	if args.iter().any(|arg| arg == "--report") {
+		let map = Map::parse_strict(if args.iter().any(|arg| arg == "--example") { example } else { input }).expect("Failed to parse map");
+		for (part, mode) in [(1, AntinodeMode::Single), (2, AntinodeMode::Unbounded)] {
+			println!("Part {part}:");
+			for report in map.antinode_report(&mode) {
+				println!(
+					"{}: {} antennas, {} antinodes, {} unique, overlaps {:?}",
+					report.frequency, report.antennas, report.antinodes, report.unique_antinodes.len(),
+					report.overlaps.iter().map(|(frequency, count)| format!("{frequency}={count}")).collect_vec(),
+				);
+			}
+		}
+		return;
+	}
+
+	println!("Part 1 Solution on Example: {}", report(part1_solution(example), "solving day 8 part 1"));
+	println!("Part 1 Solution on Input: {}", report(part1_solution(input), "solving day 8 part 1"));
+
+	println!("Part 2 Solution on Example: {}", report(part2_solution(example), "solving day 8 part 2"));
+	println!("Part 2 Solution on Input: {}", report(part2_solution(input), "solving day 8 part 2"));
+}
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+
+	/// Tests strict parsing reports where unknown characters are
+	#[test]
+	fn test_parse_strict() {
+		assert!(Map::parse_strict("..a.\n.A0.").is_ok());
+		assert_eq!(Map::parse_strict("").unwrap_err(), MapParseError::Empty);
+		assert_eq!(
+			Map::parse_strict("....\n..a.\n.#..").unwrap_err(),
+			MapParseError::UnknownCharacter { line: 3, column: 2, character: '#' },
+		);
+		assert_eq!(part1_solution("..\n.?").unwrap_err().to_string(), "Unknown character '?' at line 2, column 2");
+	}
+
+	/// Tests the antinode report of the example map
+	#[test]
+	fn test_antinode_report() {
+		let map = Map::parse_strict("..........\n..........\n..........\n....a.....\n..........\n.....a....\n..........\n..........").unwrap();
+		let report = map.antinode_report(&AntinodeMode::Single);
+		assert_eq!(report.len(), 1);
+		assert_eq!(report[0].frequency, AntennaVariant::VariantLowerA);
+		assert_eq!((report[0].antennas, report[0].antinodes), (2, 2));
+		assert_eq!(report[0].unique_antinodes, [Vector2::new(3, 1), Vector2::new(6, 7)]);
+		assert!(report[0].overlaps.is_empty());
+
+		// Both frequencies put an antinode at (0, 0)
+		let map = Map::parse_strict("....\nAa..\nA.a.\n....").unwrap();
+		let report = map.antinode_report(&AntinodeMode::Single);
+		let overlaps = report.iter().map(|report| (report.frequency, report.overlaps.clone())).collect_vec();
+		assert_eq!(overlaps, [
+			(AntennaVariant::VariantLowerA, BTreeMap::from([(AntennaVariant::VariantUpperA, 1)])),
+			(AntennaVariant::VariantUpperA, BTreeMap::from([(AntennaVariant::VariantLowerA, 1)])),
+		]);
+	}
+
+	/// Tests midpoint antinodes only exist between antennas an even distance apart
+	#[test]
+	fn test_midpoint_steps() {
+		let midpoint = StepMultiplier::try_from("-1/2").unwrap();
+		assert_eq!(midpoint, StepMultiplier { numerator: -1, denominator: 2 });
+		assert_eq!(StepMultiplier::try_from("1/0"), Err(()));
+
+		let map = Map::parse_strict("a....\n.....\n..b..\n....b\na....").unwrap();
+		let antinodes = map.get_antinodes(&AntinodeMode::Steps(vec![midpoint]));
+		assert_eq!(antinodes[&AntennaVariant::VariantLowerA], [Vector2::new(0, 2), Vector2::new(0, 2)]);
+		assert!(antinodes[&AntennaVariant::VariantLowerB].is_empty());
+	}
+
+	/// Tests antennas can be paired across frequencies
+	#[test]
+	fn test_antinodes_paired() {
+		let map = Map::parse_strict(".....\n.a...\n..A..\n.....\n.....").unwrap();
+		assert!(map.get_antinodes_paired(&AntinodeMode::Single, |a, b| a == b).is_empty());
+		let same_letter = |a: AntennaVariant, b: AntennaVariant| char::from(a).eq_ignore_ascii_case(&char::from(b));
+		let antinodes = map.get_antinodes_paired(&AntinodeMode::Single, same_letter).into_iter().sorted_by_key(|pos| (pos.y, pos.x)).collect_vec();
+		assert_eq!(antinodes, [Vector2::new(0, 0), Vector2::new(3, 3)]);
+	}
+
+	/// Tests each antinode mode on a single pair of antennas
+	#[test]
+	fn test_antinode_modes() {
+		let map = Map::parse_strict("a.........\n.a........\n..........\n..........\n..........").unwrap();
+		let antinodes = |mode| map.get_antinodes(&mode)[&AntennaVariant::VariantLowerA].iter().sorted_by_key(|pos| (pos.y, pos.x)).copied().collect_vec();
+		assert_eq!(antinodes(AntinodeMode::Single), [Vector2::new(2, 2)]);
+		assert_eq!(antinodes(AntinodeMode::Harmonics(1..3)), [Vector2::new(2, 2), Vector2::new(3, 3)]);
+		assert_eq!(antinodes(AntinodeMode::Unbounded), (0..5).map(|idx| Vector2::new(idx, idx)).collect_vec());
+		assert_eq!(antinodes(AntinodeMode::Steps(vec![StepMultiplier::whole(-2), StepMultiplier::whole(3)])), [Vector2::new(2, 2), Vector2::new(4, 4)]);
+	}
+
+	/// Tests anonymized maps keep their answers and how many antennas share each frequency
+	#[test]
+	fn test_anonymize() {
+		let example = "............\n........0...\n.....0......\n.......0....\n....0.......\n......A.....\n............\n............\n........A...\n.........A..\n............\n............";
+		let group_sizes = |input: &str| Map::parse_strict(input).unwrap().antennas.values().map(Vec::len).sorted().collect_vec();
+		let anonymized = (0..8).map(|seed| anonymize(example, seed).unwrap()).collect_vec();
+		for map in &anonymized {
+			assert_eq!(part1_solution(map), Ok(14));
+			assert_eq!(part2_solution(map), Ok(34));
+			assert_eq!(group_sizes(map), group_sizes(example));
+		}
+		assert!(anonymized.iter().any(|map| map != example));
+		assert_eq!(anonymize(example, 3), anonymize(example, 3));
+	}
+
+	/// Tests validation reports every problem with a map at once
+	#[test]
+	fn test_validate() {
+		assert!(validate("..a.\n.A0.").is_empty());
+		assert_eq!(validate(""), [InputProblem::Empty]);
+		assert_eq!(validate("..a.\n.#\n..?."), [
+			InputProblem::RaggedLine { line: 2, length: 2, expected: 4 },
+			InputProblem::UnknownCharacter { line: 2, column: 2, character: '#' },
+			InputProblem::UnknownCharacter { line: 3, column: 3, character: '?' },
+		]);
+	}
+
+	/// Tests diffs pair the closest changed antennas of a frequency as moves
+	#[test]
+	fn test_diff() {
+		let map = Map::parse_strict("a...\n..b.\n....\na...").unwrap();
+		assert!(map.diff(&map).is_empty());
+
+		let diff = map.diff(&Map::parse_strict(".a..\n....\n.c..\na...\n....").unwrap());
+		assert_eq!(diff.added, [(AntennaVariant::VariantLowerC, Vector2::new(1, 2))]);
+		assert_eq!(diff.removed, [(AntennaVariant::VariantLowerB, Vector2::new(2, 1))]);
+		assert_eq!(diff.moved, [(AntennaVariant::VariantLowerA, Vector2::new(0, 0), Vector2::new(1, 0))]);
+		assert_eq!(diff.unchanged, [(AntennaVariant::VariantLowerA, Vector2::new(0, 3))]);
+		assert_eq!(diff.sizes, (Vector2::new(4, 4), Vector2::new(4, 5)));
+		assert_eq!(diff.overlay(), "o*..\n..-.\n.+..\na...\n....");
+		assert_eq!(diff.to_string().lines().next(), Some("Resized from 4x4 to 4x5"));
+	}
 
-	println!("Part 2 Solution on Example: {:#?}", part2_solution(example));
-	println!("Part 2 Solution on Input: {:#?}", part2_solution(input));
 }
@@ -1,11 +1,14 @@
-use std::{cmp, fmt};
+use std::{cmp, fmt, fs::File, io::{self, BufWriter, Write}, thread, time::Duration};
 use std::convert::TryFrom;
 use std::fmt::Display;
 
+use common::{debug::debug, report::report};
+
 /// Represents a block of memory on a disk.
 /// A block has an ID (which groups blocks together), size, and offset.
 #[derive(Debug, Clone, PartialEq)]
-struct Block {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Block {
     /// The ID of the block. Blocks with the same ID are grouped together.
     id: usize,
     /// The size of this block in memory.
@@ -21,24 +24,55 @@ impl Display for Block {
 }
 
 impl Block {
-    /// Gets the checksum of the block where each bit's position is multipled by its ID and summed.
-    fn get_checksum(&self) -> usize {
-        (self.offset..self.offset + self.size).map(|pos| pos * self.id).sum()
+    /// Creates a block of memory with the given ID, size, and offset.
+    pub fn new(id: usize, size: usize, offset: usize) -> Self {
+        Self { id, size, offset }
+    }
+
+    /// The ID of the file this block belongs to.
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
+    /// The size of this block in memory.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// The offset of this block in memory.
+    pub fn offset(&self) -> usize {
+        self.offset
     }
 }
 
+/// Ways of scoring a disk, each combining a value for every position holding a file.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Checksum {
+    /// Each position multiplied by the ID of the file there, summed. This is the puzzle's checksum.
+    #[default]
+    WeightedByPosition,
+    /// The ID of the file at each position, summed.
+    IdSum,
+    /// Each position multiplied by the ID of the file there, combined with XOR.
+    Xor,
+}
+
 /// Represents a disk containing a collection of memory blocks.
 /// A disk manages multiple blocks of memory.
-#[derive(Debug, Clone)]
-struct Disk {
-    /// A vector containing all the blocks in this disk.
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Disk {
+    /// A vector containing all the blocks in this disk, sorted by offset.
     blocks: Vec<Block>,
+    /// The total size of the disk, including any gap after the last block.
+    len: usize,
+    /// The ID the next file pushed onto the disk is given.
+    next_id: usize,
 }
 
 impl Display for Disk {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let back = if let Some(back) = self.blocks.last() { back } else { return Ok(()) };
-        let mut disk = vec![String::from("."); back.offset + back.size];
+        let mut disk = vec![String::from("."); self.len];
         for block in &self.blocks {
             disk[block.offset..block.offset + block.size].fill(block.id.to_string());
         }
@@ -46,20 +80,90 @@ impl Display for Disk {
     }
 }
 
+impl Disk {
+    /// Creates an empty disk.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a disk from alternating file and gap lengths, starting with a file. Files are given increasing IDs from 0.
+    pub fn from_lengths(lengths: &[usize]) -> Self {
+        let mut disk = Self::new();
+        for (idx, &length) in lengths.iter().enumerate() {
+            if idx % 2 == 0 { disk.push_file(length); } else { disk.push_gap(length); }
+        }
+        disk
+    }
+
+    /// Adds a file of the given size to the end of the disk, with an ID one higher than any file already on it.
+    pub fn push_file(&mut self, size: usize) -> &mut Self {
+        self.blocks.push(Block { id: self.next_id, size, offset: self.len });
+        self.next_id += 1;
+        self.len += size;
+        self
+    }
+
+    /// Adds a gap of free memory of the given size to the end of the disk.
+    pub fn push_gap(&mut self, size: usize) -> &mut Self {
+        self.len += size;
+        self
+    }
+
+    /// All blocks of memory on the disk, sorted by offset.
+    pub fn blocks(&self) -> &[Block] {
+        &self.blocks
+    }
+
+    /// The total size of the disk, including free memory.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether the disk has no memory at all.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Iterates over the blocks of every file, sorted by offset. A fragmented file gives one block per fragment.
+    pub fn files(&self) -> impl Iterator<Item = &Block> {
+        self.blocks.iter().filter(|block| block.size > 0)
+    }
+
+    /// Iterates over the gaps of free memory on the disk as `(offset, size)`, sorted by offset.
+    pub fn gaps(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let ends = self.blocks.iter().map(|block| block.offset).chain([self.len]);
+        let starts = [0].into_iter().chain(self.blocks.iter().map(|block| block.offset + block.size));
+        starts.zip(ends).filter(|&(start, end)| end > start).map(|(start, end)| (start, end - start))
+    }
+
+    /// Renders the disk as a strip of colored blocks, one character per unit of memory, with gaps shown as `.`.
+    /// Each file ID is given its own terminal color so neighbouring files can be told apart.
+    fn to_colored_string(&self) -> String {
+        let mut strip = String::new();
+        let mut end = 0;
+        for block in &self.blocks {
+            strip.push_str(&".".repeat(block.offset.saturating_sub(end)));
+            strip.push_str(&format!("\x1b[38;5;{}m{}\x1b[0m", 16 + (block.id * 37) % 216, "█".repeat(block.size)));
+            end = block.offset + block.size;
+        }
+        strip
+    }
+}
+
 /// Custom error type for parsing a Disk from a string.
 #[derive(Debug)]
-enum DiskParseError {
+pub enum DiskParseError {
     /// Error when a character in the input string is not a valid digit.
     InvalidCharacter(char, usize),
-    /// Error when a chunk does not have exactly two elements.
-    InvalidChunk,
+    /// Error when a delimited length isn't a valid number, with the index of the length.
+    InvalidLength(String, usize),
 }
 
 impl fmt::Display for DiskParseError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             DiskParseError::InvalidCharacter(c, idx) => write!(f, "Invalid character '{}' at position {}.", c, idx),
-            DiskParseError::InvalidChunk => write!(f, "Input string contains an invalid chunk. Each chunk must consist of exactly two characters."),
+            DiskParseError::InvalidLength(length, idx) => write!(f, "Invalid length '{}' at index {}.", length, idx),
         }
     }
 }
@@ -70,24 +174,129 @@ impl TryFrom<&str> for Disk {
     type Error = DiskParseError;
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
-        let blocks = value.chars().collect::<Vec<_>>()
-            .chunks(2).enumerate().map(|(id, chunk)| {
-                let (size_char, gaps_char) = match chunk {
-                    [size_char, gaps_char] => (size_char, gaps_char),
-                    [size_char] => (size_char, &'0'),
-                    _ => return Err(DiskParseError::InvalidChunk),
-                };
-
-                let size = size_char.to_digit(10).ok_or(DiskParseError::InvalidCharacter(*size_char, id * 2))? as usize;
-                let gap = gaps_char.to_digit(10).ok_or(DiskParseError::InvalidCharacter(*gaps_char, id * 2 + 1))? as usize;
-                Ok((id, size, gap))
-            }).collect::<Result<Vec<_>, _>>()?
-            .iter().scan(0usize, |offset, &(id, size, gap)| {
-                let block = Some(Block { id, size, offset: *offset });
-                *offset += size + gap;
-                block
-            }).collect();
-        Ok(Self { blocks })
+        let lengths = value.chars().enumerate()
+            .map(|(idx, c)| c.to_digit(10).map(|length| length as usize).ok_or(DiskParseError::InvalidCharacter(c, idx)))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self::from_lengths(&lengths))
+    }
+}
+
+impl Disk {
+    /// Parses a disk from alternating file and gap lengths separated by commas and/or whitespace, e.g. `12,3,400,7`.
+    /// Unlike the compact format, lengths aren't limited to a single digit.
+    pub fn parse_lengths(value: &str) -> Result<Self, DiskParseError> {
+        let lengths = value.split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|length| !length.is_empty())
+            .enumerate()
+            .map(|(idx, length)| length.parse().map_err(|_| DiskParseError::InvalidLength(length.to_string(), idx)))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self::from_lengths(&lengths))
+    }
+}
+
+/// A single move of (part of) a file made while compacting a disk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Move {
+    /// The ID of the file being moved.
+    pub id: usize,
+    /// The offset the moved memory started at.
+    pub from: usize,
+    /// The offset the moved memory was placed at.
+    pub to: usize,
+    /// The amount of memory moved.
+    pub size: usize,
+}
+
+/// An in-progress compaction of a disk, moving one file per step so compaction can be inspected part way through.
+#[derive(Debug, Clone)]
+struct Compaction {
+    /// The disk in its current, partially compacted state.
+    disk: Disk,
+    /// Files which haven't been moved yet, the next file to move is last.
+    pending: Vec<Block>,
+    /// Whether files may be split up to fill gaps, or only moved whole.
+    fragment: bool,
+    /// The moves made by the most recent step.
+    last_moves: Vec<Move>,
+}
+
+impl Compaction {
+    /// Starts compacting a disk. When `fragment` is set files may be split across gaps, otherwise only full files are moved.
+    fn new(disk: &Disk, fragment: bool) -> Self {
+        // Files are moved in order of decreasing ID, each only once
+        let mut pending = disk.blocks.clone();
+        pending.sort_by_key(|block| block.id);
+        Self { disk: disk.clone(), pending, fragment, last_moves: Vec::new() }
+    }
+
+    /// The ID of the file which will be moved by the next step, None once compaction is complete.
+    fn next_file(&self) -> Option<usize> {
+        self.pending.last().map(|block| block.id)
+    }
+
+    /// Moves the next pending file. Returns false when there were no files left to move.
+    fn step(&mut self) -> bool {
+        let Some(block) = self.pending.pop() else { return false };
+        self.last_moves.clear();
+        if self.fragment { self.move_fragmented(block) } else { self.move_whole(block) }
+        true
+    }
+
+    /// Moves a file as far left as possible, splitting it across every gap before it.
+    fn move_fragmented(&mut self, mut block: Block) {
+        let blocks = &mut self.disk.blocks;
+        let removal_block = block.clone();
+        while block.size > 0 {
+            // Gaps as (index of the block after the gap, offset, size), starting from the beginning of the disk
+            let Some((idx, offset, gap)) = blocks.iter().enumerate()
+                .scan(0, |end, (idx, next)| {
+                    let gap = (idx, *end, next.offset - *end);
+                    *end = next.offset + next.size;
+                    Some(gap)
+                })
+                .take_while(|&(_, offset, _)| offset < block.offset)
+                .find(|&(_, _, size)| size > 0) else { break; };
+            let size = cmp::min(gap, block.size);
+            // Memory is taken from the end of the file, the remainder stays where the file started
+            block.size -= size;
+            self.last_moves.push(Move { id: block.id, from: block.offset + block.size, to: offset, size });
+            blocks.insert(idx, Block { id: block.id, size, offset })
+        }
+        let existing_idx = blocks.iter().position(|x| *x == removal_block).unwrap();
+        if block.size == 0 { blocks.remove(existing_idx); }
+        else { blocks[existing_idx].size = block.size; }
+    }
+
+    /// Moves a file into the leftmost gap to its left which can hold all of it, leaving it in place if there is none.
+    fn move_whole(&mut self, block: Block) {
+        let blocks = &mut self.disk.blocks;
+        // Gaps as (index of the block after the gap, offset, size), files are never moved right
+        let Some((idx, offset, _)) = blocks.iter().enumerate()
+            .scan(0, |end, (idx, next)| {
+                let gap = (idx, *end, next.offset - *end);
+                *end = next.offset + next.size;
+                Some(gap)
+            })
+            .take_while(|&(_, offset, _)| offset < block.offset)
+            .find(|&(_, _, size)| size >= block.size) else { return };
+        let removal_idx = blocks.iter().position(|x| *x == block).unwrap();
+        let mut block = blocks.remove(removal_idx);
+        self.last_moves.push(Move { id: block.id, from: block.offset, to: offset, size: block.size });
+        block.offset = offset;
+        blocks.insert(idx, block);
+    }
+
+    /// Moves all remaining files and returns the compacted disk.
+    fn finish(mut self) -> Disk {
+        while self.step() {}
+        self.disk
+    }
+
+    /// Moves all remaining files, returning the compacted disk and every move made in order.
+    fn finish_logged(mut self) -> (Disk, Vec<Move>) {
+        let mut moves = Vec::new();
+        while self.step() { moves.append(&mut self.last_moves); }
+        (self.disk, moves)
     }
 }
 
@@ -98,24 +307,7 @@ impl Disk {
     /// contents to the left, removing all gaps. The resulting disk has no gaps,
     /// and the memory layout is continuous.
     pub fn condense(&self) -> Disk {
-        let mut blocks = self.blocks.clone();
-        for mut block in blocks.clone().into_iter().rev() {
-            let removal_block = block.clone();
-            while block.size > 0 {
-                let Some((idx, offset, size)) = blocks.windows(2).enumerate().find_map(|(idx, window)| {
-                    let [current, next] = window else { return None };
-                    if current.offset + current.size >= block.offset { return None };
-                    let gap = next.offset - (current.offset + current.size);
-                    (gap > 0).then_some((idx + 1, current.offset + current.size, cmp::min(gap, block.size)))
-                }) else { break; };
-                block.size -= size;
-                blocks.insert(idx, Block { id: block.id, size, offset })
-            }
-            let existing_idx = blocks.iter().position(|x| *x == removal_block).unwrap();
-            if block.size == 0 { blocks.remove(existing_idx); }
-            else { blocks[existing_idx].size = block.size; }
-        }
-        Self { blocks }
+        Compaction::new(self, true).finish()
     }
 
     /// Condenses only full blocks at a time. 
@@ -123,25 +315,39 @@ impl Disk {
     /// This method moves full blocks to fill gaps without fragmenting the blocks themselves.
     /// If a block cannot be moved in its entirety due to insufficient space, it will remain in its current position.
     pub fn condense_blocks(&self) -> Disk {
-        let mut blocks = self.blocks.clone();
-        for block in blocks.clone().into_iter().rev() {
-            let Some((idx, offset)) = blocks.windows(2).enumerate().find_map(|(idx, window)| {
-                let [current, next] = window else { return None };
-                if current.offset + current.size >= block.offset { return None };
-                (next.offset - (current.offset + current.size) >= block.size)
-                    .then_some((idx + 1, current.offset + current.size))
-            }) else { continue };
-            let removal_idx = blocks.iter().position(|x| *x == block).unwrap();
-            let mut block = blocks.remove(removal_idx);
-            block.offset = offset;
-            blocks.insert(idx, block);
-        }
-        Self { blocks }
+        Compaction::new(self, false).finish()
+    }
+
+    /// Like `condense`, but also returns every move made to reach the condensed disk, in order.
+    pub fn condense_logged(&self) -> (Disk, Vec<Move>) {
+        Compaction::new(self, true).finish_logged()
+    }
+
+    /// Like `condense_blocks`, but also returns every move made to reach the condensed disk, in order.
+    pub fn condense_blocks_logged(&self) -> (Disk, Vec<Move>) {
+        Compaction::new(self, false).finish_logged()
     }
 
     /// Gets the checksum of the disk where each block's position is multipled by its ID and summed.
     fn get_checksum(&self) -> usize {
-        self.blocks.iter().map(|block| block.get_checksum()).sum()
+        self.get_checksum_with(Checksum::WeightedByPosition)
+    }
+
+    /// Gets the checksum of the disk with the given scoring scheme.
+    pub fn get_checksum_with(&self, checksum: Checksum) -> usize {
+        match checksum {
+            Checksum::WeightedByPosition => self.fold_positions(0, |acc, pos, id| acc + pos * id),
+            Checksum::IdSum => self.fold_positions(0, |acc, _pos, id| acc + id),
+            Checksum::Xor => self.fold_positions(0, |acc, pos, id| acc ^ (pos * id)),
+        }
+    }
+
+    /// Folds over every position holding a file in order of position, calling `f(acc, position, id)`. Use this for
+    /// checksums which `Checksum` doesn't cover.
+    pub fn fold_positions<T>(&self, init: T, mut f: impl FnMut(T, usize, usize) -> T) -> T {
+        self.blocks.iter()
+            .flat_map(|block| (block.offset..block.offset + block.size).map(|pos| (pos, block.id)))
+            .fold(init, |acc, (pos, id)| f(acc, pos, id))
     }
 }
 
@@ -156,14 +362,187 @@ fn part2_solution(input: &str) -> Result<usize, DiskParseError> {
 }
 
 
+/// Runs a compaction to completion, writing a JSON line for every move made.
+fn write_trace(compaction: Compaction, out: &mut impl Write) -> io::Result<()> {
+    let (_disk, moves) = compaction.finish_logged();
+    for Move { id, from, to, size } in moves {
+        writeln!(out, "{}", serde_json::json!({ "id": id, "from": from, "to": to, "size": size }))?;
+    }
+    Ok(())
+}
+
+/// Animates a compaction in the terminal, redrawing the disk each time a file moves.
+fn animate(mut compaction: Compaction, delay: Duration) {
+    loop {
+        let moving = compaction.next_file();
+        println!("\x1b[2J\x1b[H{}", compaction.disk.to_colored_string());
+        match moving {
+            Some(id) => println!("Next file to move: {id}"),
+            None => { println!("Compaction complete, checksum {}", compaction.disk.get_checksum()); break },
+        }
+        io::stdout().flush().expect("Failed to flush stdout");
+
+        // Skip over files which stay in place so every frame shows a move
+        let blocks = compaction.disk.blocks.clone();
+        while compaction.step() && compaction.disk.blocks == blocks {}
+        thread::sleep(delay);
+    }
+}
+
 /// Entry point
 pub fn main() {
     let example = "2333133121414131402";
     let input = include_str!("day9.txt");
 
-    println!("Part 1 Solution on Example: {:#?}", part1_solution(example));
-	println!("Part 1 Solution on Input: {:#?}", part1_solution(input));
+    // Solve a disk from a file, in either the compact or the delimited format, e.g. `12,3,400,7` or `12 3 400 7`
+    let args = std::env::args().collect::<Vec<_>>();
+    if let Some(path) = args.iter().skip_while(|&arg| arg != "--file").nth(1) {
+        let contents = std::fs::read_to_string(path).expect("Failed to read disk file");
+        let contents = contents.trim();
+        // Any delimiter, a comma or whitespace, means the lengths can have more than one digit
+        let delimited = contents.contains(|c: char| c == ',' || c.is_whitespace());
+        let disk = if delimited { Disk::parse_lengths(contents) } else { Disk::try_from(contents) }
+            .expect("Failed to parse disk");
+        println!("Part 1 Solution on File: {}", disk.condense().get_checksum());
+        println!("Part 2 Solution on File: {}", disk.condense_blocks().get_checksum());
+        return;
+    }
+
+    // Score both compacted layouts with another checksum, `weighted`, `id-sum`, or `xor`
+    if let Some(checksum) = args.iter().skip_while(|&arg| arg != "--checksum").nth(1) {
+        let checksum = match checksum.as_str() {
+            "weighted" => Checksum::WeightedByPosition,
+            "id-sum" => Checksum::IdSum,
+            "xor" => Checksum::Xor,
+            _ => panic!("Unknown checksum {checksum}, expected weighted, id-sum, or xor"),
+        };
+        let disk = Disk::try_from(if args.iter().any(|arg| arg == "--example") { example } else { input }).expect("Failed to parse disk");
+        println!("Part 1 {checksum:?}: {}", disk.condense().get_checksum_with(checksum));
+        println!("Part 2 {checksum:?}: {}", disk.condense_blocks().get_checksum_with(checksum));
+        return;
+    }
+
+    let source = if args.iter().any(|arg| arg == "--example") { example } else { input };
+    let fragment = !args.iter().any(|arg| arg == "--whole-files");
+    if let Some(path) = args.iter().skip_while(|&arg| arg != "--trace").nth(1) {
+        let disk = Disk::try_from(source).expect("Failed to parse disk");
+        let mut out = BufWriter::new(File::create(path).expect("Failed to create trace file"));
+        write_trace(Compaction::new(&disk, fragment), &mut out).and_then(|_| out.flush()).expect("Failed to write trace");
+        return;
+    }
+    if args.iter().any(|arg| arg == "--animate") {
+        let disk = Disk::try_from(source).expect("Failed to parse disk");
+        let delay = args.iter().skip_while(|&arg| arg != "--delay").nth(1)
+            .map(|ms| ms.parse().expect("Invalid --delay, expected milliseconds"))
+            .unwrap_or(200);
+        animate(Compaction::new(&disk, fragment), Duration::from_millis(delay));
+        return;
+    }
+    if args.iter().any(|arg| arg == "--debug") {
+        let disk = Disk::try_from(source).expect("Failed to parse disk");
+        debug(Compaction::new(&disk, fragment), Compaction::step, |compaction| {
+            let next = match compaction.next_file() {
+                Some(id) => format!("Next file to move: {id}"),
+                None => String::from("Compaction complete"),
+            };
+            format!("{}\n{next}, checksum {}", compaction.disk, compaction.disk.get_checksum())
+        });
+        return;
+    }
+
+    println!("Part 1 Solution on Example: {}", report(part1_solution(example), "solving day 9 part 1"));
+	println!("Part 1 Solution on Input: {}", report(part1_solution(input), "solving day 9 part 1"));
+
+	println!("Part 2 Solution on Example: {}", report(part2_solution(example), "solving day 9 part 2"));
+	println!("Part 2 Solution on Input: {}", report(part2_solution(input), "solving day 9 part 2"));
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    /// Tests building a disk matches parsing the same lengths
+    #[test]
+    fn test_disk_builder() {
+        let mut disk = Disk::new();
+        disk.push_file(12).push_gap(3).push_file(1).push_gap(0).push_file(2).push_gap(4);
+        assert_eq!(disk.len(), 22);
+        assert_eq!(disk.blocks(), [Block::new(0, 12, 0), Block::new(1, 1, 15), Block::new(2, 2, 16)]);
+        assert_eq!(disk.gaps().collect::<Vec<_>>(), [(12, 3), (18, 4)]);
+        assert_eq!(disk.files().map(Block::id).collect::<Vec<_>>(), [0, 1, 2]);
+
+        let parsed = Disk::try_from("12345").unwrap();
+        assert_eq!(parsed.blocks(), Disk::from_lengths(&[1, 2, 3, 4, 5]).blocks());
+        assert_eq!(parsed.to_string(), "0..111....22222");
+    }
+
+    /// Tests the delimited format accepts lengths the compact format can't express
+    #[test]
+    fn test_parse_lengths() {
+        let disk = Disk::parse_lengths("12,3,400,7\n1").unwrap();
+        assert_eq!(disk.blocks(), [Block::new(0, 12, 0), Block::new(1, 400, 15), Block::new(2, 1, 422)]);
+        assert_eq!(Disk::parse_lengths("2 3 3").unwrap().blocks(), Disk::try_from("233").unwrap().blocks());
+        assert!(matches!(Disk::parse_lengths("1,x"), Err(DiskParseError::InvalidLength(length, 1)) if length == "x"));
+    }
+
+    /// Tests each checksum on a small disk
+    #[test]
+    fn test_checksums() {
+        // 0..111
+        let disk = Disk::try_from("123").unwrap();
+        assert_eq!(disk.get_checksum_with(Checksum::WeightedByPosition), 3 + 4 + 5);
+        assert_eq!(disk.get_checksum_with(Checksum::IdSum), 3);
+        assert_eq!(disk.get_checksum_with(Checksum::Xor), 3 ^ 4 ^ 5);
+        assert_eq!(disk.fold_positions(Vec::new(), |mut acc, pos, _id| { acc.push(pos); acc }), [0, 3, 4, 5]);
+    }
+
+    /// Tests the move log replays to the condensed disk
+    #[test]
+    fn test_move_log() {
+        let disk = Disk::try_from("2333133121414131402").unwrap();
+        let (condensed, moves) = disk.condense_blocks_logged();
+        assert_eq!(condensed.to_string(), disk.condense_blocks().to_string());
+        assert_eq!(moves[0], Move { id: 9, from: 40, to: 2, size: 2 });
+        assert_eq!(moves.iter().map(|m| m.id).collect::<Vec<_>>(), [9, 7, 4, 2]);
+
+        let (condensed, moves) = disk.condense_logged();
+        assert_eq!(condensed.get_checksum(), 1928);
+        assert_eq!(moves[..2], [Move { id: 9, from: 40, to: 2, size: 2 }, Move { id: 8, from: 39, to: 4, size: 1 }]);
+        assert!(moves.iter().all(|m| m.to < m.from));
+
+        // The trace has a JSON line for each move
+        let mut out = Vec::new();
+        write_trace(Compaction::new(&disk, true), &mut out).unwrap();
+        let trace = String::from_utf8(out).unwrap();
+        assert_eq!(trace.lines().next(), Some(r#"{"from":40,"id":9,"size":2,"to":2}"#));
+        assert_eq!(trace.lines().count(), moves.len());
+    }
+
+    /// Tests whole file compaction on the official example, and that files only move left once in decreasing ID order
+    #[test]
+    fn test_whole_file_compaction() {
+        assert_eq!(part2_solution("2333133121414131402").unwrap(), 2858);
+        let disk = Disk::try_from("2333133121414131402").unwrap();
+        assert_eq!(disk.condense_blocks().to_string(), "00992111777.44.333....5555.6666.....8888..");
+
+        // File 1 fits in the leading gap, file 0 would only fit to its right
+        let mut disk = Disk::new();
+        disk.push_gap(2).push_file(3).push_gap(4).push_file(2);
+        let (condensed, moves) = disk.condense_blocks_logged();
+        assert_eq!(condensed.to_string(), "11000......");
+        assert_eq!(moves, [Move { id: 1, from: 9, to: 0, size: 2 }]);
+    }
+
+    /// Tests fragmented compaction fills a gap at the very start of the disk
+    #[test]
+    fn test_leading_gap_compaction() {
+        let mut disk = Disk::new();
+        disk.push_gap(2).push_file(3).push_gap(1).push_file(1);
+        assert_eq!(disk.condense_blocks().to_string(), "1.000..");
+        let (condensed, moves) = disk.condense_logged();
+        assert_eq!(condensed.to_string(), "1000...");
+        assert_eq!(moves[0], Move { id: 1, from: 6, to: 0, size: 1 });
+    }
 
-	println!("Part 2 Solution on Example: {:#?}", part2_solution(example));
-	println!("Part 2 Solution on Input: {:#?}", part2_solution(input));
 }